@@ -1,11 +1,13 @@
 //! HTTP client for communicating with the AI Pulse API server
 
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Response};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::time::Duration;
 
 /// API client for the AI Pulse local server
+#[derive(Clone)]
 pub struct ApiClient {
     client: Client,
     base_url: String,
@@ -61,6 +63,21 @@ impl ApiClient {
         }
     }
 
+    /// Copy of this client with a different request timeout (e.g. for
+    /// requests that derive keys or carry a lot of data)
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            base_url: self.base_url.clone(),
+            token: self.token.clone(),
+        }
+    }
+
     /// Build headers for requests
     fn headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
@@ -130,6 +147,70 @@ impl ApiClient {
             .map_err(|e| ApiError::ParseError(e.to_string()))
     }
 
+    /// Make a POST request with a JSON body
+    pub fn post_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ApiError> {
+        let url = format!("{}{}", self.base_url, path);
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.headers())
+            .json(body)
+            .send()
+            .map_err(|e| ApiError::ConnectionFailed(e.to_string()))?;
+
+        let status = response.status();
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(ApiError::Unauthorized);
+        }
+
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(ApiError::ServerError(status.as_u16(), body));
+        }
+
+        response
+            .json()
+            .map_err(|e| ApiError::ParseError(e.to_string()))
+    }
+
+    /// Open a long-lived GET request (e.g. the `/events` stream)
+    ///
+    /// The response body is read incrementally, so no overall timeout applies.
+    pub fn stream(&self, path: &str) -> Result<Response, ApiError> {
+        let url = format!("{}{}", self.base_url, path);
+
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(None)
+            .build()
+            .map_err(|e| ApiError::ConnectionFailed(e.to_string()))?;
+
+        let response = client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .map_err(|e| ApiError::ConnectionFailed(e.to_string()))?;
+
+        let status = response.status();
+
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(ApiError::Unauthorized);
+        }
+
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(ApiError::ServerError(status.as_u16(), body));
+        }
+
+        Ok(response)
+    }
+
     /// Check if the server is healthy
     #[allow(dead_code)]
     pub fn health(&self) -> Result<HealthResponse, ApiError> {
@@ -162,6 +243,18 @@ pub struct AccountStatus {
     pub limits: Vec<UsageLimit>,
     pub last_updated: String,
     pub session_valid: bool,
+    #[serde(default)]
+    pub forecast: Vec<LimitForecast>,
+}
+
+/// Burn rate and projected time-to-limit for a usage limit
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitForecast {
+    pub limit_id: String,
+    pub burn_rate_per_hour: Option<f64>,
+    pub hits_limit_at: Option<String>,
+    pub hits_before_reset: bool,
 }
 
 /// Usage limit
@@ -182,6 +275,10 @@ pub struct UsageLimit {
 pub struct HistoryResponse {
     pub entries: Vec<HistoryEntry>,
     pub total: usize,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
 }
 
 /// History entry
@@ -205,6 +302,37 @@ pub struct LimitSnapshot {
     pub resets_at: String,
 }
 
+/// Accounts list response
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsResponse {
+    pub accounts: Vec<AccountInfo>,
+}
+
+/// Account info (without credentials)
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+}
+
+/// Counter increment request
+#[derive(Debug, serde::Serialize)]
+pub struct IncrementRequest {
+    pub amount: u32,
+}
+
+/// Usage data for one account (returned after a counter increment)
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageData {
+    pub account_id: String,
+    pub account_name: String,
+    pub limits: Vec<UsageLimit>,
+}
+
 /// Refresh response
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -212,3 +340,52 @@ pub struct RefreshResponse {
     pub success: bool,
     pub message: String,
 }
+
+/// Account export request
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRequest {
+    pub passphrase: String,
+    pub include_history: bool,
+}
+
+/// Account export response
+#[derive(Debug, serde::Deserialize)]
+pub struct ExportResponse {
+    /// Encrypted bundle
+    pub bundle: String,
+}
+
+/// What to do with a bundled account whose ID already exists
+#[derive(Debug, Clone, Copy, serde::Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflict {
+    /// Keep the existing account
+    Skip,
+    /// Import the bundled account next to it under a new ID
+    Rename,
+    /// Replace the existing account
+    Overwrite,
+}
+
+/// Account import request
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRequest {
+    pub bundle: String,
+    pub passphrase: String,
+    pub on_conflict: ImportConflict,
+    pub import_settings: bool,
+}
+
+/// Account import result
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub renamed: Vec<String>,
+    pub skipped: Vec<String>,
+    pub settings_imported: bool,
+    pub history_entries: usize,
+}
@@ -1,6 +1,6 @@
 //! History command - show usage history
 
-use crate::client::{ApiClient, HistoryResponse};
+use crate::client::{ApiClient, HistoryEntry, HistoryResponse};
 use crate::output::print_header;
 use chrono::{DateTime, Duration, Utc};
 use colored::Colorize;
@@ -10,18 +10,19 @@ pub fn run(
     client: &ApiClient,
     days: u32,
     limit: Option<usize>,
+    all: bool,
+    page_size: usize,
     json: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Calculate start date
     let start_date = Utc::now() - Duration::days(days as i64);
     let start_param = start_date.to_rfc3339();
 
-    // Build query parameters
-    let mut path = format!("/history?startDate={}", urlencoding_encode(&start_param));
-    if let Some(l) = limit {
-        path.push_str(&format!("&limit={}", l));
+    if all {
+        return run_all(client, days, &start_param, page_size, json);
     }
 
+    let path = history_path(&start_param, limit, None);
     let response: HistoryResponse = client.get(&path)?;
 
     if json {
@@ -30,29 +31,122 @@ pub fn run(
     }
 
     if response.entries.is_empty() {
-        println!("{}", "No history entries found".yellow());
-        println!("Usage data will be recorded as you use AI Pulse.");
+        print_empty();
         return Ok(());
     }
 
+    print_history_header(days);
+
+    let mut current_account: Option<String> = None;
+    print_entries(&response.entries, &mut current_account);
+
+    println!();
+    if response.has_more {
+        println!(
+            "{} {} of {} entries (use --all to fetch every page)",
+            "Showing:".dimmed(),
+            response.entries.len(),
+            response.total
+        );
+    } else {
+        println!("{} {} entries", "Total:".dimmed(), response.total);
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Stream every page of history by following the server's cursor
+///
+/// With `--json`, each entry is printed as one JSON object per line so the
+/// output can be piped without buffering the whole history in memory.
+fn run_all(
+    client: &ApiClient,
+    days: u32,
+    start_param: &str,
+    page_size: usize,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut cursor: Option<String> = None;
+    let mut current_account: Option<String> = None;
+    let mut printed = 0;
+    let mut total;
+
+    loop {
+        let path = history_path(start_param, Some(page_size.max(1)), cursor.as_deref());
+        let response: HistoryResponse = client.get(&path)?;
+        total = response.total;
+
+        if json {
+            for entry in &response.entries {
+                println!("{}", serde_json::to_string(entry)?);
+            }
+        } else if !response.entries.is_empty() {
+            if printed == 0 {
+                print_history_header(days);
+            }
+            print_entries(&response.entries, &mut current_account);
+        }
+        printed += response.entries.len();
+
+        match response.next_cursor {
+            Some(next) if response.has_more => cursor = Some(next),
+            _ => break,
+        }
+    }
+
+    if json {
+        return Ok(());
+    }
+
+    if printed == 0 {
+        print_empty();
+        return Ok(());
+    }
+
+    println!();
+    println!("{} {} entries", "Total:".dimmed(), total);
+    println!();
+
+    Ok(())
+}
+
+/// Build the /history request path
+fn history_path(start_param: &str, limit: Option<usize>, cursor: Option<&str>) -> String {
+    let mut path = format!("/history?startDate={}", urlencoding_encode(start_param));
+    if let Some(l) = limit {
+        path.push_str(&format!("&limit={}", l));
+    }
+    if let Some(c) = cursor {
+        path.push_str(&format!("&cursor={}", urlencoding_encode(c)));
+    }
+    path
+}
+
+fn print_empty() {
+    println!("{}", "No history entries found".yellow());
+    println!("Usage data will be recorded as you use AI Pulse.");
+}
+
+fn print_history_header(days: u32) {
     print_header(&format!(
         "Usage History (last {} day{})",
         days,
         if days == 1 { "" } else { "s" }
     ));
     println!();
+}
 
-    // Group entries by account
-    let mut current_account: Option<String> = None;
-
-    for entry in &response.entries {
+/// Print entries, grouped by account
+fn print_entries(entries: &[HistoryEntry], current_account: &mut Option<String>) {
+    for entry in entries {
         // Print account header if changed
         if current_account.as_ref() != Some(&entry.account_id) {
             if current_account.is_some() {
                 println!();
             }
             println!("{} ({})", entry.account_name.bold(), entry.provider.dimmed());
-            current_account = Some(entry.account_id.clone());
+            *current_account = Some(entry.account_id.clone());
         }
 
         // Parse and format timestamp
@@ -73,20 +167,10 @@ pub fn run(
             limits_str.join(", ")
         );
     }
-
-    println!();
-    println!(
-        "{} {} entries",
-        "Total:".dimmed(),
-        response.total
-    );
-    println!();
-
-    Ok(())
 }
 
 /// Simple URL encoding for query parameters
-fn urlencoding_encode(s: &str) -> String {
+pub(crate) fn urlencoding_encode(s: &str) -> String {
     s.chars()
         .map(|c| match c {
             'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
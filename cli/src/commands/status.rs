@@ -94,6 +94,20 @@ pub fn run(client: &ApiClient, json: bool, account_filter: Option<&str>) -> Resu
                 pct,
                 reset.dimmed()
             );
+
+            // Warn when the current pace runs out before the window resets
+            let forecast = account
+                .forecast
+                .iter()
+                .find(|f| f.limit_id == limit.id && f.hits_before_reset);
+            if let Some(hits_at) = forecast.and_then(|f| f.hits_limit_at.as_deref()) {
+                println!(
+                    "  {:<16} {} At current pace, hits 100% in {}",
+                    "",
+                    "↳".yellow(),
+                    format_time_until(hits_at)
+                );
+            }
         }
 
         if account.limits.is_empty() {
@@ -0,0 +1,135 @@
+//! Tick command - log uses for a manual counter account
+
+use crate::client::{AccountInfo, AccountsResponse, ApiClient, IncrementRequest, UsageData};
+use crate::output::{format_percentage, format_time_until, progress_bar};
+use colored::Colorize;
+
+/// Provider ID of manual counter accounts
+const COUNTER_PROVIDER: &str = "counter";
+
+/// Run the tick command
+pub fn run(
+    client: &ApiClient,
+    account_filter: Option<&str>,
+    amount: u32,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let response: AccountsResponse = client.get("/accounts")?;
+    let counters: Vec<AccountInfo> = response
+        .accounts
+        .into_iter()
+        .filter(|a| a.provider == COUNTER_PROVIDER)
+        .collect();
+    let account = find_counter(&counters, account_filter)?;
+
+    let usage: UsageData = client.post_json(
+        &format!("/counters/{}/increment", account.id),
+        &IncrementRequest { amount },
+    )?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&usage)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} Logged {} use{} for {}",
+        "✓".green(),
+        amount,
+        if amount == 1 { "" } else { "s" },
+        usage.account_name.bold()
+    );
+    for limit in &usage.limits {
+        println!(
+            "  {} {} {}  {}",
+            progress_bar(limit.utilization, 20),
+            format_percentage(limit.utilization),
+            limit.label,
+            format!("resets in {}", format_time_until(&limit.resets_at)).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Pick the counter account to tick
+///
+/// Matches an exact ID or name first, then a unique partial name. Without a
+/// filter, the only counter account is used.
+fn find_counter<'a>(
+    counters: &'a [AccountInfo],
+    filter: Option<&str>,
+) -> Result<&'a AccountInfo, String> {
+    let Some(filter) = filter else {
+        return match counters {
+            [] => Err(
+                "No counter accounts. Add a Manual Counter account in AI Pulse first.".to_string(),
+            ),
+            [only] => Ok(only),
+            _ => Err(format!(
+                "Several counter accounts, pick one: {}",
+                names(counters.iter())
+            )),
+        };
+    };
+
+    let filter_lower = filter.to_lowercase();
+    if let Some(exact) = counters
+        .iter()
+        .find(|a| a.id == filter || a.name.to_lowercase() == filter_lower)
+    {
+        return Ok(exact);
+    }
+
+    let partial: Vec<_> = counters
+        .iter()
+        .filter(|a| a.name.to_lowercase().contains(&filter_lower))
+        .collect();
+    match partial.as_slice() {
+        [only] => Ok(only),
+        [] => Err(format!("No counter account matching '{}'", filter)),
+        _ => Err(format!(
+            "'{}' matches several counter accounts: {}",
+            filter,
+            names(partial.into_iter())
+        )),
+    }
+}
+
+fn names<'a>(accounts: impl Iterator<Item = &'a AccountInfo>) -> String {
+    accounts
+        .map(|a| a.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(id: &str, name: &str) -> AccountInfo {
+        AccountInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            provider: COUNTER_PROVIDER.to_string(),
+        }
+    }
+
+    #[test]
+    fn find_counter_defaults_to_only_account() {
+        let counters = vec![counter("a", "ChatGPT")];
+        assert_eq!(find_counter(&counters, None).unwrap().id, "a");
+        assert!(find_counter(&[], None).is_err());
+    }
+
+    #[test]
+    fn find_counter_prefers_exact_then_unique_partial() {
+        let counters = vec![counter("a", "ChatGPT"), counter("b", "ChatGPT Team")];
+        assert!(find_counter(&counters, None).is_err());
+        assert_eq!(find_counter(&counters, Some("chatgpt")).unwrap().id, "a");
+        assert_eq!(find_counter(&counters, Some("team")).unwrap().id, "b");
+        assert_eq!(find_counter(&counters, Some("b")).unwrap().id, "b");
+        assert!(find_counter(&counters, Some("chat")).is_err());
+        assert!(find_counter(&counters, Some("gemini")).is_err());
+    }
+}
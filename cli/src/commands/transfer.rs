@@ -0,0 +1,184 @@
+//! Export/import commands - move accounts to another machine
+
+use crate::client::{
+    ApiClient, ExportRequest, ExportResponse, ImportConflict, ImportRequest, ImportSummary,
+};
+use colored::Colorize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// Environment variable read instead of prompting for the passphrase
+const PASSPHRASE_ENV: &str = "AI_PULSE_PASSPHRASE";
+
+/// Deriving the bundle key and moving history can outlast the default timeout
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Export accounts, settings and optionally history to an encrypted file
+pub fn export(
+    client: &ApiClient,
+    file: &Path,
+    include_history: bool,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let passphrase = passphrase(true)?;
+
+    let response: ExportResponse = client.with_timeout(TRANSFER_TIMEOUT).post_json(
+        "/accounts/export",
+        &ExportRequest {
+            passphrase,
+            include_history,
+        },
+    )?;
+
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if force {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut out = options.open(file).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AlreadyExists {
+            format!(
+                "{} already exists (use --force to replace it)",
+                file.display()
+            )
+        } else {
+            format!("Failed to write {}: {}", file.display(), e)
+        }
+    })?;
+    out.write_all(response.bundle.as_bytes())?;
+
+    println!(
+        "{} Exported accounts to {}",
+        "✓".green(),
+        file.display().to_string().bold()
+    );
+    println!(
+        "  {}",
+        "Import it on the other machine with `ai-pulse import` and the same passphrase.".dimmed()
+    );
+    Ok(())
+}
+
+/// Import accounts from a file created by `export`
+pub fn import(
+    client: &ApiClient,
+    file: &Path,
+    on_conflict: ImportConflict,
+    import_settings: bool,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bundle = std::fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let passphrase = passphrase(false)?;
+
+    let summary: ImportSummary = client.with_timeout(TRANSFER_TIMEOUT).post_json(
+        "/accounts/import",
+        &ImportRequest {
+            bundle,
+            passphrase,
+            on_conflict,
+            import_settings,
+        },
+    )?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} Imported {}",
+        "✓".green(),
+        file.display().to_string().bold()
+    );
+    for line in summary_lines(&summary) {
+        println!("  {}", line);
+    }
+    Ok(())
+}
+
+/// Read the passphrase from the environment or prompt for it (twice when
+/// `confirm` is set)
+fn passphrase(confirm: bool) -> Result<String, Box<dyn std::error::Error>> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+
+    let passphrase = rpassword::prompt_password("Passphrase: ")?;
+    if confirm && rpassword::prompt_password("Repeat passphrase: ")? != passphrase {
+        return Err("Passphrases don't match".into());
+    }
+    Ok(passphrase)
+}
+
+/// One line per kind of outcome that happened
+fn summary_lines(summary: &ImportSummary) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (label, names) in [
+        ("Added", &summary.added),
+        ("Overwritten", &summary.overwritten),
+        ("Imported as", &summary.renamed),
+        ("Skipped (already present)", &summary.skipped),
+    ] {
+        if !names.is_empty() {
+            lines.push(format!("{}: {}", label, names.join(", ")));
+        }
+    }
+    if summary.settings_imported {
+        lines.push("Settings replaced".to_string());
+    }
+    if summary.history_entries > 0 {
+        lines.push(format!("{} history entries added", summary.history_entries));
+    }
+    if lines.is_empty() {
+        lines.push("Nothing to import".to_string());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> ImportSummary {
+        ImportSummary {
+            added: Vec::new(),
+            overwritten: Vec::new(),
+            renamed: Vec::new(),
+            skipped: Vec::new(),
+            settings_imported: false,
+            history_entries: 0,
+        }
+    }
+
+    #[test]
+    fn summary_lines_list_what_happened() {
+        let mut imported = summary();
+        imported.added = vec!["Personal".to_string(), "Work".to_string()];
+        imported.skipped = vec!["Team".to_string()];
+        imported.history_entries = 12;
+
+        assert_eq!(
+            summary_lines(&imported),
+            vec![
+                "Added: Personal, Work",
+                "Skipped (already present): Team",
+                "12 history entries added",
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_lines_without_changes() {
+        assert_eq!(summary_lines(&summary()), vec!["Nothing to import"]);
+    }
+}
@@ -0,0 +1,453 @@
+//! Watch command - live usage dashboard that redraws in place
+
+use crate::client::{
+    AccountStatus, ApiClient, ApiError, HistoryResponse, RefreshResponse, StatusResponse,
+};
+use crate::commands::history::urlencoding_encode;
+use crate::output::{format_countdown, format_percentage, progress_bar, sparkline};
+use chrono::{DateTime, Duration as ChronoDuration, Local, SecondsFormat, Utc};
+use colored::Colorize;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute, queue,
+    terminal::{self, Clear, ClearType},
+};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How far back the sparklines reach
+const SPARKLINE_HOURS: i64 = 24;
+/// Characters per sparkline
+const SPARKLINE_WIDTH: usize = 24;
+/// Delay before reconnecting to a dropped event stream
+const STREAM_RETRY: Duration = Duration::from_secs(5);
+/// How long to wait for a key press before redrawing the countdowns
+const TICK: Duration = Duration::from_millis(250);
+/// How long to gather pushed events before reloading, so a burst (one event
+/// per account each refresh) costs a single reload
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Messages from the event stream thread
+enum StreamMessage {
+    Connected,
+    /// The app pushed a usage, session or scheduler event
+    Update,
+    Disconnected,
+    /// The app has no event stream (older version); keep polling
+    Unavailable,
+}
+
+/// Everything the dashboard draws
+struct Dashboard {
+    status: Option<StatusResponse>,
+    /// Utilization samples per (account ID, limit ID), oldest first
+    history: HashMap<(String, String), Vec<f64>>,
+    updated_at: Option<DateTime<Local>>,
+    error: Option<String>,
+    message: Option<String>,
+    live: bool,
+}
+
+/// Restores the terminal when the dashboard exits (including on error)
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(
+            std::io::stdout(),
+            terminal::EnterAlternateScreen,
+            cursor::Hide
+        )?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(
+            std::io::stdout(),
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Run the watch command
+pub fn run(
+    client: &ApiClient,
+    interval: u64,
+    account_filter: Option<&str>,
+    no_stream: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let interval = Duration::from_secs(interval.max(1));
+
+    // Fail fast (before taking over the terminal) if the app is unreachable
+    let mut dashboard = Dashboard {
+        status: None,
+        history: HashMap::new(),
+        updated_at: None,
+        error: None,
+        message: None,
+        live: false,
+    };
+    dashboard.reload(client)?;
+
+    let (tx, rx) = mpsc::channel();
+    if !no_stream {
+        let stream_client = client.clone();
+        thread::spawn(move || follow_event_stream(stream_client, tx));
+    }
+
+    let _guard = TerminalGuard::enter()?;
+    watch_loop(client, &mut dashboard, &rx, interval, account_filter)
+}
+
+fn watch_loop(
+    client: &ApiClient,
+    dashboard: &mut Dashboard,
+    rx: &Receiver<StreamMessage>,
+    interval: Duration,
+    account_filter: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut last_fetch = Instant::now();
+    let mut last_draw: Option<Instant> = None;
+    // When the first event of a not yet reloaded burst arrived
+    let mut pending: Option<Instant> = None;
+
+    loop {
+        // Events pushed by the app trigger a reload once the burst settles
+        while let Ok(message) = rx.try_recv() {
+            match message {
+                StreamMessage::Connected => dashboard.live = true,
+                StreamMessage::Update => {
+                    pending.get_or_insert_with(Instant::now);
+                }
+                StreamMessage::Disconnected | StreamMessage::Unavailable => dashboard.live = false,
+            }
+        }
+
+        if pending.is_some_and(|t| t.elapsed() >= DEBOUNCE) || last_fetch.elapsed() >= interval {
+            dashboard.reload_or_record(client);
+            last_fetch = Instant::now();
+            last_draw = None;
+            pending = None;
+        }
+
+        // Redraw every second so the countdowns tick
+        if last_draw.is_none_or(|t| t.elapsed() >= Duration::from_secs(1)) {
+            draw(dashboard, interval, account_filter)?;
+            last_draw = Some(Instant::now());
+        }
+
+        // Wake up in time to reload a pending burst
+        let timeout = pending.map_or(TICK, |t| DEBOUNCE.saturating_sub(t.elapsed()).min(TICK));
+        if !event::poll(timeout)? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(())
+                }
+                KeyCode::Char('r') => {
+                    dashboard.message = Some(match client.post::<RefreshResponse>("/refresh") {
+                        Ok(response) => response.message,
+                        Err(e) => first_line(&e.to_string()),
+                    });
+                    // Without a stream nothing tells us when the refresh lands
+                    if !dashboard.live {
+                        dashboard.reload_or_record(client);
+                        last_fetch = Instant::now();
+                    }
+                    last_draw = None;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Dashboard {
+    /// Fetch status and recent history
+    fn reload(&mut self, client: &ApiClient) -> Result<(), ApiError> {
+        let status: StatusResponse = client.get("/status")?;
+
+        let since = (Utc::now() - ChronoDuration::hours(SPARKLINE_HOURS))
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        let mut history = HashMap::new();
+        for account in &status.accounts {
+            let path = format!(
+                "/history?accountId={}&startDate={}&limit=1000",
+                urlencoding_encode(&account.id),
+                urlencoding_encode(&since)
+            );
+            // Sparklines are optional; keep the last ones if history fails
+            let Ok(response) = client.get::<HistoryResponse>(&path) else {
+                continue;
+            };
+            // Entries come newest first
+            for entry in response.entries.iter().rev() {
+                for limit in &entry.limits {
+                    history
+                        .entry((account.id.clone(), limit.id.clone()))
+                        .or_insert_with(Vec::new)
+                        .push(limit.utilization);
+                }
+            }
+        }
+
+        self.history.extend(history);
+        self.status = Some(status);
+        self.updated_at = Some(Local::now());
+        self.error = None;
+        Ok(())
+    }
+
+    /// Reload, keeping the last good data on screen if it fails
+    fn reload_or_record(&mut self, client: &ApiClient) {
+        if let Err(e) = self.reload(client) {
+            self.error = Some(first_line(&e.to_string()));
+        }
+    }
+}
+
+/// Redraw the whole screen
+fn draw(
+    dashboard: &Dashboard,
+    interval: Duration,
+    account_filter: Option<&str>,
+) -> std::io::Result<()> {
+    let mut out = std::io::stdout();
+    queue!(out, cursor::MoveTo(0, 0), Clear(ClearType::All))?;
+
+    // Raw mode needs explicit carriage returns
+    for line in render(dashboard, interval, account_filter) {
+        write!(out, "{}\r\n", line)?;
+    }
+    out.flush()
+}
+
+fn render(dashboard: &Dashboard, interval: Duration, account_filter: Option<&str>) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let mode = if dashboard.live {
+        "live".green().to_string()
+    } else {
+        format!("polling every {}s", interval.as_secs())
+            .yellow()
+            .to_string()
+    };
+    let updated = dashboard
+        .updated_at
+        .map(|t| t.format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "never".to_string());
+    lines.push(format!(
+        "{}  {}  {}",
+        "AI Pulse Usage".bold(),
+        mode,
+        format!("updated {}", updated).dimmed()
+    ));
+    lines.push("━".repeat(14).dimmed().to_string());
+
+    let accounts: Vec<&AccountStatus> = dashboard
+        .status
+        .as_ref()
+        .map(|s| {
+            s.accounts
+                .iter()
+                .filter(|a| matches_filter(a, account_filter))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if accounts.is_empty() {
+        lines.push(String::new());
+        lines.push("No accounts to show".yellow().to_string());
+    }
+
+    for account in accounts {
+        lines.push(String::new());
+
+        let session_status = if account.session_valid {
+            "●".green()
+        } else {
+            "●".red()
+        };
+        lines.push(format!(
+            "{} {} ({})",
+            session_status,
+            account.name.bold(),
+            account.provider.dimmed()
+        ));
+
+        if !account.session_valid {
+            lines.push(format!(
+                "  {} Session expired - update credentials in AI Pulse",
+                "⚠".yellow()
+            ));
+        }
+
+        for limit in &account.limits {
+            let samples = dashboard
+                .history
+                .get(&(account.id.clone(), limit.id.clone()))
+                .map(Vec::as_slice)
+                .unwrap_or(&[]);
+            let countdown = DateTime::parse_from_rfc3339(&limit.resets_at)
+                .map(|t| {
+                    format_countdown(
+                        t.with_timezone(&Utc)
+                            .signed_duration_since(Utc::now())
+                            .num_seconds(),
+                    )
+                })
+                .unwrap_or_else(|_| "unknown".to_string());
+
+            lines.push(format!(
+                "  {:<16} {}  {}  {}  Resets in {}",
+                limit.label,
+                progress_bar(limit.utilization, 10),
+                format_percentage(limit.utilization),
+                // Pad before colouring so escape codes don't throw off alignment
+                format!(
+                    "{:<width$}",
+                    sparkline(samples, SPARKLINE_WIDTH),
+                    width = SPARKLINE_WIDTH
+                )
+                .dimmed(),
+                countdown
+            ));
+        }
+
+        if account.limits.is_empty() {
+            lines.push(format!("  {}", "No usage data available".dimmed()));
+        }
+    }
+
+    lines.push(String::new());
+    if let Some(error) = &dashboard.error {
+        lines.push(format!("{} {}", "⚠".yellow(), error));
+    }
+    if let Some(message) = &dashboard.message {
+        lines.push(message.dimmed().to_string());
+    }
+    lines.push(
+        format!("{} refresh  {} quit", "r".bold(), "q".bold())
+            .dimmed()
+            .to_string(),
+    );
+
+    lines
+}
+
+fn matches_filter(account: &AccountStatus, filter: Option<&str>) -> bool {
+    filter.is_none_or(|f| {
+        let f = f.to_lowercase();
+        account.name.to_lowercase().contains(&f) || account.id.to_lowercase().contains(&f)
+    })
+}
+
+/// Errors can span several lines of advice; the dashboard only has room for one
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().to_string()
+}
+
+/// Follow `/events`, reconnecting when the stream drops
+fn follow_event_stream(client: ApiClient, tx: Sender<StreamMessage>) {
+    loop {
+        let response = match client.stream("/events") {
+            Ok(response) => response,
+            // Older app versions have no event stream
+            Err(ApiError::ServerError(404, _)) => {
+                let _ = tx.send(StreamMessage::Unavailable);
+                return;
+            }
+            Err(_) => {
+                if tx.send(StreamMessage::Disconnected).is_err() {
+                    return;
+                }
+                thread::sleep(STREAM_RETRY);
+                continue;
+            }
+        };
+
+        if tx.send(StreamMessage::Connected).is_err() {
+            return;
+        }
+
+        let mut parser = SseParser::default();
+        for line in BufReader::new(response).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if parser.feed(&line).is_some() && tx.send(StreamMessage::Update).is_err() {
+                // The dashboard has exited
+                return;
+            }
+        }
+
+        if tx.send(StreamMessage::Disconnected).is_err() {
+            return;
+        }
+        thread::sleep(STREAM_RETRY);
+    }
+}
+
+/// Minimal Server-Sent Events parser that reports the name of each event
+#[derive(Default)]
+struct SseParser {
+    event: Option<String>,
+    has_data: bool,
+}
+
+impl SseParser {
+    /// Feed one line; returns the event name when a blank line completes an event
+    fn feed(&mut self, line: &str) -> Option<String> {
+        if line.is_empty() {
+            let has_data = std::mem::take(&mut self.has_data);
+            let event = self.event.take();
+            return has_data.then(|| event.unwrap_or_else(|| "message".to_string()));
+        }
+
+        if let Some(name) = line.strip_prefix("event:") {
+            self.event = Some(name.trim().to_string());
+        } else if line.starts_with("data:") {
+            self.has_data = true;
+        }
+        // Comments (": keep-alive") and other fields are ignored
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sse_parser_reports_named_events() {
+        let mut parser = SseParser::default();
+        assert_eq!(parser.feed("event: usage-update"), None);
+        assert_eq!(parser.feed("data: {\"accountId\":\"a\"}"), None);
+        assert_eq!(parser.feed(""), Some("usage-update".to_string()));
+    }
+
+    #[test]
+    fn sse_parser_ignores_keep_alive_comments() {
+        let mut parser = SseParser::default();
+        assert_eq!(parser.feed(":"), None);
+        assert_eq!(parser.feed(""), None);
+        assert_eq!(parser.feed("data: {}"), None);
+        assert_eq!(parser.feed(""), Some("message".to_string()));
+    }
+}
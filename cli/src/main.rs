@@ -12,10 +12,14 @@ mod commands {
     pub mod history;
     pub mod refresh;
     pub mod status;
+    pub mod tick;
+    pub mod transfer;
+    pub mod watch;
 }
 
 use clap::{Parser, Subcommand};
 use colored::Colorize;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "ai-pulse")]
@@ -50,6 +54,14 @@ enum Commands {
         #[arg(short, long)]
         limit: Option<usize>,
 
+        /// Fetch every page instead of just the first
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Entries per request when fetching all pages
+        #[arg(long, default_value = "500")]
+        page_size: usize,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -58,6 +70,67 @@ enum Commands {
     /// Trigger an immediate usage refresh
     Refresh,
 
+    /// Log uses for a manual counter account
+    Tick {
+        /// Counter account (by name or ID); optional with a single counter
+        account: Option<String>,
+
+        /// Number of uses to log
+        #[arg(short = 'n', long, default_value = "1")]
+        amount: u32,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Live dashboard that updates in place (press r to refresh, q to quit)
+    Watch {
+        /// Seconds between reloads (events pushed by the app also trigger one)
+        #[arg(short, long, default_value = "30")]
+        interval: u64,
+
+        /// Show a specific account (by name or ID)
+        #[arg(short, long)]
+        account: Option<String>,
+
+        /// Poll on the interval instead of following the app's event stream
+        #[arg(long)]
+        no_stream: bool,
+    },
+
+    /// Export accounts, settings and optionally history to an encrypted file
+    Export {
+        /// File to write the bundle to
+        file: PathBuf,
+
+        /// Include the usage history
+        #[arg(long)]
+        include_history: bool,
+
+        /// Replace the file if it exists
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Import accounts from a file created by `export`
+    Import {
+        /// Bundle to import
+        file: PathBuf,
+
+        /// What to do with accounts that already exist (same ID)
+        #[arg(long, value_enum, default_value = "skip")]
+        on_conflict: client::ImportConflict,
+
+        /// Replace the current settings with the exported ones
+        #[arg(long)]
+        settings: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Manage CLI configuration
     Config {
         #[command(subcommand)]
@@ -101,12 +174,37 @@ fn main() {
         Commands::Status { json, account } => {
             commands::status::run(&client, json, account.as_deref())
         }
-        Commands::History { days, limit, json } => {
-            commands::history::run(&client, days, limit, json)
-        }
+        Commands::History {
+            days,
+            limit,
+            all,
+            page_size,
+            json,
+        } => commands::history::run(&client, days, limit, all, page_size, json),
         Commands::Refresh => {
             commands::refresh::run(&client)
         }
+        Commands::Tick {
+            account,
+            amount,
+            json,
+        } => commands::tick::run(&client, account.as_deref(), amount, json),
+        Commands::Watch {
+            interval,
+            account,
+            no_stream,
+        } => commands::watch::run(&client, interval, account.as_deref(), no_stream),
+        Commands::Export {
+            file,
+            include_history,
+            force,
+        } => commands::transfer::export(&client, &file, include_history, force),
+        Commands::Import {
+            file,
+            on_conflict,
+            settings,
+            json,
+        } => commands::transfer::import(&client, &file, on_conflict, settings, json),
         Commands::Config { action } => match action {
             ConfigAction::Show => {
                 commands::config_cmd::show(&config)
@@ -41,6 +41,26 @@ pub fn format_time_until(iso_timestamp: &str) -> String {
     }
 }
 
+/// Format a countdown with seconds so it visibly ticks (e.g., "1h 05m 09s")
+pub fn format_countdown(seconds: i64) -> String {
+    if seconds < 0 {
+        return "now".to_string();
+    }
+
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+
+    if days > 0 {
+        format!("{}d {}h {:02}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, secs)
+    } else {
+        format!("{}m {:02}s", minutes, secs)
+    }
+}
+
 /// Create a progress bar string
 pub fn progress_bar(percentage: f64, width: usize) -> String {
     let filled = ((percentage / 100.0) * width as f64).round() as usize;
@@ -48,6 +68,29 @@ pub fn progress_bar(percentage: f64, width: usize) -> String {
     format!("{}{}", "█".repeat(filled), "░".repeat(empty))
 }
 
+/// Render utilization samples (0-100, oldest first) as a sparkline
+///
+/// When there are more samples than `width`, each character shows the
+/// highest value in its bucket so short spikes stay visible.
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    let buckets = values.len().min(width);
+    (0..buckets)
+        .map(|i| {
+            let start = i * values.len() / buckets;
+            let end = ((i + 1) * values.len() / buckets).max(start + 1);
+            let peak = values[start..end].iter().cloned().fold(0.0, f64::max);
+            let level = ((peak.clamp(0.0, 100.0) / 100.0) * (BARS.len() - 1) as f64).round();
+            BARS[level as usize]
+        })
+        .collect()
+}
+
 /// Get color based on usage percentage
 pub fn usage_color(percentage: f64) -> colored::Color {
     if percentage >= 90.0 {
@@ -95,6 +138,22 @@ mod tests {
         assert_eq!(progress_bar(100.0, 10), "██████████");
     }
 
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(-5), "now");
+        assert_eq!(format_countdown(9), "0m 09s");
+        assert_eq!(format_countdown(3909), "1h 05m 09s");
+        assert_eq!(format_countdown(90061), "1d 1h 01m");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[], 10), "");
+        assert_eq!(sparkline(&[0.0, 50.0, 100.0], 10), "▁▅█");
+        // Downsampling keeps each bucket's peak
+        assert_eq!(sparkline(&[0.0, 100.0, 0.0, 0.0], 2), "█▁");
+    }
+
     #[test]
     fn test_usage_color() {
         assert_eq!(usage_color(0.0), colored::Color::Green);
@@ -1,18 +1,32 @@
 //! API request handlers
 
+use std::convert::Infallible;
+
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tauri::{Emitter, Manager};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tokio_stream::{Stream, StreamExt};
 
+use super::metrics::{self, AccountMetrics, MetricsSnapshot};
 use super::ApiState;
-use crate::models::{HistoryQuery, UsageData, UsageHistoryEntry, UsageLimit};
+use crate::error::AppError;
+use crate::models::{
+    AccountForecast, CredentialsIssue, HistoryQuery, HistoryResolution, LimitForecast,
+    SchemaRecord, UsageData, UsageHistoryEntry, UsageLimit,
+};
 use crate::providers::ProviderRegistry;
-use crate::services::{CredentialService, HistoryService};
+use crate::services::{
+    CircuitBreaker, CircuitState, CounterService, CredentialService, ForecastService,
+    HistoryService, ImportConflict, ImportSummary, SchedulerService, SchemaService,
+    TransferService,
+};
 
 /// Health check response
 #[derive(Serialize)]
@@ -39,6 +53,10 @@ pub struct AccountStatus {
     pub limits: Vec<UsageLimit>,
     pub last_updated: DateTime<Utc>,
     pub session_valid: bool,
+    /// Circuit breaker state (open while the account is failing)
+    pub circuit: CircuitBreaker,
+    /// Burn rate and projected time-to-limit per limit
+    pub forecast: Vec<LimitForecast>,
 }
 
 /// Account info (without credentials)
@@ -49,6 +67,8 @@ pub struct AccountInfo {
     pub name: String,
     pub provider: String,
     pub created_at: DateTime<Utc>,
+    /// Set when stored credentials couldn't be read and must be entered again
+    pub needs_reentry: Option<CredentialsIssue>,
 }
 
 /// Accounts list response
@@ -68,6 +88,9 @@ pub struct HistoryParams {
     pub end_date: Option<DateTime<Utc>>,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    pub cursor: Option<String>,
+    /// `raw` (default), `hourly` or `daily`
+    pub resolution: Option<HistoryResolution>,
 }
 
 /// History response
@@ -75,7 +98,34 @@ pub struct HistoryParams {
 #[serde(rename_all = "camelCase")]
 pub struct HistoryResponse {
     pub entries: Vec<UsageHistoryEntry>,
+    /// Total number of entries matching the filters (across all pages)
     pub total: usize,
+    /// Cursor for the next page, if there is one
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+/// Forecast query parameters
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastParams {
+    pub account_id: Option<String>,
+}
+
+/// Forecast response
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastResponse {
+    pub timestamp: DateTime<Utc>,
+    pub accounts: Vec<AccountForecast>,
+}
+
+/// Schema diagnostics response
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiagnosticsResponse {
+    pub timestamp: DateTime<Utc>,
+    pub schemas: Vec<SchemaRecord>,
 }
 
 /// Refresh response
@@ -86,6 +136,40 @@ pub struct RefreshResponse {
     pub message: String,
 }
 
+/// Counter increment request body (optional; defaults to one use)
+#[derive(Deserialize)]
+pub struct IncrementRequest {
+    pub amount: Option<u32>,
+}
+
+/// Account export request body
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRequest {
+    pub passphrase: String,
+    #[serde(default)]
+    pub include_history: bool,
+}
+
+/// Account export response
+#[derive(Serialize)]
+pub struct ExportResponse {
+    /// Encrypted bundle, to be written to a file as is
+    pub bundle: String,
+}
+
+/// Account import request body
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRequest {
+    pub bundle: String,
+    pub passphrase: String,
+    #[serde(default)]
+    pub on_conflict: ImportConflict,
+    #[serde(default)]
+    pub import_settings: bool,
+}
+
 /// Error response
 #[derive(Serialize)]
 pub struct ErrorResponse {
@@ -140,7 +224,7 @@ pub async fn accounts(
     // Get accounts for all providers
     let mut all_accounts = Vec::new();
 
-    let registry = ProviderRegistry::default();
+    let registry = ProviderRegistry::for_app(&state.app).unwrap_or_default();
     for provider_id in registry.available_ids() {
         match CredentialService::list_accounts(&state.app, provider_id) {
             Ok(accounts) => {
@@ -150,6 +234,7 @@ pub async fn accounts(
                         name: account.name,
                         provider: account.provider,
                         created_at: account.created_at,
+                        needs_reentry: account.needs_reentry,
                     });
                 }
             }
@@ -176,13 +261,21 @@ pub async fn history(
         end_date: params.end_date,
         limit: params.limit,
         offset: params.offset,
+        cursor: params.cursor,
+        resolution: params.resolution.unwrap_or_default(),
     };
 
     match HistoryService::query(&state.app, &query) {
-        Ok(entries) => {
-            let total = entries.len();
-            Ok(Json(HistoryResponse { entries, total }))
-        }
+        Ok(page) => Ok(Json(HistoryResponse {
+            entries: page.entries,
+            total: page.total,
+            next_cursor: page.next_cursor,
+            has_more: page.has_more,
+        })),
+        Err(AppError::InvalidInput(message)) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: message }),
+        )),
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -192,15 +285,136 @@ pub async fn history(
     }
 }
 
+/// GET /forecast - Burn rate and time-to-limit for each account's limits
+pub async fn forecast(
+    State(state): State<ApiState>,
+    Query(params): Query<ForecastParams>,
+) -> Result<Json<ForecastResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let accounts = get_all_account_statuses(&state)
+        .await?
+        .into_iter()
+        .filter(|a| params.account_id.as_ref().map_or(true, |id| &a.id == id))
+        .map(|a| AccountForecast {
+            account_id: a.id,
+            account_name: a.name,
+            provider: a.provider,
+            as_of: a.last_updated,
+            limits: a.forecast,
+        })
+        .collect();
+
+    Ok(Json(ForecastResponse {
+        timestamp: Utc::now(),
+        accounts,
+    }))
+}
+
+/// GET /events - Stream scheduler events as Server-Sent Events
+///
+/// Each SSE event is named after the frontend event (`usage-update`,
+/// `session-status`, `scheduler-status`, `schema-drift`, `circuit-state`) and
+/// carries the same JSON payload.
+pub async fn events(
+    State(state): State<ApiState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = state.scheduler_state.subscribe_events();
+
+    let stream = BroadcastStream::new(receiver).filter_map(|result| match result {
+        Ok(event) => match Event::default().event(event.name()).json_data(&event) {
+            Ok(sse_event) => Some(Ok(sse_event)),
+            Err(e) => {
+                log::warn!("Failed to serialize {} event: {}", event.name(), e);
+                None
+            }
+        },
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            log::warn!("Event stream client lagged, skipped {} events", skipped);
+            None
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// GET /metrics - Prometheus metrics
+///
+/// Only reports cached usage; scraping never triggers a fetch.
+pub async fn metrics(State(state): State<ApiState>) -> ([(header::HeaderName, &'static str); 1], String) {
+    let scheduler = &state.scheduler_state;
+    let mut accounts = Vec::new();
+
+    let registry = ProviderRegistry::for_app(&state.app).unwrap_or_default();
+    for provider_id in registry.available_ids() {
+        // Labels only need names, so scrapes keep working while the vault is locked
+        let provider_accounts =
+            match CredentialService::list_account_metadata(&state.app, provider_id) {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    log::warn!("Failed to list accounts for {}: {}", provider_id, e);
+                    continue;
+                }
+            };
+
+        for account in provider_accounts {
+            let circuit = scheduler.get_circuit(&account.id).await;
+            let session_errors = scheduler.get_account_error_count(&account.id).await;
+            let limits = scheduler
+                .get_previous_usage(&account.id)
+                .await
+                .map(|usage| usage.limits)
+                .unwrap_or_default();
+
+            accounts.push(AccountMetrics {
+                id: account.id,
+                name: account.name,
+                provider: account.provider,
+                session_valid: circuit.state == CircuitState::Closed && session_errors == 0,
+                session_errors,
+                limits,
+            });
+        }
+    }
+
+    let snapshot = MetricsSnapshot {
+        accounts,
+        scheduler_running: scheduler.is_running(),
+        interval_secs: scheduler.get_interval(),
+        last_fetch_millis: scheduler.get_last_fetch(),
+        fetch_counts: scheduler.fetch_counts(),
+        fetch_attempts: scheduler.fetch_attempts(),
+        now: Utc::now(),
+    };
+
+    ([(header::CONTENT_TYPE, metrics::CONTENT_TYPE)], metrics::render(&snapshot))
+}
+
+/// GET /diagnostics/schema - Current vs expected response shape per provider endpoint
+pub async fn schema_diagnostics(
+    State(state): State<ApiState>,
+) -> Result<Json<SchemaDiagnosticsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    match SchemaService::list(&state.app) {
+        Ok(schemas) => Ok(Json(SchemaDiagnosticsResponse {
+            timestamp: Utc::now(),
+            schemas,
+        })),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to load response schemas: {}", e),
+            }),
+        )),
+    }
+}
+
 /// POST /refresh - Trigger an immediate usage refresh
 pub async fn refresh(
     State(state): State<ApiState>,
 ) -> Result<Json<RefreshResponse>, (StatusCode, Json<ErrorResponse>)> {
-    // Check if we can fetch (rate limiting)
-    if !state.scheduler_state.can_fetch() {
+    // Don't queue another refresh behind one that is still running
+    if state.scheduler_state.is_fetching() {
         return Ok(Json(RefreshResponse {
             success: false,
-            message: "Rate limited. Please wait before refreshing again.".to_string(),
+            message: "A refresh is already in progress.".to_string(),
         }));
     }
 
@@ -216,13 +430,128 @@ pub async fn refresh(
     }))
 }
 
+/// POST /counters/:account_id/increment - Log uses for a counter account
+///
+/// Returns the account's refreshed usage.
+pub async fn increment_counter(
+    State(state): State<ApiState>,
+    Path(account_id): Path<String>,
+    body: Option<Json<IncrementRequest>>,
+) -> Result<Json<UsageData>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+
+    match CredentialService::get_account(&state.app, &account_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Err(error(
+                StatusCode::NOT_FOUND,
+                format!("Account not found: {}", account_id),
+            ))
+        }
+        Err(e) => return Err(error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+
+    let amount = body.and_then(|Json(body)| body.amount).unwrap_or(1);
+    match CounterService::increment(&state.app, &account_id, amount) {
+        Ok(()) => {}
+        Err(AppError::InvalidInput(message)) => {
+            return Err(error(StatusCode::BAD_REQUEST, message))
+        }
+        Err(e) => {
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to update counter: {}", e),
+            ))
+        }
+    }
+
+    SchedulerService::refresh_account(&state.app, &state.scheduler_state, &account_id)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to refresh counter: {}", e),
+            )
+        })
+}
+
+/// POST /accounts/export - Export accounts, settings and optionally history
+/// as a passphrase-encrypted bundle
+///
+/// Only served when an API token is set, since the bundle carries credentials.
+pub async fn export_accounts(
+    State(state): State<ApiState>,
+    Json(body): Json<ExportRequest>,
+) -> Result<Json<ExportResponse>, (StatusCode, Json<ErrorResponse>)> {
+    require_token(&state)?;
+
+    TransferService::export(&state.app, &body.passphrase, body.include_history)
+        .await
+        .map(|bundle| Json(ExportResponse { bundle }))
+        .map_err(transfer_error)
+}
+
+/// POST /accounts/import - Import a bundle created by /accounts/export
+///
+/// Only served when an API token is set, like /accounts/export.
+pub async fn import_accounts(
+    State(state): State<ApiState>,
+    Json(body): Json<ImportRequest>,
+) -> Result<Json<ImportSummary>, (StatusCode, Json<ErrorResponse>)> {
+    require_token(&state)?;
+
+    TransferService::import(
+        &state.app,
+        &body.bundle,
+        &body.passphrase,
+        body.on_conflict,
+        body.import_settings,
+    )
+    .await
+    .map(Json)
+    .map_err(transfer_error)
+}
+
+/// Refuse requests that would expose or replace credentials unless the
+/// server is checking an API token
+///
+/// Only the token the server started with counts: one set since then isn't
+/// enforced until a restart.
+fn require_token(state: &ApiState) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if state.token_enforced {
+        return Ok(());
+    }
+    Err((
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            error: "Set an API token in Settings > Developer and restart AI Pulse to export or import accounts"
+                .to_string(),
+        }),
+    ))
+}
+
+fn transfer_error(e: AppError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match e {
+        AppError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        AppError::VaultLocked => StatusCode::LOCKED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            error: e.to_string(),
+        }),
+    )
+}
+
 /// Helper to get status for all accounts
 async fn get_all_account_statuses(
     state: &ApiState,
 ) -> Result<Vec<AccountStatus>, (StatusCode, Json<ErrorResponse>)> {
     let mut statuses = Vec::new();
 
-    let registry = ProviderRegistry::default();
+    let registry = ProviderRegistry::for_app(&state.app).unwrap_or_default();
     for provider_id in registry.available_ids() {
         let accounts = match CredentialService::list_accounts(&state.app, provider_id) {
             Ok(accounts) => accounts,
@@ -234,7 +563,8 @@ async fn get_all_account_statuses(
 
         for account in accounts {
             // Get session status
-            let session_valid = !state.scheduler_state.is_account_paused(&account.id).await;
+            let circuit = state.scheduler_state.get_circuit(&account.id).await;
+            let session_valid = circuit.state == CircuitState::Closed;
             let error_count = state.scheduler_state.get_account_error_count(&account.id).await;
 
             // Try to get cached usage from scheduler state
@@ -244,13 +574,37 @@ async fn get_all_account_statuses(
                 .await
             {
                 Some(usage) => (usage.limits, usage.timestamp),
-                None => {
-                    // No cached data, try to fetch fresh
-                    match fetch_usage_for_account(state, &account.id).await {
+                // No cached data yet: fetch through the scheduler so host budgets,
+                // retries and rate limits apply, unless the account's circuit is open
+                None if circuit.state == CircuitState::Closed => {
+                    match SchedulerService::refresh_account(
+                        &state.app,
+                        &state.scheduler_state,
+                        &account.id,
+                    )
+                    .await
+                    {
                         Ok(usage) => (usage.limits, usage.timestamp),
-                        Err(_) => (vec![], Utc::now()),
+                        Err(e) => {
+                            log::debug!("Failed to fetch usage for {}: {}", account.id, e);
+                            (vec![], Utc::now())
+                        }
                     }
                 }
+                None => (vec![], Utc::now()),
+            };
+
+            let forecast = match ForecastService::forecast_limits(
+                &state.app,
+                &account.id,
+                &limits,
+                last_updated,
+            ) {
+                Ok(forecast) => forecast,
+                Err(e) => {
+                    log::warn!("Failed to forecast usage for {}: {}", account.id, e);
+                    Vec::new()
+                }
             };
 
             statuses.push(AccountStatus {
@@ -260,6 +614,8 @@ async fn get_all_account_statuses(
                 limits,
                 last_updated,
                 session_valid: session_valid && error_count == 0,
+                circuit,
+                forecast,
             });
         }
     }
@@ -267,31 +623,6 @@ async fn get_all_account_statuses(
     Ok(statuses)
 }
 
-/// Helper to fetch usage for a specific account
-async fn fetch_usage_for_account(
-    state: &ApiState,
-    account_id: &str,
-) -> Result<UsageData, String> {
-    let account = CredentialService::get_account(&state.app, account_id)
-        .map_err(|e| format!("Failed to get account: {}", e))?
-        .ok_or_else(|| format!("Account not found: {}", account_id))?;
-
-    let registry = ProviderRegistry::default();
-    let provider = registry
-        .get(&account.provider)
-        .ok_or_else(|| format!("Provider not found: {}", account.provider))?;
-
-    provider
-        .fetch_usage(&account.credentials)
-        .await
-        .map(|mut data| {
-            data.account_id = account.id.clone();
-            data.account_name = account.name.clone();
-            data
-        })
-        .map_err(|e| format!("Failed to fetch usage: {}", e))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,4 +657,18 @@ mod tests {
         assert!(json.contains("\"success\":true"));
         assert!(json.contains("\"message\":\"Refresh triggered\""));
     }
+
+    #[test]
+    fn import_request_defaults() {
+        let request: ImportRequest =
+            serde_json::from_str(r#"{"bundle": "{}", "passphrase": "correct horse"}"#).unwrap();
+        assert_eq!(request.on_conflict, ImportConflict::Skip);
+        assert!(!request.import_settings);
+
+        let request: ImportRequest = serde_json::from_str(
+            r#"{"bundle": "{}", "passphrase": "correct horse", "onConflict": "rename"}"#,
+        )
+        .unwrap();
+        assert_eq!(request.on_conflict, ImportConflict::Rename);
+    }
 }
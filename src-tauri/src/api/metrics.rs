@@ -0,0 +1,285 @@
+//! Prometheus text exposition for GET /metrics
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+
+use crate::models::UsageLimit;
+
+/// Content type of the Prometheus text format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Everything exported by /metrics, gathered at scrape time
+pub struct MetricsSnapshot {
+    pub accounts: Vec<AccountMetrics>,
+    pub scheduler_running: bool,
+    pub interval_secs: u64,
+    /// Unix millis of the last scheduler fetch (0 = never)
+    pub last_fetch_millis: u64,
+    /// Fetch counts per (provider, outcome)
+    pub fetch_counts: HashMap<(String, String), u64>,
+    /// Requests per provider, counting retries
+    pub fetch_attempts: HashMap<String, u64>,
+    pub now: DateTime<Utc>,
+}
+
+/// Per-account values
+pub struct AccountMetrics {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub session_valid: bool,
+    pub session_errors: u64,
+    /// Latest cached limits (empty until the first fetch)
+    pub limits: Vec<UsageLimit>,
+}
+
+/// Render a snapshot in the Prometheus text format
+pub fn render(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+
+    header(
+        &mut out,
+        "ai_pulse_limit_utilization_ratio",
+        "gauge",
+        "Utilization of a usage limit (0-1, can exceed 1)",
+    );
+    for account in &snapshot.accounts {
+        for limit in &account.limits {
+            sample(
+                &mut out,
+                "ai_pulse_limit_utilization_ratio",
+                &limit_labels(account, limit),
+                limit.utilization / 100.0,
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "ai_pulse_limit_reset_seconds",
+        "gauge",
+        "Seconds until a usage limit resets",
+    );
+    for account in &snapshot.accounts {
+        for limit in &account.limits {
+            let seconds = (limit.resets_at - snapshot.now).num_seconds().max(0);
+            sample(
+                &mut out,
+                "ai_pulse_limit_reset_seconds",
+                &limit_labels(account, limit),
+                seconds as f64,
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "ai_pulse_session_valid",
+        "gauge",
+        "Whether the account's session is valid (1) or expired/paused (0)",
+    );
+    for account in &snapshot.accounts {
+        sample(
+            &mut out,
+            "ai_pulse_session_valid",
+            &account_labels(account),
+            if account.session_valid { 1.0 } else { 0.0 },
+        );
+    }
+
+    header(
+        &mut out,
+        "ai_pulse_session_errors",
+        "gauge",
+        "Consecutive session errors for the account",
+    );
+    for account in &snapshot.accounts {
+        sample(
+            &mut out,
+            "ai_pulse_session_errors",
+            &account_labels(account),
+            account.session_errors as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "ai_pulse_scheduler_running",
+        "gauge",
+        "Whether the background refresh scheduler is running",
+    );
+    sample(
+        &mut out,
+        "ai_pulse_scheduler_running",
+        &[],
+        if snapshot.scheduler_running { 1.0 } else { 0.0 },
+    );
+
+    header(
+        &mut out,
+        "ai_pulse_scheduler_interval_seconds",
+        "gauge",
+        "Current refresh interval",
+    );
+    sample(
+        &mut out,
+        "ai_pulse_scheduler_interval_seconds",
+        &[],
+        snapshot.interval_secs as f64,
+    );
+
+    header(
+        &mut out,
+        "ai_pulse_last_fetch_timestamp_seconds",
+        "gauge",
+        "Unix time of the last scheduled fetch (0 if none yet)",
+    );
+    sample(
+        &mut out,
+        "ai_pulse_last_fetch_timestamp_seconds",
+        &[],
+        snapshot.last_fetch_millis as f64 / 1000.0,
+    );
+
+    header(
+        &mut out,
+        "ai_pulse_fetches_total",
+        "counter",
+        "Usage fetches by provider and outcome (success or the provider error kind)",
+    );
+    let mut counts: Vec<_> = snapshot.fetch_counts.iter().collect();
+    counts.sort();
+    for ((provider, outcome), count) in counts {
+        sample(
+            &mut out,
+            "ai_pulse_fetches_total",
+            &[("provider", provider), ("outcome", outcome)],
+            *count as f64,
+        );
+    }
+
+    header(
+        &mut out,
+        "ai_pulse_fetch_attempts_total",
+        "counter",
+        "Requests made to fetch usage by provider, including retries",
+    );
+    let mut attempts: Vec<_> = snapshot.fetch_attempts.iter().collect();
+    attempts.sort();
+    for (provider, count) in attempts {
+        sample(
+            &mut out,
+            "ai_pulse_fetch_attempts_total",
+            &[("provider", provider)],
+            *count as f64,
+        );
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+fn account_labels(account: &AccountMetrics) -> Vec<(&str, &str)> {
+    vec![
+        ("account_id", &account.id),
+        ("account_name", &account.name),
+        ("provider", &account.provider),
+    ]
+}
+
+fn limit_labels<'a>(account: &'a AccountMetrics, limit: &'a UsageLimit) -> Vec<(&'a str, &'a str)> {
+    let mut labels = account_labels(account);
+    labels.push(("limit_id", &limit.id));
+    labels
+}
+
+/// Escape a label value (backslash, double quote and newline)
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn snapshot() -> MetricsSnapshot {
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        MetricsSnapshot {
+            accounts: vec![AccountMetrics {
+                id: "acc-1".to_string(),
+                name: "Work \"main\"".to_string(),
+                provider: "claude".to_string(),
+                session_valid: true,
+                session_errors: 0,
+                limits: vec![UsageLimit {
+                    id: "five_hour".to_string(),
+                    label: "5-Hour Limit".to_string(),
+                    utilization: 42.5,
+                    resets_at: now + Duration::minutes(90),
+                    category: None,
+                }],
+            }],
+            scheduler_running: true,
+            interval_secs: 300,
+            last_fetch_millis: 1_736_942_400_000,
+            fetch_counts: HashMap::from([
+                (("claude".to_string(), "success".to_string()), 7),
+                (("claude".to_string(), "session_expired".to_string()), 2),
+            ]),
+            fetch_attempts: HashMap::from([("claude".to_string(), 11)]),
+            now,
+        }
+    }
+
+    #[test]
+    fn renders_limit_gauges() {
+        let text = render(&snapshot());
+        assert!(text.contains(
+            "ai_pulse_limit_utilization_ratio{account_id=\"acc-1\",account_name=\"Work \\\"main\\\"\",provider=\"claude\",limit_id=\"five_hour\"} 0.425\n"
+        ));
+        assert!(text.contains("limit_id=\"five_hour\"} 5400\n"));
+        assert!(text.contains("# TYPE ai_pulse_limit_reset_seconds gauge\n"));
+    }
+
+    #[test]
+    fn renders_scheduler_and_fetch_counters() {
+        let text = render(&snapshot());
+        assert!(text.contains("ai_pulse_scheduler_interval_seconds 300\n"));
+        assert!(text.contains("ai_pulse_last_fetch_timestamp_seconds 1736942400\n"));
+        assert!(text.contains("# TYPE ai_pulse_fetches_total counter\n"));
+        assert!(text.contains(
+            "ai_pulse_fetches_total{provider=\"claude\",outcome=\"session_expired\"} 2\n"
+        ));
+        assert!(
+            text.contains("ai_pulse_fetches_total{provider=\"claude\",outcome=\"success\"} 7\n")
+        );
+        assert!(text.contains("ai_pulse_fetch_attempts_total{provider=\"claude\"} 11\n"));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape_label("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+}
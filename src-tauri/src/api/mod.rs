@@ -4,6 +4,7 @@
 //! external tools like the CLI and VS Code extension to query usage data.
 
 mod handlers;
+mod metrics;
 mod routes;
 
 use std::net::SocketAddr;
@@ -19,6 +20,9 @@ use crate::services::SchedulerState;
 pub struct ApiState {
     pub app: AppHandle,
     pub scheduler_state: Arc<SchedulerState>,
+    /// The router checks an API token on every private route (set by
+    /// `create_router`; token changes only apply after a restart)
+    pub token_enforced: bool,
 }
 
 impl ApiState {
@@ -26,6 +30,7 @@ impl ApiState {
         Self {
             app,
             scheduler_state,
+            token_enforced: false,
         }
     }
 }
@@ -33,9 +38,10 @@ impl ApiState {
 /// Start the API server on the specified port
 ///
 /// The server runs until the app exits. No shutdown handle is needed.
-pub fn start_server(state: ApiState, port: u16, token: Option<String>) {
+/// With `public_metrics`, /metrics is served without the token.
+pub fn start_server(state: ApiState, port: u16, token: Option<String>, public_metrics: bool) {
     tauri::async_runtime::spawn(async move {
-        let app = create_app(state, token);
+        let app = create_app(state, token, public_metrics);
         let addr = SocketAddr::from(([127, 0, 0, 1], port));
 
         log::info!("Starting API server on http://{}", addr);
@@ -56,8 +62,8 @@ pub fn start_server(state: ApiState, port: u16, token: Option<String>) {
 }
 
 /// Create the Axum application with all routes
-fn create_app(state: ApiState, token: Option<String>) -> Router {
-    routes::create_router(state, token)
+fn create_app(state: ApiState, token: Option<String>, public_metrics: bool) -> Router {
+    routes::create_router(state, token, public_metrics)
 }
 
 #[cfg(test)]
@@ -1,7 +1,7 @@
 //! API route definitions
 
 use axum::{
-    extract::Request,
+    extract::{DefaultBodyLimit, Request},
     http::{header, StatusCode},
     middleware::{self, Next},
     response::Response,
@@ -13,8 +13,13 @@ use tower_http::cors::{Any, CorsLayer};
 use super::handlers;
 use super::ApiState;
 
+/// Largest account bundle accepted by /accounts/import (history makes them big)
+const IMPORT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
 /// Create the main router with all routes
-pub fn create_router(state: ApiState, token: Option<String>) -> Router {
+pub fn create_router(mut state: ApiState, token: Option<String>, public_metrics: bool) -> Router {
+    state.token_enforced = enforces_token(token.as_deref());
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
@@ -25,8 +30,21 @@ pub fn create_router(state: ApiState, token: Option<String>) -> Router {
         .route("/status", get(handlers::status))
         .route("/status/{account_id}", get(handlers::status_by_account))
         .route("/accounts", get(handlers::accounts))
+        .route("/accounts/export", post(handlers::export_accounts))
+        .route(
+            "/accounts/import",
+            post(handlers::import_accounts).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
         .route("/history", get(handlers::history))
+        .route("/forecast", get(handlers::forecast))
+        .route("/events", get(handlers::events))
+        .route("/metrics", get(handlers::metrics))
+        .route("/diagnostics/schema", get(handlers::schema_diagnostics))
         .route("/refresh", post(handlers::refresh))
+        .route(
+            "/counters/:account_id/increment",
+            post(handlers::increment_counter),
+        )
         .layer(cors)
         .with_state(state);
 
@@ -34,20 +52,37 @@ pub fn create_router(state: ApiState, token: Option<String>) -> Router {
     if let Some(token) = token {
         router = router.layer(middleware::from_fn(move |req, next| {
             let token = token.clone();
-            auth_middleware(req, next, token)
+            auth_middleware(req, next, token, public_metrics)
         }));
     }
 
     router
 }
 
+/// Whether the auth middleware built for `token` actually protects anything
+fn enforces_token(token: Option<&str>) -> bool {
+    token.is_some_and(|t| !t.trim().is_empty())
+}
+
+/// Whether a path is served without authentication
+///
+/// /health is always public; /metrics only when `public_metrics` is set.
+fn is_public_path(path: &str, public_metrics: bool) -> bool {
+    path == "/health" || (public_metrics && path == "/metrics")
+}
+
 /// Authentication middleware
 ///
 /// Validates the Bearer token in the Authorization header.
-/// Skips auth for the /health endpoint.
-async fn auth_middleware(req: Request, next: Next, expected_token: String) -> Result<Response, StatusCode> {
-    // Skip auth for health endpoint
-    if req.uri().path() == "/health" {
+/// Skips auth for public endpoints (see `is_public_path`).
+async fn auth_middleware(
+    req: Request,
+    next: Next,
+    expected_token: String,
+    public_metrics: bool,
+) -> Result<Response, StatusCode> {
+    // Skip auth for public endpoints
+    if is_public_path(req.uri().path(), public_metrics) {
         return Ok(next.run(req).await);
     }
 
@@ -82,4 +117,19 @@ mod tests {
             .allow_methods(Any)
             .allow_headers(Any);
     }
+
+    #[test]
+    fn empty_token_is_not_enforced() {
+        assert!(enforces_token(Some("secret")));
+        assert!(!enforces_token(Some(" ")));
+        assert!(!enforces_token(None));
+    }
+
+    #[test]
+    fn metrics_is_only_public_when_enabled() {
+        assert!(is_public_path("/health", false));
+        assert!(!is_public_path("/metrics", false));
+        assert!(is_public_path("/metrics", true));
+        assert!(!is_public_path("/status", true));
+    }
 }
@@ -1,7 +1,7 @@
 use crate::error::{AppError, ProviderError};
-use crate::models::Account;
+use crate::models::{Account, ProviderId};
 use crate::providers::ProviderRegistry;
-use crate::services::CredentialService;
+use crate::services::{BrowserSession, CounterService, CredentialService, SessionImportService};
 use tauri::AppHandle;
 
 use super::usage::TestConnectionResult;
@@ -31,15 +31,42 @@ pub async fn save_account(app: AppHandle, account: Account) -> Result<(), AppErr
 #[tauri::command]
 pub async fn delete_account(app: AppHandle, account_id: String) -> Result<(), AppError> {
     log::info!("Deleting account: {}", account_id);
-    CredentialService::delete_account(&app, &account_id)
+    CredentialService::delete_account(&app, &account_id)?;
+
+    // Drop any uses logged for a counter account
+    if let Err(e) = CounterService::clear(&app, &account_id) {
+        log::warn!("Failed to clear counter for {}: {}", account_id, e);
+    }
+    Ok(())
+}
+
+/// Clear credentials that can't be read back, returning the accounts that
+/// need them entered again
+#[tauri::command]
+pub async fn repair_credentials(app: AppHandle) -> Result<Vec<Account>, AppError> {
+    log::info!("Repairing stored credentials");
+    CredentialService::repair(&app)
+}
+
+/// Find a claude.ai session in the local browsers (of `org_id` when set)
+#[tauri::command]
+pub async fn find_browser_session(
+    app: AppHandle,
+    org_id: Option<String>,
+) -> Result<Option<BrowserSession>, AppError> {
+    log::info!("Looking for a claude.ai session in local browsers");
+    SessionImportService::find(&app, org_id.as_deref(), None).await
 }
 
 /// Test connection for an account
 #[tauri::command]
-pub async fn test_account_connection(account: Account) -> Result<TestConnectionResult, AppError> {
+pub async fn test_account_connection(
+    app: AppHandle,
+    account: Account,
+) -> Result<TestConnectionResult, AppError> {
     log::info!("Testing connection for account: {} ({})", account.name, account.id);
 
-    let registry = ProviderRegistry::new()?;
+    let registry = ProviderRegistry::for_app(&app)?;
 
     let provider_impl = match registry.get(&account.provider) {
         Some(p) => p,
@@ -49,6 +76,7 @@ pub async fn test_account_connection(account: Account) -> Result<TestConnectionR
                 error_code: Some("PROVIDER_UNAVAILABLE".to_string()),
                 error_message: Some(format!("Provider '{}' is not available", account.provider)),
                 hint: Some("This provider is currently blocked or not supported.".to_string()),
+                browser_session: None,
             });
         }
     };
@@ -59,47 +87,100 @@ pub async fn test_account_connection(account: Account) -> Result<TestConnectionR
             success: false,
             error_code: Some("INVALID_FORMAT".to_string()),
             error_message: Some("Credentials format is invalid".to_string()),
-            hint: Some("Please ensure both Organization ID and Session Key are provided.".to_string()),
+            hint: Some(format!(
+                "Please fill in all required {} credentials.",
+                provider_impl.name()
+            )),
+            browser_session: None,
         });
     }
 
     // Try to fetch usage
-    match provider_impl.fetch_usage(&account.credentials).await {
+    match provider_impl.fetch_account_usage(&account).await {
         Ok(_) => Ok(TestConnectionResult {
             success: true,
             error_code: None,
             error_message: None,
             hint: None,
+            browser_session: None,
         }),
-        Err(ProviderError::SessionExpired) => Ok(TestConnectionResult {
-            success: false,
-            error_code: Some("SESSION_EXPIRED".to_string()),
-            error_message: Some("Your session has expired".to_string()),
-            hint: Some("Please get a fresh session key from Claude.ai. Open DevTools → Application → Cookies → copy sessionKey.".to_string()),
-        }),
+        Err(ProviderError::SessionExpired) => {
+            // Offer a session the user is still logged in with
+            let browser_session = if account.provider == ProviderId::Claude.as_str() {
+                SessionImportService::find(
+                    &app,
+                    account.credentials.org_id.as_deref(),
+                    account.credentials.session_key.as_deref(),
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to look for a browser session: {}", e);
+                    None
+                })
+            } else {
+                None
+            };
+
+            let hint = match &browser_session {
+                Some(session) => format!(
+                    "Found a working session in {} ({}). Use it, or get a fresh session key from Claude.ai.",
+                    session.browser, session.profile
+                ),
+                None => "Please get a fresh session key from Claude.ai. Open DevTools → Application → Cookies → copy sessionKey.".to_string(),
+            };
+
+            Ok(TestConnectionResult {
+                success: false,
+                error_code: Some("SESSION_EXPIRED".to_string()),
+                error_message: Some("Your session has expired".to_string()),
+                hint: Some(hint),
+                browser_session,
+            })
+        }
         Err(ProviderError::CloudflareBlocked) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("CLOUDFLARE_BLOCKED".to_string()),
             error_message: Some("Request was blocked by Cloudflare".to_string()),
             hint: Some("This may be a temporary issue. Please try again in a few minutes.".to_string()),
+            browser_session: None,
         }),
-        Err(ProviderError::RateLimited) => Ok(TestConnectionResult {
+        Err(ProviderError::RateLimited(retry_after)) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("RATE_LIMITED".to_string()),
             error_message: Some("Too many requests".to_string()),
-            hint: Some("Please wait a moment before trying again.".to_string()),
+            hint: Some(match retry_after {
+                Some(wait) => format!("Please wait {} seconds before trying again.", wait.as_secs()),
+                None => "Please wait a moment before trying again.".to_string(),
+            }),
+            browser_session: None,
+        }),
+        Err(ProviderError::Network(msg)) => Ok(TestConnectionResult {
+            success: false,
+            error_code: Some("NETWORK_ERROR".to_string()),
+            error_message: Some(msg),
+            hint: Some("Please check your internet connection.".to_string()),
+            browser_session: None,
+        }),
+        Err(ProviderError::ServerError(status, _)) => Ok(TestConnectionResult {
+            success: false,
+            error_code: Some("SERVER_ERROR".to_string()),
+            error_message: Some(format!("The service returned an error ({})", status)),
+            hint: Some("The service may be having problems. Please try again in a few minutes.".to_string()),
+            browser_session: None,
         }),
         Err(ProviderError::MissingCredentials(field)) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("MISSING_CREDENTIALS".to_string()),
             error_message: Some(format!("Missing required field: {}", field)),
             hint: Some("Please provide all required credentials.".to_string()),
+            browser_session: None,
         }),
         Err(ProviderError::InvalidCredentials(msg)) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("INVALID_CREDENTIALS".to_string()),
             error_message: Some(msg),
             hint: Some("Please check your credentials and try again.".to_string()),
+            browser_session: None,
         }),
         Err(ProviderError::HttpError(msg)) => {
             let hint = if msg.contains("404") {
@@ -115,6 +196,7 @@ pub async fn test_account_connection(account: Account) -> Result<TestConnectionR
                 error_code: Some("HTTP_ERROR".to_string()),
                 error_message: Some(msg),
                 hint,
+                browser_session: None,
             })
         }
         Err(ProviderError::ParseError(msg)) => Ok(TestConnectionResult {
@@ -122,6 +204,7 @@ pub async fn test_account_connection(account: Account) -> Result<TestConnectionR
             error_code: Some("PARSE_ERROR".to_string()),
             error_message: Some("Failed to parse API response".to_string()),
             hint: Some(format!("The API response format was unexpected: {}", msg)),
+            browser_session: None,
         }),
     }
 }
@@ -0,0 +1,17 @@
+use crate::error::AppError;
+use crate::models::UsageData;
+use crate::services::{CounterService, SchedulerService, SchedulerState};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+/// Log uses for a counter account and return its refreshed usage
+#[tauri::command]
+pub async fn increment_counter(
+    app: AppHandle,
+    state: State<'_, Arc<SchedulerState>>,
+    account_id: String,
+    amount: Option<u32>,
+) -> Result<UsageData, AppError> {
+    CounterService::increment(&app, &account_id, amount.unwrap_or(1))?;
+    SchedulerService::refresh_account(&app, &state, &account_id).await
+}
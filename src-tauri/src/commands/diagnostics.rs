@@ -0,0 +1,10 @@
+use crate::error::AppError;
+use crate::models::SchemaRecord;
+use crate::services::SchemaService;
+use tauri::AppHandle;
+
+/// Get the known response shape of each provider endpoint (current vs expected)
+#[tauri::command]
+pub fn get_response_schemas(app: AppHandle) -> Result<Vec<SchemaRecord>, AppError> {
+    SchemaService::list(&app)
+}
@@ -0,0 +1,27 @@
+use crate::error::AppError;
+use crate::models::AccountForecast;
+use crate::services::{ForecastService, SchedulerState};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+/// Forecast burn rate and time-to-limit from the latest usage of each account
+#[tauri::command]
+pub async fn get_usage_forecast(
+    app: AppHandle,
+    state: State<'_, Arc<SchedulerState>>,
+    account_id: Option<String>,
+) -> Result<Vec<AccountForecast>, AppError> {
+    log::info!("Getting usage forecast (account: {:?})", account_id);
+
+    state
+        .all_previous_usage()
+        .await
+        .iter()
+        .filter(|usage| {
+            account_id
+                .as_ref()
+                .map_or(true, |id| &usage.account_id == id)
+        })
+        .map(|usage| ForecastService::forecast_usage(&app, usage))
+        .collect()
+}
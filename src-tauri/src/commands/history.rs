@@ -1,4 +1,7 @@
-use crate::models::{HistoryMetadata, HistoryQuery, RetentionPolicy, UsageHistoryEntry, UsageStats};
+use crate::models::{
+    HistoryMetadata, HistoryQuery, HistoryResolution, RetentionPolicy, UsageHistoryEntry,
+    UsageStats,
+};
 use crate::services::HistoryService;
 use tauri::AppHandle;
 
@@ -17,9 +20,13 @@ pub async fn query_history(
         end_date: None,
         limit: Some(1000),
         offset: None,
+        cursor: None,
+        resolution: HistoryResolution::Raw,
     });
 
-    HistoryService::query(&app, &query).map_err(|e| e.to_string())
+    HistoryService::query(&app, &query)
+        .map(|page| page.entries)
+        .map_err(|e| e.to_string())
 }
 
 /// Get history metadata
@@ -1,13 +1,25 @@
 mod accounts;
+mod counters;
 mod credentials;
+mod diagnostics;
+mod forecast;
 mod history;
 mod scheduler;
 mod settings;
+mod transfer;
 mod usage;
+mod vault;
+mod webhooks;
 
 pub use accounts::*;
+pub use counters::*;
 pub use credentials::*;
+pub use diagnostics::*;
+pub use forecast::*;
 pub use history::*;
 pub use scheduler::*;
 pub use settings::*;
+pub use transfer::*;
 pub use usage::*;
+pub use vault::*;
+pub use webhooks::*;
@@ -1,5 +1,6 @@
 use crate::error::AppError;
-use crate::services::{SchedulerService, SchedulerState, SettingsService};
+use crate::services::{CircuitBreaker, SchedulerService, SchedulerState, SettingsService};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -72,6 +73,7 @@ pub async fn get_session_status(state: State<'_, Arc<SchedulerState>>) -> Result
         valid: !any_paused,
         error_count: 0, // Legacy field, no longer tracked globally
         paused: any_paused,
+        circuits: state.all_circuits().await,
     })
 }
 
@@ -89,4 +91,6 @@ pub struct SessionStatusResponse {
     pub valid: bool,
     pub error_count: u64,
     pub paused: bool,
+    /// Circuit breaker per account ID (accounts that never failed are omitted)
+    pub circuits: HashMap<String, CircuitBreaker>,
 }
@@ -0,0 +1,33 @@
+use crate::error::AppError;
+use crate::services::{ImportConflict, ImportSummary, TransferService};
+use tauri::AppHandle;
+
+/// Export all accounts, the settings and optionally the history as a bundle
+/// encrypted with `passphrase`
+#[tauri::command]
+pub async fn export_accounts(
+    app: AppHandle,
+    passphrase: String,
+    include_history: Option<bool>,
+) -> Result<String, AppError> {
+    TransferService::export(&app, &passphrase, include_history.unwrap_or(false)).await
+}
+
+/// Import a bundle created by `export_accounts`
+#[tauri::command]
+pub async fn import_accounts(
+    app: AppHandle,
+    bundle: String,
+    passphrase: String,
+    on_conflict: Option<ImportConflict>,
+    import_settings: Option<bool>,
+) -> Result<ImportSummary, AppError> {
+    TransferService::import(
+        &app,
+        &bundle,
+        &passphrase,
+        on_conflict.unwrap_or_default(),
+        import_settings.unwrap_or(false),
+    )
+    .await
+}
@@ -1,13 +1,13 @@
 use crate::error::{AppError, ProviderError};
 use crate::models::{Credentials, UsageData};
 use crate::providers::{ProviderMetadata, ProviderRegistry};
-use crate::services::CredentialService;
+use crate::services::{BrowserSession, CredentialService};
 use tauri::AppHandle;
 
 /// Get metadata for all providers (including blocked/planned ones)
 #[tauri::command]
-pub fn list_providers() -> Result<Vec<ProviderMetadata>, AppError> {
-    let registry = ProviderRegistry::new()?;
+pub fn list_providers(app: AppHandle) -> Result<Vec<ProviderMetadata>, AppError> {
+    let registry = ProviderRegistry::for_app(&app)?;
     Ok(registry.all_metadata())
 }
 
@@ -17,7 +17,7 @@ pub async fn fetch_usage(app: AppHandle, provider: String) -> Result<UsageData,
     log::info!("Fetching usage for provider: {}", provider);
 
     // Get the provider from registry
-    let registry = ProviderRegistry::new()?;
+    let registry = ProviderRegistry::for_app(&app)?;
     let provider_impl = registry
         .get(&provider)
         .ok_or_else(|| ProviderError::HttpError(format!("Unknown or unavailable provider: {}", provider)))?;
@@ -38,7 +38,7 @@ pub async fn fetch_usage(app: AppHandle, provider: String) -> Result<UsageData,
     }
 
     // Fetch usage
-    let mut usage = provider_impl.fetch_usage(&account.credentials).await?;
+    let mut usage = provider_impl.fetch_account_usage(&account).await?;
 
     // Add account info to usage data
     usage.account_id = account.id;
@@ -57,7 +57,7 @@ pub async fn fetch_usage_for_account(app: AppHandle, account_id: String) -> Resu
         .ok_or_else(|| ProviderError::MissingCredentials(format!("Account not found: {}", account_id)))?;
 
     // Get the provider from registry
-    let registry = ProviderRegistry::new()?;
+    let registry = ProviderRegistry::for_app(&app)?;
     let provider_impl = registry
         .get(&account.provider)
         .ok_or_else(|| ProviderError::HttpError(format!("Unknown or unavailable provider: {}", account.provider)))?;
@@ -71,7 +71,7 @@ pub async fn fetch_usage_for_account(app: AppHandle, account_id: String) -> Resu
     }
 
     // Fetch usage
-    let mut usage = provider_impl.fetch_usage(&account.credentials).await?;
+    let mut usage = provider_impl.fetch_account_usage(&account).await?;
 
     // Add account info to usage data
     usage.account_id = account.id;
@@ -83,12 +83,13 @@ pub async fn fetch_usage_for_account(app: AppHandle, account_id: String) -> Resu
 /// Validate credentials for a specific provider
 #[tauri::command]
 pub async fn validate_credentials(
+    app: AppHandle,
     provider: String,
     credentials: Credentials,
 ) -> Result<bool, AppError> {
     log::info!("Validating credentials for provider: {}", provider);
 
-    let registry = ProviderRegistry::new()?;
+    let registry = ProviderRegistry::for_app(&app)?;
 
     match registry.get(&provider) {
         Some(provider_impl) => Ok(provider_impl.validate_credentials(&credentials)),
@@ -107,17 +108,22 @@ pub struct TestConnectionResult {
     pub error_code: Option<String>,
     pub error_message: Option<String>,
     pub hint: Option<String>,
+    /// Working session found in a local browser (offered when a claude.ai
+    /// session has expired)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser_session: Option<BrowserSession>,
 }
 
 /// Test connection by making an actual API request
 #[tauri::command]
 pub async fn test_connection(
+    app: AppHandle,
     provider: String,
     credentials: Credentials,
 ) -> Result<TestConnectionResult, AppError> {
     log::info!("Testing connection for provider: {}", provider);
 
-    let registry = ProviderRegistry::new()?;
+    let registry = ProviderRegistry::for_app(&app)?;
 
     let provider_impl = match registry.get(&provider) {
         Some(p) => p,
@@ -127,6 +133,7 @@ pub async fn test_connection(
                 error_code: Some("PROVIDER_UNAVAILABLE".to_string()),
                 error_message: Some(format!("Provider '{}' is not available", provider)),
                 hint: Some("This provider is currently blocked or not supported.".to_string()),
+                browser_session: None,
             });
         }
     };
@@ -138,6 +145,7 @@ pub async fn test_connection(
             error_code: Some("INVALID_FORMAT".to_string()),
             error_message: Some("Credentials format is invalid".to_string()),
             hint: Some("Please ensure both Organization ID and Session Key are provided.".to_string()),
+            browser_session: None,
         });
     }
 
@@ -148,36 +156,59 @@ pub async fn test_connection(
             error_code: None,
             error_message: None,
             hint: None,
+            browser_session: None,
         }),
         Err(ProviderError::SessionExpired) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("SESSION_EXPIRED".to_string()),
             error_message: Some("Your session has expired".to_string()),
             hint: Some("Please get a fresh session key from Claude.ai. Open DevTools → Application → Cookies → copy sessionKey.".to_string()),
+            browser_session: None,
         }),
         Err(ProviderError::CloudflareBlocked) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("CLOUDFLARE_BLOCKED".to_string()),
             error_message: Some("Request was blocked by Cloudflare".to_string()),
             hint: Some("This may be a temporary issue. Please try again in a few minutes.".to_string()),
+            browser_session: None,
         }),
-        Err(ProviderError::RateLimited) => Ok(TestConnectionResult {
+        Err(ProviderError::RateLimited(retry_after)) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("RATE_LIMITED".to_string()),
             error_message: Some("Too many requests".to_string()),
-            hint: Some("Please wait a moment before trying again.".to_string()),
+            hint: Some(match retry_after {
+                Some(wait) => format!("Please wait {} seconds before trying again.", wait.as_secs()),
+                None => "Please wait a moment before trying again.".to_string(),
+            }),
+            browser_session: None,
+        }),
+        Err(ProviderError::Network(msg)) => Ok(TestConnectionResult {
+            success: false,
+            error_code: Some("NETWORK_ERROR".to_string()),
+            error_message: Some(msg),
+            hint: Some("Please check your internet connection.".to_string()),
+            browser_session: None,
+        }),
+        Err(ProviderError::ServerError(status, _)) => Ok(TestConnectionResult {
+            success: false,
+            error_code: Some("SERVER_ERROR".to_string()),
+            error_message: Some(format!("The service returned an error ({})", status)),
+            hint: Some("The service may be having problems. Please try again in a few minutes.".to_string()),
+            browser_session: None,
         }),
         Err(ProviderError::MissingCredentials(field)) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("MISSING_CREDENTIALS".to_string()),
             error_message: Some(format!("Missing required field: {}", field)),
             hint: Some("Please provide all required credentials.".to_string()),
+            browser_session: None,
         }),
         Err(ProviderError::InvalidCredentials(msg)) => Ok(TestConnectionResult {
             success: false,
             error_code: Some("INVALID_CREDENTIALS".to_string()),
             error_message: Some(msg),
             hint: Some("Please check your credentials and try again.".to_string()),
+            browser_session: None,
         }),
         Err(ProviderError::HttpError(msg)) => {
             // Parse for common issues
@@ -194,6 +225,7 @@ pub async fn test_connection(
                 error_code: Some("HTTP_ERROR".to_string()),
                 error_message: Some(msg),
                 hint,
+                browser_session: None,
             })
         }
         Err(ProviderError::ParseError(msg)) => Ok(TestConnectionResult {
@@ -201,6 +233,7 @@ pub async fn test_connection(
             error_code: Some("PARSE_ERROR".to_string()),
             error_message: Some("Failed to parse API response".to_string()),
             hint: Some(format!("The API response format was unexpected: {}", msg)),
+            browser_session: None,
         }),
     }
 }
@@ -0,0 +1,61 @@
+use crate::error::AppError;
+use crate::services::{SchedulerService, SchedulerState, VaultService, VaultStatus};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+/// Run a vault operation on the blocking pool
+///
+/// Deriving the key takes a good part of a second, and resealing touches
+/// every stored secret; neither should hold up an async worker.
+async fn run_blocking(
+    app: &AppHandle,
+    operation: impl FnOnce(&AppHandle) -> Result<(), AppError> + Send + 'static,
+) -> Result<(), AppError> {
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || operation(&app))
+        .await
+        .map_err(|e| AppError::Store(format!("Vault operation failed: {}", e)))?
+}
+
+/// Get whether a master passphrase is set and whether it has been entered
+#[tauri::command]
+pub fn get_vault_status(app: AppHandle) -> Result<VaultStatus, AppError> {
+    VaultService::status(&app)
+}
+
+/// Set a master passphrase, encrypting all stored credentials with it
+#[tauri::command]
+pub async fn enable_vault(app: AppHandle, passphrase: String) -> Result<VaultStatus, AppError> {
+    run_blocking(&app, move |app| VaultService::enable(app, &passphrase)).await?;
+    VaultService::status(&app)
+}
+
+/// Enter the master passphrase and resume fetching
+#[tauri::command]
+pub async fn unlock_vault(
+    app: AppHandle,
+    state: State<'_, Arc<SchedulerState>>,
+    passphrase: String,
+) -> Result<VaultStatus, AppError> {
+    run_blocking(&app, move |app| VaultService::unlock(app, &passphrase)).await?;
+
+    // Fetching was paused while locked; catch up now
+    if let Err(e) = SchedulerService::force_refresh(&app, &state).await {
+        log::warn!("Refresh after unlocking failed: {}", e);
+    }
+    VaultService::status(&app)
+}
+
+/// Forget the master passphrase until it is entered again
+#[tauri::command]
+pub fn lock_vault(app: AppHandle) -> Result<VaultStatus, AppError> {
+    VaultService::lock(&app);
+    VaultService::status(&app)
+}
+
+/// Remove the master passphrase
+#[tauri::command]
+pub async fn disable_vault(app: AppHandle, passphrase: String) -> Result<VaultStatus, AppError> {
+    run_blocking(&app, move |app| VaultService::disable(app, &passphrase)).await?;
+    VaultService::status(&app)
+}
@@ -0,0 +1,37 @@
+use crate::error::AppError;
+use crate::models::{WebhookDeadLetter, WebhookDelivery};
+use crate::services::WebhookService;
+use tauri::AppHandle;
+
+/// Get the webhook delivery log (newest first)
+#[tauri::command]
+pub fn list_webhook_deliveries(
+    app: AppHandle,
+    webhook_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<WebhookDelivery>, AppError> {
+    WebhookService::list_deliveries(&app, webhook_id.as_deref(), limit)
+}
+
+/// Get deliveries that exhausted their retries
+#[tauri::command]
+pub fn list_webhook_dead_letters(app: AppHandle) -> Result<Vec<WebhookDeadLetter>, AppError> {
+    WebhookService::list_dead_letters(&app)
+}
+
+/// Redeliver a dead letter
+#[tauri::command]
+pub async fn retry_webhook_dead_letter(
+    app: AppHandle,
+    delivery_id: String,
+) -> Result<WebhookDelivery, AppError> {
+    log::info!("Retrying webhook delivery {}", delivery_id);
+    WebhookService::retry_dead_letter(&app, &delivery_id).await
+}
+
+/// Empty the dead-letter queue
+#[tauri::command]
+pub fn clear_webhook_dead_letters(app: AppHandle) -> Result<usize, AppError> {
+    log::info!("Clearing webhook dead letters");
+    WebhookService::clear_dead_letters(&app)
+}
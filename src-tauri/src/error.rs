@@ -1,4 +1,5 @@
 use serde::Serialize;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -9,9 +10,15 @@ pub enum AppError {
     #[error("Store error: {0}")]
     Store(String),
 
+    #[error("Crypto error: {0}")]
+    Crypto(#[from] CryptoError),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
     #[error("Tauri error: {0}")]
     Tauri(#[from] tauri::Error),
 
@@ -20,6 +27,35 @@ pub enum AppError {
 
     #[error("Notification error: {0}")]
     Notification(String),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// Credentials are sealed with a master passphrase that hasn't been entered
+    #[error("Credential vault is locked - enter your passphrase to unlock it")]
+    VaultLocked,
+
+    /// Stored secrets of the account couldn't be read back
+    #[error("Credentials of {0} need to be entered again")]
+    NeedsReentry(String),
+}
+
+/// Failures to encrypt, decrypt or derive keys for stored credentials
+#[derive(Debug, Error)]
+pub enum CryptoError {
+    #[error("Encryption failed: {0}")]
+    Encrypt(String),
+
+    /// Wrong key or tampered data, e.g. a legacy value after the user name
+    /// or home directory changed
+    #[error("Decryption failed: {0}")]
+    Decrypt(String),
+
+    #[error("Malformed ciphertext: {0}")]
+    Malformed(String),
+
+    #[error("Key derivation failed: {0}")]
+    KeyDerivation(String),
 }
 
 #[derive(Debug, Error)]
@@ -27,14 +63,22 @@ pub enum ProviderError {
     #[error("HTTP request failed: {0}")]
     HttpError(String),
 
+    /// The request never got a response (connection, DNS, timeout)
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Server error {0}: {1}")]
+    ServerError(u16, String),
+
     #[error("Session expired - please update your credentials")]
     SessionExpired,
 
     #[error("Access blocked by Cloudflare - try again later")]
     CloudflareBlocked,
 
+    /// Carries the server's `Retry-After`, if it sent one
     #[error("Rate limited - please wait before retrying")]
-    RateLimited,
+    RateLimited(Option<Duration>),
 
     #[error("Invalid response format: {0}")]
     ParseError(String),
@@ -46,6 +90,28 @@ pub enum ProviderError {
     InvalidCredentials(String),
 }
 
+impl ProviderError {
+    /// Stable snake_case name of the variant (used as a metrics label)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::HttpError(_) => "http_error",
+            Self::Network(_) => "network_error",
+            Self::ServerError(..) => "server_error",
+            Self::SessionExpired => "session_expired",
+            Self::CloudflareBlocked => "cloudflare_blocked",
+            Self::RateLimited(_) => "rate_limited",
+            Self::ParseError(_) => "parse_error",
+            Self::MissingCredentials(_) => "missing_credentials",
+            Self::InvalidCredentials(_) => "invalid_credentials",
+        }
+    }
+
+    /// Whether the same request may succeed if retried right away
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Network(_) | Self::ServerError(..))
+    }
+}
+
 // Make errors serializable for Tauri commands
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -80,7 +146,7 @@ mod tests {
             "Access blocked by Cloudflare - try again later"
         );
         assert_eq!(
-            ProviderError::RateLimited.to_string(),
+            ProviderError::RateLimited(None).to_string(),
             "Rate limited - please wait before retrying"
         );
     }
@@ -104,6 +170,31 @@ mod tests {
         assert!(app_err.to_string().contains("Session expired"));
     }
 
+    #[test]
+    fn provider_error_kind() {
+        assert_eq!(ProviderError::SessionExpired.kind(), "session_expired");
+        assert_eq!(ProviderError::HttpError("timeout".to_string()).kind(), "http_error");
+    }
+
+    #[test]
+    fn provider_error_transient() {
+        assert!(ProviderError::Network("timeout".to_string()).is_transient());
+        assert!(ProviderError::ServerError(503, String::new()).is_transient());
+        assert!(!ProviderError::SessionExpired.is_transient());
+        assert!(!ProviderError::RateLimited(None).is_transient());
+        assert!(!ProviderError::HttpError("Unexpected status 404".to_string()).is_transient());
+    }
+
+    #[test]
+    fn app_error_from_crypto_error() {
+        let app_err: AppError = CryptoError::Decrypt("wrong key".to_string()).into();
+        assert!(matches!(app_err, AppError::Crypto(CryptoError::Decrypt(_))));
+        assert_eq!(
+            app_err.to_string(),
+            "Crypto error: Decryption failed: wrong key"
+        );
+    }
+
     #[test]
     fn provider_error_serialization() {
         let err = ProviderError::SessionExpired;
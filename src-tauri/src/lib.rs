@@ -16,14 +16,17 @@ mod providers;
 mod services;
 
 use commands::{
-    clear_history, cleanup_history, delete_account, delete_credentials, export_history_csv,
+    clear_history, clear_webhook_dead_letters, cleanup_history, delete_account, delete_credentials, export_history_csv,
     export_history_json, fetch_usage, fetch_usage_for_account, force_refresh, get_account,
-    get_credentials, get_history_metadata, get_retention_policy, get_scheduler_status,
-    get_session_status, get_settings, get_usage_stats, has_credentials, list_accounts,
-    list_providers, query_history, resume_scheduler, save_account, save_credentials,
+    get_credentials, get_history_metadata, get_response_schemas, get_retention_policy, get_scheduler_status,
+    get_session_status, get_settings, get_usage_forecast, get_usage_stats, has_credentials,
+    increment_counter, list_accounts,
+    list_providers, list_webhook_dead_letters, list_webhook_deliveries, query_history,
+    resume_scheduler, retry_webhook_dead_letter, save_account, save_credentials,
     save_settings, send_test_notification, set_refresh_interval, set_retention_policy,
     start_scheduler, stop_scheduler, test_account_connection, test_connection,
-    validate_credentials,
+    validate_credentials, get_vault_status, repair_credentials, enable_vault, unlock_vault, lock_vault,
+    disable_vault, export_accounts, import_accounts, find_browser_session,
 };
 use services::{HistoryService, SchedulerService, SchedulerState, SettingsService};
 
@@ -55,6 +58,10 @@ pub fn run() {
             save_account,
             delete_account,
             test_account_connection,
+            find_browser_session,
+            repair_credentials,
+            // Counter commands
+            increment_counter,
             // Settings commands
             get_settings,
             save_settings,
@@ -83,6 +90,24 @@ pub fn run() {
             export_history_json,
             export_history_csv,
             clear_history,
+            // Forecast commands
+            get_usage_forecast,
+            // Diagnostics commands
+            get_response_schemas,
+            // Webhook commands
+            list_webhook_deliveries,
+            list_webhook_dead_letters,
+            retry_webhook_dead_letter,
+            clear_webhook_dead_letters,
+            // Vault commands
+            get_vault_status,
+            enable_vault,
+            unlock_vault,
+            lock_vault,
+            disable_vault,
+            // Transfer commands
+            export_accounts,
+            import_accounts,
         ])
         .setup(|app| {
             // Set up logging in debug mode
@@ -310,6 +335,11 @@ pub fn run() {
                 });
             }
 
+            // Open the history database (migrates legacy history.json on first launch)
+            if let Err(e) = HistoryService::init(app.handle()) {
+                log::error!("Failed to open history database: {}", e);
+            }
+
             // Run history cleanup on startup (if enabled)
             if let Ok(policy) = HistoryService::get_retention_policy(app.handle()) {
                 if policy.auto_cleanup {
@@ -346,6 +376,7 @@ pub fn run() {
                             api_state,
                             settings.api_server_port,
                             settings.api_server_token.clone(),
+                            settings.api_metrics_public,
                         );
                         log::info!(
                             "API server started on port {}",
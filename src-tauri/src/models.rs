@@ -1,21 +1,28 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-/// Provider identifier (used in Phase 4 multi-provider support)
-#[allow(dead_code)]
+use crate::providers::schema::{SchemaDiff, Shape};
+use crate::providers::CredentialField;
+
+/// Identifier of a functional provider
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum ProviderId {
     Claude,
-    Codex,
+    ClaudeCode,
+    AnthropicApi,
+    #[serde(rename = "openai_api")]
+    OpenAiApi,
 }
 
-#[allow(dead_code)]
 impl ProviderId {
     pub fn as_str(&self) -> &'static str {
         match self {
             ProviderId::Claude => "claude",
-            ProviderId::Codex => "codex",
+            ProviderId::ClaudeCode => "claude_code",
+            ProviderId::AnthropicApi => "anthropic_api",
+            ProviderId::OpenAiApi => "openai_api",
         }
     }
 }
@@ -37,9 +44,23 @@ pub struct Credentials {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_key: Option<String>,
 
-    /// Codex: API key (future use)
+    /// Anthropic API / OpenAI API: admin API key
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+
+    /// Any other field a provider declares in its credential list, such as a
+    /// budget or a cap (and every field of a custom provider)
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Credentials {
+    /// A provider-specific field, if it is set and has the expected type
+    pub fn field<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.extra
+            .get(key)
+            .and_then(|value| T::deserialize(value).ok())
+    }
 }
 
 /// Account for multi-account support
@@ -56,6 +77,19 @@ pub struct Account {
     pub credentials: Credentials,
     /// When the account was created
     pub created_at: DateTime<Utc>,
+    /// Set when stored secrets couldn't be read back and must be entered again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub needs_reentry: Option<CredentialsIssue>,
+}
+
+/// Stored secrets of an account that couldn't be read back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialsIssue {
+    /// Affected credential fields (e.g. `session_key`, `extra.token`)
+    pub fields: Vec<String>,
+    /// Why they couldn't be read
+    pub reason: String,
 }
 
 impl Account {
@@ -67,6 +101,7 @@ impl Account {
             provider,
             credentials,
             created_at: Utc::now(),
+            needs_reentry: None,
         }
     }
 }
@@ -84,6 +119,9 @@ pub struct UsageData {
     pub account_name: String,
     pub timestamp: DateTime<Utc>,
     pub limits: Vec<UsageLimit>,
+    /// Paid overage credits (Claude only, when reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_usage: Option<ExtraUsage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw: Option<serde_json::Value>,
 }
@@ -100,17 +138,14 @@ pub struct UsageLimit {
     pub category: Option<String>,
 }
 
-/// Claude API response structures
+/// Claude API response
+///
+/// Kept as a map: every field shaped like `LimitUsage` is a limit, so limits
+/// added by Anthropic show up without a release.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ClaudeUsageResponse {
-    pub five_hour: Option<LimitUsage>,
-    pub seven_day: Option<LimitUsage>,
-    pub seven_day_oauth_apps: Option<LimitUsage>,
-    pub seven_day_opus: Option<LimitUsage>,
-    pub seven_day_sonnet: Option<LimitUsage>,
-    // Additional fields from API (may be null)
-    pub iguana_necktie: Option<LimitUsage>,
-    pub extra_usage: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -120,6 +155,53 @@ pub struct LimitUsage {
     pub resets_at: Option<String>,
 }
 
+/// Claude `extra_usage` field (paid overage credits)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClaudeExtraUsage {
+    #[serde(default)]
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub monthly_limit: Option<f64>,
+    #[serde(default)]
+    pub used_credits: Option<f64>,
+    #[serde(default)]
+    pub utilization: Option<f64>,
+}
+
+/// Organization a claude.ai session belongs to (`GET /organizations`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeOrganization {
+    pub uuid: String,
+    #[serde(default)]
+    pub name: String,
+    /// e.g. `chat`, `claude_pro`; API-only organizations lack `chat`
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+/// Paid overage credits used once plan limits run out
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraUsage {
+    pub is_enabled: bool,
+    /// Monthly credit limit (None when uncapped)
+    pub monthly_limit: Option<f64>,
+    pub used_credits: Option<f64>,
+    /// Percentage of the monthly limit used
+    pub utilization: Option<f64>,
+}
+
+impl From<ClaudeExtraUsage> for ExtraUsage {
+    fn from(extra: ClaudeExtraUsage) -> Self {
+        Self {
+            is_enabled: extra.is_enabled,
+            monthly_limit: extra.monthly_limit,
+            used_credits: extra.used_credits,
+            utilization: extra.utilization,
+        }
+    }
+}
+
 /// App settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -137,6 +219,10 @@ pub struct AppSettings {
     pub global_shortcut: Option<String>,
     pub notifications: NotificationSettings,
     pub providers: Vec<ProviderConfig>,
+    /// Replace an expired claude.ai session with one from a local browser
+    /// without asking
+    #[serde(default)]
+    pub auto_import_browser_session: bool,
     /// Enable the local API server for CLI and IDE integrations
     #[serde(default)]
     pub api_server_enabled: bool,
@@ -146,6 +232,15 @@ pub struct AppSettings {
     /// Optional authentication token for the API server
     #[serde(default)]
     pub api_server_token: Option<String>,
+    /// Serve /metrics without the API token (for Prometheus scrapers)
+    #[serde(default)]
+    pub api_metrics_public: bool,
+    /// Outbound webhooks for usage events
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// User-defined HTTP/JSON providers
+    #[serde(default)]
+    pub custom_providers: Vec<CustomProviderConfig>,
 }
 
 fn default_api_server_port() -> u16 {
@@ -163,6 +258,9 @@ pub struct NotificationSettings {
     pub thresholds: Vec<u32>,
     pub notify_on_reset: bool,
     pub notify_on_expiry: bool,
+    /// Warn when the recent burn rate projects a limit past 100% before it resets
+    #[serde(default)]
+    pub notify_on_projected_overrun: bool,
     /// Do Not Disturb mode - when enabled, notifications are muted during the scheduled time
     #[serde(default)]
     pub dnd_enabled: bool,
@@ -182,6 +280,205 @@ pub struct ProviderConfig {
     pub credentials: std::collections::HashMap<String, String>,
 }
 
+// ============================================================================
+// Webhook Models
+// ============================================================================
+
+/// Events that can be delivered to webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookEventType {
+    UsageUpdate,
+    SessionStatus,
+    UsageReset,
+    ThresholdCrossed,
+    ProjectedOverrun,
+}
+
+impl WebhookEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UsageUpdate => "usage-update",
+            Self::SessionStatus => "session-status",
+            Self::UsageReset => "usage-reset",
+            Self::ThresholdCrossed => "threshold-crossed",
+            Self::ProjectedOverrun => "projected-overrun",
+        }
+    }
+}
+
+/// An outbound webhook target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    /// Unique webhook identifier
+    pub id: String,
+    /// Display name (e.g., "Team Slack")
+    #[serde(default)]
+    pub name: String,
+    /// Target URL (receives a JSON POST)
+    pub url: String,
+    /// Shared secret for the HMAC-SHA256 signature header
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Events to deliver (empty = all events)
+    #[serde(default)]
+    pub events: Vec<WebhookEventType>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl WebhookConfig {
+    /// Whether this webhook should receive an event
+    pub fn accepts(&self, event: WebhookEventType) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One logged use of a counter account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterEvent {
+    pub at: DateTime<Utc>,
+    pub amount: u32,
+}
+
+/// Known response shape of one provider endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaRecord {
+    pub provider: String,
+    pub endpoint: String,
+    /// Shape before the last drift (same as `current` until one is seen)
+    pub expected: Shape,
+    /// Shape of the latest responses
+    pub current: Shape,
+    /// What changed in the last drift
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<SchemaDiff>,
+    pub last_seen: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drifted_at: Option<DateTime<Utc>>,
+}
+
+/// A user-defined provider that reads limits from any HTTP/JSON endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomProviderConfig {
+    /// Provider ID used by accounts (must not clash with a built-in provider)
+    pub id: String,
+    /// Display name (e.g., "Internal Gateway")
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// URL to GET; `{field}` placeholders are filled from the account's credentials
+    pub url: String,
+    /// Request headers (values may use `{field}` placeholders)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Credential fields each account of this provider asks for
+    #[serde(default)]
+    pub credentials: Vec<CredentialField>,
+    /// How limits are read from the JSON response
+    #[serde(default)]
+    pub limits: Vec<CustomLimitMapping>,
+    /// Status codes meaning the credentials expired
+    #[serde(default = "default_session_expired_statuses")]
+    pub session_expired_statuses: Vec<u16>,
+    /// Status codes meaning the endpoint is rate limiting us
+    #[serde(default = "default_rate_limited_statuses")]
+    pub rate_limited_statuses: Vec<u16>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// Where one limit lives in a custom provider's response (JSON pointers, RFC 6901)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomLimitMapping {
+    /// Limit ID (e.g., "daily_tokens")
+    pub id: String,
+    /// Static label (used when `label_pointer` is unset or missing)
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Pointer to a label string, e.g. "/limits/0/name"
+    #[serde(default)]
+    pub label_pointer: Option<String>,
+    /// Pointer to a number (or numeric string), e.g. "/usage/percent"
+    pub utilization_pointer: String,
+    /// Multiplier applied to the utilization (e.g., 100 for 0-1 ratios)
+    #[serde(default = "default_utilization_scale")]
+    pub utilization_scale: f64,
+    /// Pointer to the reset time (RFC 3339 string or Unix seconds/millis);
+    /// the limit is skipped while it is null, like Claude's idle limits
+    pub resets_at_pointer: String,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+fn default_session_expired_statuses() -> Vec<u16> {
+    vec![401]
+}
+
+fn default_rate_limited_statuses() -> Vec<u16> {
+    vec![429]
+}
+
+fn default_utilization_scale() -> f64 {
+    1.0
+}
+
+/// Body POSTed to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    /// Delivery ID (also sent as the X-AI-Pulse-Delivery header)
+    pub id: String,
+    pub event: WebhookEventType,
+    pub timestamp: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+/// Outcome of a webhook delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    Delivered,
+    Failed,
+}
+
+/// Delivery log entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    /// Delivery ID (matches the payload ID)
+    pub id: String,
+    pub webhook_id: String,
+    pub event: WebhookEventType,
+    pub url: String,
+    pub status: WebhookDeliveryStatus,
+    /// Number of attempts made
+    pub attempts: u32,
+    /// HTTP status of the last attempt (None if the request itself failed)
+    pub status_code: Option<u16>,
+    /// Error from the last failed attempt
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// A delivery that exhausted its retries, kept so it can be replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeadLetter {
+    pub delivery: WebhookDelivery,
+    pub payload: WebhookPayload,
+}
+
 // ============================================================================
 // History Models
 // ============================================================================
@@ -211,8 +508,15 @@ pub struct UsageHistoryEntry {
 #[serde(rename_all = "camelCase")]
 pub struct UsageLimitSnapshot {
     pub id: String,
+    /// Utilization at the snapshot (average over the bucket for rollups)
     pub utilization: f64,
     pub resets_at: DateTime<Utc>,
+    /// Lowest utilization in the bucket (rollups only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_utilization: Option<f64>,
+    /// Highest utilization in the bucket (rollups only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_utilization: Option<f64>,
 }
 
 /// History storage metadata
@@ -232,7 +536,7 @@ pub struct HistoryMetadata {
 }
 
 /// Query parameters for history retrieval
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoryQuery {
     /// Filter by provider (optional)
@@ -247,6 +551,58 @@ pub struct HistoryQuery {
     pub limit: Option<usize>,
     /// Offset for pagination (optional)
     pub offset: Option<usize>,
+    /// Cursor from a previous page's `next_cursor` (optional)
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Granularity of the returned entries (default: raw)
+    #[serde(default)]
+    pub resolution: HistoryResolution,
+}
+
+/// Granularity of history entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryResolution {
+    /// Individual snapshots as they were fetched
+    #[default]
+    Raw,
+    /// Hourly min/avg/max per limit
+    Hourly,
+    /// Daily min/avg/max per limit
+    Daily,
+}
+
+impl HistoryResolution {
+    /// Bucket width in milliseconds (None for raw samples)
+    pub fn bucket_millis(&self) -> Option<i64> {
+        match self {
+            Self::Raw => None,
+            Self::Hourly => Some(60 * 60 * 1000),
+            Self::Daily => Some(24 * 60 * 60 * 1000),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+        }
+    }
+}
+
+/// One page of history query results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    /// Entries on this page (newest first)
+    pub entries: Vec<UsageHistoryEntry>,
+    /// Number of entries matching the filters across all pages
+    pub total: usize,
+    /// Cursor for fetching the next page, if there is one
+    pub next_cursor: Option<String>,
+    /// Whether more entries exist after this page
+    pub has_more: bool,
 }
 
 /// Aggregated usage statistics for a time period
@@ -263,20 +619,70 @@ pub struct UsageStats {
     pub sample_count: usize,
 }
 
+/// Projected burn rate and time-to-limit for one usage limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitForecast {
+    pub limit_id: String,
+    /// Utilization at the latest sample
+    pub utilization: f64,
+    /// When the current window resets
+    pub resets_at: DateTime<Utc>,
+    /// Number of samples taken since the current window began
+    pub sample_count: usize,
+    /// Utilization growth in percentage points per hour (None with too few samples)
+    pub burn_rate_per_hour: Option<f64>,
+    /// Lower bound of the burn rate (95% confidence)
+    pub burn_rate_low: Option<f64>,
+    /// Upper bound of the burn rate (95% confidence)
+    pub burn_rate_high: Option<f64>,
+    /// Projected time of reaching 100% at the current pace (None if not increasing)
+    pub hits_limit_at: Option<DateTime<Utc>>,
+    /// Projected time of reaching 100% at the upper burn rate
+    pub hits_limit_earliest: Option<DateTime<Utc>>,
+    /// Projected time of reaching 100% at the lower burn rate
+    pub hits_limit_latest: Option<DateTime<Utc>>,
+    /// Projected utilization when the window resets, at the current pace
+    pub projected_at_reset: Option<f64>,
+    /// Whether the projection reaches 100% before the window resets
+    pub hits_before_reset: bool,
+}
+
+/// Forecasts for every limit of an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountForecast {
+    pub account_id: String,
+    pub account_name: String,
+    pub provider: String,
+    /// Time of the usage snapshot the forecast is based on
+    pub as_of: DateTime<Utc>,
+    pub limits: Vec<LimitForecast>,
+}
+
 /// Data retention policy
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RetentionPolicy {
-    /// Number of days to keep history (30, 60, 90, or 0 for unlimited)
+    /// Number of days to keep raw samples (30, 60, 90, or 0 for unlimited)
     pub retention_days: u32,
+    /// Number of months to keep hourly rollups (0 for unlimited).
+    /// Daily rollups are kept forever.
+    #[serde(default = "default_hourly_retention_months")]
+    pub hourly_retention_months: u32,
     /// Whether to auto-cleanup on app start
     pub auto_cleanup: bool,
 }
 
+fn default_hourly_retention_months() -> u32 {
+    6
+}
+
 impl Default for RetentionPolicy {
     fn default() -> Self {
         Self {
             retention_days: 30,
+            hourly_retention_months: default_hourly_retention_months(),
             auto_cleanup: true,
         }
     }
@@ -297,6 +703,7 @@ impl Default for AppSettings {
                 thresholds: vec![50, 75, 90],
                 notify_on_reset: true,
                 notify_on_expiry: true,
+                notify_on_projected_overrun: false,
                 dnd_enabled: false,
                 dnd_start_time: Some("22:00".to_string()),
                 dnd_end_time: Some("08:00".to_string()),
@@ -308,14 +715,18 @@ impl Default for AppSettings {
                     credentials: std::collections::HashMap::new(),
                 },
                 ProviderConfig {
-                    id: "codex".to_string(),
+                    id: "openai_api".to_string(),
                     enabled: false,
                     credentials: std::collections::HashMap::new(),
                 },
             ],
+            auto_import_browser_session: false,
             api_server_enabled: false,
             api_server_port: 31415,
             api_server_token: None,
+            api_metrics_public: false,
+            webhooks: Vec::new(),
+            custom_providers: Vec::new(),
         }
     }
 }
@@ -327,13 +738,19 @@ mod tests {
     #[test]
     fn provider_id_as_str() {
         assert_eq!(ProviderId::Claude.as_str(), "claude");
-        assert_eq!(ProviderId::Codex.as_str(), "codex");
+        assert_eq!(ProviderId::ClaudeCode.as_str(), "claude_code");
+        assert_eq!(ProviderId::AnthropicApi.as_str(), "anthropic_api");
+        assert_eq!(ProviderId::OpenAiApi.as_str(), "openai_api");
     }
 
     #[test]
     fn provider_id_display() {
         assert_eq!(format!("{}", ProviderId::Claude), "claude");
-        assert_eq!(format!("{}", ProviderId::Codex), "codex");
+        assert_eq!(format!("{}", ProviderId::OpenAiApi), "openai_api");
+        assert_eq!(
+            serde_json::to_string(&ProviderId::OpenAiApi).unwrap(),
+            "\"openai_api\""
+        );
     }
 
     #[test]
@@ -350,6 +767,7 @@ mod tests {
             org_id: Some("org-123".to_string()),
             session_key: Some("sk-xxx".to_string()),
             api_key: None,
+            ..Default::default()
         };
         let json = serde_json::to_string(&creds).unwrap();
         assert!(json.contains("org_id"));
@@ -367,13 +785,36 @@ mod tests {
         assert!(creds.api_key.is_none());
     }
 
+    #[test]
+    fn credentials_provider_fields() {
+        let json = r#"{"api_key":"sk-xxx","monthly_budget":250,"weekly_reset":"mon 09:00"}"#;
+        let creds: Credentials = serde_json::from_str(json).unwrap();
+        assert_eq!(creds.field::<f64>("monthly_budget"), Some(250.0));
+        assert_eq!(
+            creds.field::<String>("weekly_reset").as_deref(),
+            Some("mon 09:00")
+        );
+        // Missing or of another type
+        assert_eq!(creds.field::<u64>("counter_cap"), None);
+        assert_eq!(creds.field::<u64>("weekly_reset"), None);
+    }
+
     #[test]
     fn retention_policy_default() {
         let policy = RetentionPolicy::default();
         assert_eq!(policy.retention_days, 30);
+        assert_eq!(policy.hourly_retention_months, 6);
         assert!(policy.auto_cleanup);
     }
 
+    #[test]
+    fn retention_policy_without_hourly_tier_uses_default() {
+        let json = r#"{"retentionDays":60,"autoCleanup":false}"#;
+        let policy: RetentionPolicy = serde_json::from_str(json).unwrap();
+        assert_eq!(policy.retention_days, 60);
+        assert_eq!(policy.hourly_retention_months, 6);
+    }
+
     #[test]
     fn app_settings_default() {
         let settings = AppSettings::default();
@@ -390,6 +831,7 @@ mod tests {
         assert!(!settings.api_server_enabled);
         assert_eq!(settings.api_server_port, 31415);
         assert!(settings.api_server_token.is_none());
+        assert!(!settings.api_metrics_public);
     }
 
     #[test]
@@ -418,5 +860,17 @@ mod tests {
         assert!(query.end_date.is_none());
         assert_eq!(query.limit, Some(100));
         assert!(query.offset.is_none());
+        assert!(query.cursor.is_none());
+        assert_eq!(query.resolution, HistoryResolution::Raw);
+    }
+
+    #[test]
+    fn history_resolution_serialization() {
+        let query: HistoryQuery = serde_json::from_str(r#"{"resolution":"hourly"}"#).unwrap();
+        assert_eq!(query.resolution, HistoryResolution::Hourly);
+        assert_eq!(
+            serde_json::to_string(&HistoryResolution::Daily).unwrap(),
+            r#""daily""#
+        );
     }
 }
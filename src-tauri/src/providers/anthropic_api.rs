@@ -0,0 +1,703 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ProviderError;
+use crate::models::{Credentials, ProviderId, UsageData, UsageLimit};
+use crate::providers::{
+    get_report_page, host_of, month_bounds, CredentialField, ProviderMetadata, ProviderStatus,
+    UsageProvider,
+};
+
+const ANTHROPIC_API_BASE: &str = "https://api.anthropic.com/v1";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Daily buckets per page (a month fits in one page)
+const PAGE_LIMIT: &str = "31";
+/// Upper bound on pages followed per report
+const MAX_PAGES: usize = 10;
+
+/// Label used for cost/usage rows without a workspace
+const DEFAULT_WORKSPACE: &str = "default";
+
+/// Paginated Admin API report
+#[derive(Debug, Deserialize)]
+struct Report<T> {
+    data: Vec<ReportBucket<T>>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportBucket<T> {
+    results: Vec<T>,
+}
+
+/// Row of /organizations/cost_report grouped by workspace and description
+#[derive(Debug, Deserialize)]
+struct CostResult {
+    /// Cost in cents as a decimal string (e.g. "123.45" = $1.2345)
+    amount: String,
+    #[serde(default)]
+    workspace_id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+}
+
+/// Row of /organizations/usage_report/messages grouped by model and workspace
+#[derive(Debug, Deserialize)]
+struct MessagesUsageResult {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    uncached_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+    #[serde(default)]
+    cache_creation: Option<CacheCreation>,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct CacheCreation {
+    #[serde(default)]
+    ephemeral_1h_input_tokens: u64,
+    #[serde(default)]
+    ephemeral_5m_input_tokens: u64,
+}
+
+impl MessagesUsageResult {
+    fn input_tokens(&self) -> u64 {
+        let cache_creation = self
+            .cache_creation
+            .as_ref()
+            .map(|c| c.ephemeral_1h_input_tokens + c.ephemeral_5m_input_tokens)
+            .unwrap_or(0);
+        self.uncached_input_tokens + self.cache_read_input_tokens + cache_creation
+    }
+}
+
+/// Month-to-date spend summary (stored as `UsageData.raw`)
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpendSummary {
+    period_start: DateTime<Utc>,
+    budget_usd: f64,
+    spent_usd: f64,
+    models: Vec<ModelSpend>,
+    workspaces: Vec<WorkspaceSpend>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelSpend {
+    model: String,
+    cost_usd: f64,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceSpend {
+    workspace_id: String,
+    cost_usd: f64,
+}
+
+/// Anthropic API (Console) spend against a monthly budget, read with an admin key
+pub struct AnthropicApiProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl AnthropicApiProvider {
+    pub fn new() -> Result<Self, ProviderError> {
+        Self::with_base_url(ANTHROPIC_API_BASE)
+    }
+
+    /// Create a provider with a custom base URL (for testing)
+    pub fn with_base_url(base_url: &str) -> Result<Self, ProviderError> {
+        let client = reqwest::Client::builder()
+            .build()
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.to_string(),
+        })
+    }
+
+    /// Fetch every page of an Admin API report
+    async fn fetch_report<T: DeserializeOwned>(
+        &self,
+        api_key: &str,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Vec<T>, ProviderError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut results = Vec::new();
+        let mut page: Option<String> = None;
+
+        for _ in 0..MAX_PAGES {
+            let mut request = self
+                .client
+                .get(&url)
+                .header("x-api-key", api_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .query(params);
+            if let Some(ref page) = page {
+                request = request.query(&[("page", page)]);
+            }
+
+            let report: Report<T> = get_report_page(
+                request,
+                "anthropic_api",
+                path,
+                "Key lacks access to usage reports - an admin key (sk-ant-admin...) is required",
+            )
+            .await?;
+
+            results.extend(report.data.into_iter().flat_map(|bucket| bucket.results));
+
+            match report.next_page {
+                Some(next) if report.has_more => page = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Build limits from month-to-date cost and token usage
+    fn build_usage(
+        budget: f64,
+        period_start: DateTime<Utc>,
+        resets_at: DateTime<Utc>,
+        costs: &[CostResult],
+        usage: &[MessagesUsageResult],
+    ) -> Result<UsageData, ProviderError> {
+        let mut models: BTreeMap<String, ModelSpend> = BTreeMap::new();
+        let mut workspaces: BTreeMap<String, f64> = BTreeMap::new();
+        let mut spent = 0.0;
+
+        for cost in costs {
+            let cents: f64 = cost.amount.parse().map_err(|_| {
+                ProviderError::ParseError(format!("Invalid cost amount: {}", cost.amount))
+            })?;
+            let usd = cents / 100.0;
+            spent += usd;
+
+            if let Some(ref model) = cost.model {
+                models.entry(model.clone()).or_default().cost_usd += usd;
+            }
+            let workspace = cost.workspace_id.as_deref().unwrap_or(DEFAULT_WORKSPACE);
+            *workspaces.entry(workspace.to_string()).or_default() += usd;
+        }
+
+        for row in usage {
+            if let Some(ref model) = row.model {
+                let entry = models.entry(model.clone()).or_default();
+                entry.input_tokens += row.input_tokens();
+                entry.output_tokens += row.output_tokens;
+            }
+        }
+
+        let utilization = |usd: f64| usd / budget * 100.0;
+
+        let mut limits = vec![UsageLimit {
+            id: "monthly_budget".to_string(),
+            label: "Monthly Budget".to_string(),
+            utilization: utilization(spent),
+            resets_at,
+            category: None,
+        }];
+
+        for (model, spend) in &models {
+            limits.push(UsageLimit {
+                id: format!("model:{}", model),
+                label: model.clone(),
+                utilization: utilization(spend.cost_usd),
+                resets_at,
+                category: Some("model".to_string()),
+            });
+        }
+
+        for (workspace, usd) in &workspaces {
+            let label = if workspace == DEFAULT_WORKSPACE {
+                "Default Workspace".to_string()
+            } else {
+                format!("Workspace {}", workspace)
+            };
+            limits.push(UsageLimit {
+                id: format!("workspace:{}", workspace),
+                label,
+                utilization: utilization(*usd),
+                resets_at,
+                category: Some("workspace".to_string()),
+            });
+        }
+
+        let summary = SpendSummary {
+            period_start,
+            budget_usd: budget,
+            spent_usd: spent,
+            models: models
+                .into_iter()
+                .map(|(model, spend)| ModelSpend { model, ..spend })
+                .collect(),
+            workspaces: workspaces
+                .into_iter()
+                .map(|(workspace_id, cost_usd)| WorkspaceSpend {
+                    workspace_id,
+                    cost_usd,
+                })
+                .collect(),
+        };
+
+        Ok(UsageData {
+            provider: ProviderId::AnthropicApi.as_str().to_string(),
+            account_id: String::new(),   // Will be set by caller
+            account_name: String::new(), // Will be set by caller
+            timestamp: Utc::now(),
+            limits,
+            extra_usage: None,
+            raw: Some(serde_json::to_value(&summary).unwrap_or_default()),
+        })
+    }
+}
+
+impl Default for AnthropicApiProvider {
+    fn default() -> Self {
+        Self::new().expect("Failed to create Anthropic API provider")
+    }
+}
+
+#[async_trait]
+impl UsageProvider for AnthropicApiProvider {
+    fn id(&self) -> &str {
+        ProviderId::AnthropicApi.as_str()
+    }
+
+    fn name(&self) -> &str {
+        "Anthropic API"
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            status: ProviderStatus::Available,
+            required_credentials: vec![
+                CredentialField {
+                    key: "api_key".to_string(),
+                    label: "Admin API Key".to_string(),
+                    placeholder: "sk-ant-admin01-...".to_string(),
+                    is_secret: true,
+                    is_numeric: false,
+                    is_optional: false,
+                },
+                CredentialField {
+                    key: "monthly_budget".to_string(),
+                    label: "Monthly Budget (USD)".to_string(),
+                    placeholder: "500".to_string(),
+                    is_secret: false,
+                    is_numeric: true,
+                    is_optional: false,
+                },
+            ],
+            description: Some(
+                "Track month-to-date API spend per model and workspace against a budget. \
+                 Create an admin key in Console → Settings → Admin Keys."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError> {
+        let api_key = credentials
+            .api_key
+            .as_ref()
+            .ok_or_else(|| ProviderError::MissingCredentials("api_key".to_string()))?;
+
+        let budget = credentials
+            .field::<f64>("monthly_budget")
+            .filter(|b| *b > 0.0)
+            .ok_or_else(|| ProviderError::MissingCredentials("monthly_budget".to_string()))?;
+
+        let (period_start, resets_at) = month_bounds(Utc::now());
+        let starting_at = period_start.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+
+        log::info!(
+            "Fetching Anthropic API cost and usage since {}",
+            starting_at
+        );
+
+        let costs: Vec<CostResult> = self
+            .fetch_report(
+                api_key,
+                "/organizations/cost_report",
+                &[
+                    ("starting_at", starting_at.as_str()),
+                    ("group_by[]", "workspace_id"),
+                    ("group_by[]", "description"),
+                    ("limit", PAGE_LIMIT),
+                ],
+            )
+            .await?;
+
+        let usage: Vec<MessagesUsageResult> = self
+            .fetch_report(
+                api_key,
+                "/organizations/usage_report/messages",
+                &[
+                    ("starting_at", starting_at.as_str()),
+                    ("bucket_width", "1d"),
+                    ("group_by[]", "model"),
+                    ("group_by[]", "workspace_id"),
+                    ("limit", PAGE_LIMIT),
+                ],
+            )
+            .await?;
+
+        Self::build_usage(budget, period_start, resets_at, &costs, &usage)
+    }
+
+    fn validate_credentials(&self, credentials: &Credentials) -> bool {
+        credentials
+            .api_key
+            .as_ref()
+            .map(|s| !s.is_empty())
+            .unwrap_or(false)
+            && credentials
+                .field::<f64>("monthly_budget")
+                .is_some_and(|b| b > 0.0)
+    }
+
+    fn host(&self) -> Option<String> {
+        host_of(&self.base_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_credentials() -> Credentials {
+        Credentials {
+            api_key: Some("sk-ant-admin01-test".to_string()),
+            extra: HashMap::from([("monthly_budget".to_string(), serde_json::json!(100.0))]),
+            ..Default::default()
+        }
+    }
+
+    fn make_cost_report() -> serde_json::Value {
+        serde_json::json!({
+            "data": [
+                {
+                    "starting_at": "2025-01-01T00:00:00Z",
+                    "ending_at": "2025-01-02T00:00:00Z",
+                    "results": [
+                        {
+                            "currency": "USD",
+                            "amount": "2500.00",
+                            "workspace_id": "wrkspc_01",
+                            "description": "Claude Sonnet 4 Usage - Input Tokens",
+                            "cost_type": "tokens",
+                            "model": "claude-sonnet-4-20250514",
+                            "token_type": "uncached_input_tokens"
+                        },
+                        {
+                            "currency": "USD",
+                            "amount": "1000.00",
+                            "workspace_id": null,
+                            "description": "Claude Haiku 3.5 Usage - Output Tokens",
+                            "cost_type": "tokens",
+                            "model": "claude-3-5-haiku-20241022",
+                            "token_type": "output_tokens"
+                        }
+                    ]
+                },
+                {
+                    "starting_at": "2025-01-02T00:00:00Z",
+                    "ending_at": "2025-01-03T00:00:00Z",
+                    "results": [
+                        {
+                            "currency": "USD",
+                            "amount": "500.00",
+                            "workspace_id": "wrkspc_01",
+                            "description": "Web Search Usage",
+                            "cost_type": "web_search",
+                            "model": null
+                        }
+                    ]
+                }
+            ],
+            "has_more": false,
+            "next_page": null
+        })
+    }
+
+    fn make_messages_report() -> serde_json::Value {
+        serde_json::json!({
+            "data": [
+                {
+                    "starting_at": "2025-01-01T00:00:00Z",
+                    "ending_at": "2025-01-02T00:00:00Z",
+                    "results": [
+                        {
+                            "uncached_input_tokens": 1000,
+                            "cache_creation": {
+                                "ephemeral_1h_input_tokens": 100,
+                                "ephemeral_5m_input_tokens": 50
+                            },
+                            "cache_read_input_tokens": 200,
+                            "output_tokens": 400,
+                            "workspace_id": "wrkspc_01",
+                            "model": "claude-sonnet-4-20250514"
+                        }
+                    ]
+                }
+            ],
+            "has_more": false,
+            "next_page": null
+        })
+    }
+
+    async fn mount_reports(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/organizations/cost_report"))
+            .and(header("x-api-key", "sk-ant-admin01-test"))
+            .and(header("anthropic-version", ANTHROPIC_VERSION))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_cost_report()))
+            .expect(1)
+            .mount(server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/usage_report/messages"))
+            .and(query_param("bucket_width", "1d"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_messages_report()))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    // ============================================================================
+    // Integration tests with mocked HTTP
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_fetch_usage_maps_spend_to_limits() {
+        let mock_server = MockServer::start().await;
+        mount_reports(&mock_server).await;
+
+        let provider = AnthropicApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let usage = provider.fetch_usage(&make_credentials()).await.unwrap();
+
+        assert_eq!(usage.provider, "anthropic_api");
+
+        // $25 + $10 + $5 of a $100 budget
+        let total = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "monthly_budget")
+            .unwrap();
+        assert!((total.utilization - 40.0).abs() < 0.001);
+
+        let sonnet = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "model:claude-sonnet-4-20250514")
+            .unwrap();
+        assert!((sonnet.utilization - 25.0).abs() < 0.001);
+        assert_eq!(sonnet.category, Some("model".to_string()));
+
+        let workspace = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "workspace:wrkspc_01")
+            .unwrap();
+        assert!((workspace.utilization - 30.0).abs() < 0.001);
+
+        let default = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "workspace:default")
+            .unwrap();
+        assert_eq!(default.label, "Default Workspace");
+        assert!((default.utilization - 10.0).abs() < 0.001);
+
+        // All limits reset at the start of next month
+        let (_, next_month) = month_bounds(Utc::now());
+        assert!(usage.limits.iter().all(|l| l.resets_at == next_month));
+
+        // Token totals are kept in the raw summary
+        let raw = usage.raw.unwrap();
+        let models = raw["models"].as_array().unwrap();
+        let sonnet = models
+            .iter()
+            .find(|m| m["model"] == "claude-sonnet-4-20250514")
+            .unwrap();
+        assert_eq!(sonnet["inputTokens"], 1350);
+        assert_eq!(sonnet["outputTokens"], 400);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_follows_pages() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/cost_report"))
+            .and(query_param("page", "page_2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "results": [{ "amount": "5000", "workspace_id": null, "model": null }] }],
+                "has_more": false,
+                "next_page": null
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/cost_report"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "results": [{ "amount": "1000", "workspace_id": null, "model": null }] }],
+                "has_more": true,
+                "next_page": "page_2"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/usage_report/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [],
+                "has_more": false,
+                "next_page": null
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = AnthropicApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let usage = provider.fetch_usage(&make_credentials()).await.unwrap();
+
+        let total = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "monthly_budget")
+            .unwrap();
+        assert!((total.utilization - 60.0).abs() < 0.001);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_401_invalid_key() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/cost_report"))
+            .respond_with(ResponseTemplate::new(401))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = AnthropicApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let result = provider.fetch_usage(&make_credentials()).await;
+
+        match result.unwrap_err() {
+            ProviderError::InvalidCredentials(msg) => assert!(msg.contains("401")),
+            err => panic!("Expected InvalidCredentials, got {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_429_rate_limited() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/cost_report"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = AnthropicApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let result = provider.fetch_usage(&make_credentials()).await;
+
+        match result.unwrap_err() {
+            ProviderError::RateLimited(_) => {}
+            err => panic!("Expected RateLimited, got {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_invalid_amount() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/cost_report"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "results": [{ "amount": "lots" }] }],
+                "has_more": false
+            })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/usage_report/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_messages_report()))
+            .mount(&mock_server)
+            .await;
+
+        let provider = AnthropicApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let result = provider.fetch_usage(&make_credentials()).await;
+
+        match result.unwrap_err() {
+            ProviderError::ParseError(msg) => assert!(msg.contains("lots")),
+            err => panic!("Expected ParseError, got {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_missing_budget() {
+        let provider = AnthropicApiProvider::new().unwrap();
+        let mut credentials = make_credentials();
+        credentials.extra.remove("monthly_budget");
+
+        match provider.fetch_usage(&credentials).await.unwrap_err() {
+            ProviderError::MissingCredentials(field) => assert_eq!(field, "monthly_budget"),
+            err => panic!("Expected MissingCredentials, got {:?}", err),
+        }
+    }
+
+    // ============================================================================
+    // Unit tests
+    // ============================================================================
+
+    #[test]
+    fn test_validate_credentials() {
+        let provider = AnthropicApiProvider::new().unwrap();
+        assert!(provider.validate_credentials(&make_credentials()));
+
+        let no_key = Credentials {
+            api_key: Some(String::new()),
+            ..make_credentials()
+        };
+        assert!(!provider.validate_credentials(&no_key));
+
+        let mut zero_budget = make_credentials();
+        zero_budget
+            .extra
+            .insert("monthly_budget".to_string(), serde_json::json!(0.0));
+        assert!(!provider.validate_credentials(&zero_budget));
+    }
+}
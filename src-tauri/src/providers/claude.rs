@@ -1,10 +1,16 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use reqwest::header::{HeaderMap, HeaderValue, COOKIE, ORIGIN, REFERER, USER_AGENT};
+use serde_json::Value;
 
 use crate::error::ProviderError;
-use crate::models::{ClaudeUsageResponse, Credentials, UsageData, UsageLimit};
-use crate::providers::UsageProvider;
+use crate::models::{
+    ClaudeExtraUsage, ClaudeOrganization, ClaudeUsageResponse, Credentials, ExtraUsage, LimitUsage,
+    ProviderId, UsageData, UsageLimit,
+};
+use crate::providers::{
+    host_of, retry, schema, CredentialField, ProviderMetadata, ProviderStatus, UsageProvider,
+};
 
 const CLAUDE_API_BASE: &str = "https://claude.ai/api";
 
@@ -82,66 +88,88 @@ impl ClaudeProvider {
         headers
     }
 
-    /// Parse API response into UsageData
-    fn parse_response(&self, response: ClaudeUsageResponse) -> Result<UsageData, ProviderError> {
-        let mut limits = Vec::new();
+    /// List the organizations a session key belongs to
+    pub async fn list_organizations(
+        &self,
+        session_key: &str,
+    ) -> Result<Vec<ClaudeOrganization>, ProviderError> {
+        let url = format!("{}/organizations", self.base_url);
 
-        // 5-hour limit (may be absent when no usage)
-        if let Some(ref limit) = response.five_hour {
-            if let Some(parsed) = self.parse_limit("five_hour", "5-Hour Limit", limit, None)? {
-                limits.push(parsed);
-            }
-        }
+        let response = self
+            .client
+            .get(&url)
+            .headers(self.build_headers(session_key))
+            .send()
+            .await
+            .map_err(|e| ProviderError::Network(e.to_string()))?;
 
-        // Weekly limit (optional)
-        if let Some(ref limit) = response.seven_day {
-            if let Some(parsed) = self.parse_limit("seven_day", "Weekly Limit", limit, None)? {
-                limits.push(parsed);
+        let status = response.status();
+        match status.as_u16() {
+            200 => response
+                .json()
+                .await
+                .map_err(|e| ProviderError::ParseError(e.to_string())),
+            401 => Err(ProviderError::SessionExpired),
+            403 => Err(ProviderError::CloudflareBlocked),
+            429 => Err(retry::rate_limited(response.headers())),
+            _ => {
+                let body = response.text().await.unwrap_or_default();
+                Err(retry::status_error(status, body))
             }
         }
+    }
 
-        // Opus limit (optional)
-        if let Some(ref limit) = response.seven_day_opus {
-            if let Some(parsed) = self.parse_limit(
-                "seven_day_opus",
-                "Weekly Opus",
-                limit,
-                Some("opus"),
-            )? {
-                limits.push(parsed);
-            }
-        }
+    /// Parse API response into UsageData
+    ///
+    /// Every field with a `utilization`/`resets_at` shape is a limit; known IDs
+    /// get their label from `KNOWN_LIMITS`, others a generated one.
+    fn parse_response(&self, response: ClaudeUsageResponse) -> Result<UsageData, ProviderError> {
+        let mut limits = Vec::new();
 
-        // Sonnet limit (optional)
-        if let Some(ref limit) = response.seven_day_sonnet {
-            if let Some(parsed) = self.parse_limit(
-                "seven_day_sonnet",
-                "Weekly Sonnet",
-                limit,
-                Some("sonnet"),
-            )? {
-                limits.push(parsed);
+        for (id, value) in &response.fields {
+            if !is_limit_shaped(value) {
+                continue;
             }
-        }
-
-        // OAuth apps limit (optional)
-        if let Some(ref limit) = response.seven_day_oauth_apps {
-            if let Some(parsed) = self.parse_limit(
-                "seven_day_oauth_apps",
-                "Weekly OAuth Apps",
-                limit,
-                Some("oauth"),
-            )? {
-                limits.push(parsed);
+            let (label, category) = limit_label(id);
+            let parsed = serde_json::from_value::<LimitUsage>(value.clone())
+                .map_err(|e| ProviderError::ParseError(format!("Invalid limit {}: {}", id, e)))
+                .and_then(|usage| self.parse_limit(id, &label, &usage, category.as_deref()));
+
+            match parsed {
+                Ok(Some(limit)) => limits.push(limit),
+                Ok(None) => {}
+                // A new field that merely looks like a limit shouldn't break the known ones
+                Err(e) if !KNOWN_LIMITS.iter().any(|(known, _, _)| known == id) => {
+                    log::warn!("Skipping unrecognized Claude field {}: {}", id, e);
+                }
+                Err(e) => return Err(e),
             }
         }
 
+        // Known limits in their usual order, then any new ones by ID
+        limits.sort_by_key(|limit| {
+            let known = KNOWN_LIMITS.iter().position(|(id, _, _)| *id == limit.id);
+            (known.unwrap_or(KNOWN_LIMITS.len()), limit.id.clone())
+        });
+
+        let extra_usage = match response.fields.get("extra_usage") {
+            None | Some(Value::Null) => None,
+            Some(value) => match serde_json::from_value::<ClaudeExtraUsage>(value.clone()) {
+                Ok(extra) => Some(ExtraUsage::from(extra)),
+                Err(e) => {
+                    log::warn!("Ignoring unrecognized Claude extra_usage: {}", e);
+                    None
+                }
+            },
+        };
+
         Ok(UsageData {
-            provider: "claude".to_string(),
+            provider: ProviderId::Claude.as_str().to_string(),
             account_id: String::new(),  // Will be set by caller
             account_name: String::new(), // Will be set by caller
             timestamp: Utc::now(),
             limits,
+            extra_usage,
             raw: Some(serde_json::to_value(&response).unwrap_or_default()),
         })
     }
@@ -151,7 +179,7 @@ impl ClaudeProvider {
         &self,
         id: &str,
         label: &str,
-        usage: &crate::models::LimitUsage,
+        usage: &LimitUsage,
         category: Option<&str>,
     ) -> Result<Option<UsageLimit>, ProviderError> {
         // When utilization is 0%, resets_at is null - skip this limit
@@ -174,6 +202,56 @@ impl ClaudeProvider {
     }
 }
 
+/// Limits we know by ID: (id, label, category)
+const KNOWN_LIMITS: &[(&str, &str, Option<&str>)] = &[
+    ("five_hour", "5-Hour Limit", None),
+    ("seven_day", "Weekly Limit", None),
+    ("seven_day_opus", "Weekly Opus", Some("opus")),
+    ("seven_day_sonnet", "Weekly Sonnet", Some("sonnet")),
+    ("seven_day_oauth_apps", "Weekly OAuth Apps", Some("oauth")),
+];
+
+/// Whether a response field looks like a limit (`utilization` number + `resets_at`)
+fn is_limit_shaped(value: &Value) -> bool {
+    value.get("utilization").is_some_and(Value::is_number)
+        && value
+            .get("resets_at")
+            .is_some_and(|v| v.is_string() || v.is_null())
+}
+
+/// Label and category for a limit ID
+///
+/// Unknown IDs get a generated label: "seven_day_haiku" becomes "Weekly Haiku"
+/// (category "haiku"), "iguana_necktie" becomes "Iguana Necktie".
+fn limit_label(id: &str) -> (String, Option<String>) {
+    if let Some((_, label, category)) = KNOWN_LIMITS.iter().find(|(known, _, _)| *known == id) {
+        return (label.to_string(), category.map(String::from));
+    }
+
+    for (prefix, window) in [("five_hour_", "5-Hour"), ("seven_day_", "Weekly")] {
+        if let Some(rest) = id.strip_prefix(prefix) {
+            return (format!("{} {}", window, title_case(rest)), Some(rest.to_string()));
+        }
+    }
+
+    (title_case(id), None)
+}
+
+/// "oauth_apps" -> "Oauth Apps"
+fn title_case(id: &str) -> String {
+    id.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 impl Default for ClaudeProvider {
     fn default() -> Self {
         Self::new().expect("Failed to create Claude provider")
@@ -182,14 +260,45 @@ impl Default for ClaudeProvider {
 
 #[async_trait]
 impl UsageProvider for ClaudeProvider {
-    fn id(&self) -> &'static str {
-        "claude"
+    fn id(&self) -> &str {
+        ProviderId::Claude.as_str()
     }
 
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "Claude"
     }
 
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            status: ProviderStatus::Available,
+            required_credentials: vec![
+                CredentialField {
+                    key: "org_id".to_string(),
+                    label: "Organization ID".to_string(),
+                    placeholder: "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx".to_string(),
+                    is_secret: false,
+                    is_numeric: false,
+                    is_optional: false,
+                },
+                CredentialField {
+                    key: "session_key".to_string(),
+                    label: "Session Key".to_string(),
+                    placeholder: "sk-ant-sid01-...".to_string(),
+                    is_secret: true,
+                    is_numeric: false,
+                    is_optional: false,
+                },
+            ],
+            description: Some(
+                "Monitor your Claude Pro/Max usage limits. \
+                 Get credentials from claude.ai DevTools."
+                    .to_string(),
+            ),
+        }
+    }
+
     async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError> {
         let org_id = credentials
             .org_id
@@ -212,7 +321,7 @@ impl UsageProvider for ClaudeProvider {
             .headers(headers)
             .send()
             .await
-            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+            .map_err(|e| ProviderError::Network(e.to_string()))?;
 
         let status = response.status();
         log::info!("Claude API response status: {}", status);
@@ -226,7 +335,9 @@ impl UsageProvider for ClaudeProvider {
 
                 log::info!("Claude API raw response: {}", &text[..text.len().min(1000)]);
 
-                let body: ClaudeUsageResponse = serde_json::from_str(&text)
+                let body: ClaudeUsageResponse = serde_json::from_str::<Value>(&text)
+                    .inspect(|value| schema::observe(self.id(), "usage", value))
+                    .and_then(serde_json::from_value)
                     .map_err(|e| {
                         log::error!("Failed to parse Claude response: {}. Body: {}", e, text);
                         ProviderError::ParseError(format!("{} - Response: {}", e, &text[..text.len().min(500)]))
@@ -236,13 +347,10 @@ impl UsageProvider for ClaudeProvider {
             }
             401 => Err(ProviderError::SessionExpired),
             403 => Err(ProviderError::CloudflareBlocked),
-            429 => Err(ProviderError::RateLimited),
+            429 => Err(retry::rate_limited(response.headers())),
             _ => {
                 let body = response.text().await.unwrap_or_default();
-                Err(ProviderError::HttpError(format!(
-                    "Unexpected status {}: {}",
-                    status, body
-                )))
+                Err(retry::status_error(status, body))
             }
         }
     }
@@ -255,6 +363,10 @@ impl UsageProvider for ClaudeProvider {
                 .map(|s| !s.is_empty())
                 .unwrap_or(false)
     }
+
+    fn host(&self) -> Option<String> {
+        host_of(&self.base_url)
+    }
 }
 
 #[cfg(test)]
@@ -268,6 +380,7 @@ mod tests {
             org_id: Some("test-org-123".to_string()),
             session_key: Some("sk-test-session-key".to_string()),
             api_key: None,
+            ..Default::default()
         }
     }
 
@@ -386,11 +499,31 @@ mod tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            ProviderError::RateLimited => {}
+            ProviderError::RateLimited(None) => {}
             err => panic!("Expected RateLimited, got {:?}", err),
         }
     }
 
+    #[tokio::test]
+    async fn test_fetch_usage_429_retry_after() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/test-org-123/usage"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "90"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&mock_server.uri()).unwrap();
+        let result = provider.fetch_usage(&make_credentials()).await;
+
+        match result.unwrap_err() {
+            ProviderError::RateLimited(Some(wait)) => assert_eq!(wait.as_secs(), 90),
+            err => panic!("Expected RateLimited with Retry-After, got {:?}", err),
+        }
+    }
+
     #[tokio::test]
     async fn test_fetch_usage_500_server_error() {
         let mock_server = MockServer::start().await;
@@ -409,10 +542,10 @@ mod tests {
 
         assert!(result.is_err());
         match result.unwrap_err() {
-            ProviderError::HttpError(msg) => {
-                assert!(msg.contains("500"));
+            ProviderError::ServerError(500, body) => {
+                assert_eq!(body, "Internal Server Error");
             }
-            err => panic!("Expected HttpError, got {:?}", err),
+            err => panic!("Expected ServerError, got {:?}", err),
         }
     }
 
@@ -448,6 +581,7 @@ mod tests {
             org_id: None,
             session_key: Some("sk-test".to_string()),
             api_key: None,
+            ..Default::default()
         };
 
         let result = provider.fetch_usage(&credentials).await;
@@ -468,6 +602,7 @@ mod tests {
             org_id: Some("org-123".to_string()),
             session_key: None,
             api_key: None,
+            ..Default::default()
         };
 
         let result = provider.fetch_usage(&credentials).await;
@@ -577,6 +712,110 @@ mod tests {
         assert_eq!(oauth.category, Some("oauth".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_fetch_usage_unknown_limits_and_extra_usage() {
+        let mock_server = MockServer::start().await;
+
+        let response = serde_json::json!({
+            "iguana_necktie": {
+                "utilization": 12.0,
+                "resets_at": "2025-01-20T00:00:00Z"
+            },
+            "seven_day_haiku": {
+                "utilization": 40.0,
+                "resets_at": "2025-01-20T00:00:00Z"
+            },
+            "five_hour": {
+                "utilization": 50.0,
+                "resets_at": "2025-01-15T17:00:00Z"
+            },
+            "extra_usage": {
+                "is_enabled": true,
+                "monthly_limit": 5000,
+                "used_credits": 1250,
+                "utilization": 25.0
+            },
+            "some_flag": true
+        });
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/test-org-123/usage"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&mock_server.uri()).unwrap();
+        let usage = provider.fetch_usage(&make_credentials()).await.unwrap();
+
+        // Known limits first, then new ones by ID; extra_usage is not a limit
+        let ids: Vec<&str> = usage.limits.iter().map(|l| l.id.as_str()).collect();
+        assert_eq!(ids, vec!["five_hour", "iguana_necktie", "seven_day_haiku"]);
+
+        let haiku = &usage.limits[2];
+        assert_eq!(haiku.label, "Weekly Haiku");
+        assert_eq!(haiku.category, Some("haiku".to_string()));
+        assert_eq!(usage.limits[1].label, "Iguana Necktie");
+
+        let extra = usage.extra_usage.unwrap();
+        assert!(extra.is_enabled);
+        assert_eq!(extra.monthly_limit, Some(5000.0));
+        assert_eq!(extra.used_credits, Some(1250.0));
+        assert_eq!(extra.utilization, Some(25.0));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_skips_unparseable_unknown_limits() {
+        let mock_server = MockServer::start().await;
+
+        let response = serde_json::json!({
+            "five_hour": {
+                "utilization": 50.0,
+                "resets_at": "2025-01-15T17:00:00Z"
+            },
+            "iguana_necktie": {
+                "utilization": 12.0,
+                "resets_at": "next tuesday"
+            }
+        });
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/test-org-123/usage"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&mock_server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&mock_server.uri()).unwrap();
+        let usage = provider.fetch_usage(&make_credentials()).await.unwrap();
+
+        let ids: Vec<&str> = usage.limits.iter().map(|l| l.id.as_str()).collect();
+        assert_eq!(ids, vec!["five_hour"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_invalid_known_limit_fails() {
+        let mock_server = MockServer::start().await;
+
+        let response = serde_json::json!({
+            "five_hour": {
+                "utilization": 50.0,
+                "resets_at": "next tuesday"
+            }
+        });
+
+        Mock::given(method("GET"))
+            .and(path("/organizations/test-org-123/usage"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&mock_server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&mock_server.uri()).unwrap();
+        match provider.fetch_usage(&make_credentials()).await.unwrap_err() {
+            ProviderError::ParseError(msg) => assert!(msg.contains("Invalid date format")),
+            err => panic!("Expected ParseError, got {:?}", err),
+        }
+    }
+
     #[tokio::test]
     async fn test_headers_are_browser_like() {
         let mock_server = MockServer::start().await;
@@ -598,6 +837,50 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_list_organizations() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .and(header("cookie", "sessionKey=sk-ant-sid01-test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {
+                    "uuid": "org-1",
+                    "name": "Personal",
+                    "capabilities": ["chat", "claude_pro"],
+                    "billing_type": "stripe_subscription"
+                },
+                { "uuid": "org-2", "name": "API" }
+            ])))
+            .mount(&mock_server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&mock_server.uri()).unwrap();
+        let orgs = provider.list_organizations("sk-ant-sid01-test").await.unwrap();
+
+        assert_eq!(orgs.len(), 2);
+        assert_eq!(orgs[0].uuid, "org-1");
+        assert_eq!(orgs[0].capabilities, vec!["chat", "claude_pro"]);
+        assert!(orgs[1].capabilities.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_organizations_401_session_expired() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&mock_server.uri()).unwrap();
+        let result = provider.list_organizations("sk-ant-sid01-expired").await;
+
+        assert!(matches!(result, Err(ProviderError::SessionExpired)));
+    }
+
     // ============================================================================
     // Unit tests for validate_credentials
     // ============================================================================
@@ -616,6 +899,7 @@ mod tests {
             org_id: None,
             session_key: Some("sk-test".to_string()),
             api_key: None,
+            ..Default::default()
         };
         assert!(!provider.validate_credentials(&credentials));
     }
@@ -627,6 +911,7 @@ mod tests {
             org_id: Some("org-123".to_string()),
             session_key: None,
             api_key: None,
+            ..Default::default()
         };
         assert!(!provider.validate_credentials(&credentials));
     }
@@ -638,10 +923,24 @@ mod tests {
             org_id: Some("".to_string()),
             session_key: Some("".to_string()),
             api_key: None,
+            ..Default::default()
         };
         assert!(!provider.validate_credentials(&credentials));
     }
 
+    #[test]
+    fn test_limit_label() {
+        assert_eq!(
+            limit_label("seven_day_opus"),
+            ("Weekly Opus".to_string(), Some("opus".to_string()))
+        );
+        assert_eq!(
+            limit_label("five_hour_opus"),
+            ("5-Hour Opus".to_string(), Some("opus".to_string()))
+        );
+        assert_eq!(limit_label("monthly"), ("Monthly".to_string(), None));
+    }
+
     #[test]
     fn test_provider_id_and_name() {
         let provider = ClaudeProvider::new().unwrap();
@@ -0,0 +1,597 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ProviderError;
+use crate::models::{Credentials, ProviderId, UsageData, UsageLimit};
+use crate::providers::{CredentialField, ProviderMetadata, ProviderStatus, UsageProvider};
+
+/// Length of a Claude Code session block
+const BLOCK_HOURS: i64 = 5;
+
+/// One line of a Claude Code transcript (only the fields we need)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptLine {
+    #[serde(default)]
+    timestamp: Option<DateTime<Utc>>,
+    #[serde(default)]
+    request_id: Option<String>,
+    #[serde(default)]
+    message: Option<TranscriptMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptMessage {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    usage: Option<TranscriptUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+}
+
+/// Token usage of one assistant message
+#[derive(Debug, Clone)]
+struct TokenEntry {
+    timestamp: DateTime<Utc>,
+    tokens: TokenTotals,
+}
+
+/// Token counts summed over a window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenTotals {
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+}
+
+impl TokenTotals {
+    fn add(&mut self, other: &TokenTotals) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+    }
+
+    fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+/// A 5-hour block of activity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Block {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    tokens: TokenTotals,
+}
+
+/// Token summary (stored as `UsageData.raw`)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptSummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    five_hour: Option<Block>,
+    week_start: DateTime<Utc>,
+    week: TokenTotals,
+    files_scanned: usize,
+}
+
+/// Estimates Claude usage from local Claude Code transcripts (no network access)
+pub struct ClaudeCodeProvider {
+    /// Used when the account does not set `projects_dir`
+    default_dir: Option<PathBuf>,
+}
+
+impl ClaudeCodeProvider {
+    pub fn new() -> Self {
+        Self {
+            default_dir: dirs::home_dir().map(|home| home.join(".claude").join("projects")),
+        }
+    }
+
+    fn projects_dir(&self, credentials: &Credentials) -> Option<PathBuf> {
+        credentials
+            .field::<String>("projects_dir")
+            .filter(|dir| !dir.trim().is_empty())
+            .map(|dir| PathBuf::from(dir.trim()))
+            .or_else(|| self.default_dir.clone())
+    }
+}
+
+impl Default for ClaudeCodeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collect `.jsonl` files under `dir` modified since `since`
+///
+/// Symlinked directories are not followed, so a link back up the tree can't
+/// recurse forever.
+fn collect_transcripts(dir: &Path, since: SystemTime, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_transcripts(&path, since, files);
+        } else if file_type.is_symlink() && path.is_dir() {
+            log::debug!("Skipping symlinked directory {}", path.display());
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            let modified = entry.metadata().and_then(|m| m.modified());
+            if modified.map_or(true, |modified| modified >= since) {
+                files.push(path);
+            }
+        }
+    }
+}
+
+/// Parse token usage from transcript contents, skipping entries before `since`
+/// and messages already seen (resumed sessions repeat earlier messages)
+fn parse_transcript(
+    contents: &str,
+    since: DateTime<Utc>,
+    seen: &mut HashSet<String>,
+    entries: &mut Vec<TokenEntry>,
+) {
+    for line in contents.lines() {
+        let Ok(parsed) = serde_json::from_str::<TranscriptLine>(line) else {
+            continue;
+        };
+        let (Some(timestamp), Some(message)) = (parsed.timestamp, parsed.message) else {
+            continue;
+        };
+        let Some(usage) = message.usage else {
+            continue;
+        };
+        if timestamp < since {
+            continue;
+        }
+
+        if let (Some(id), Some(request_id)) = (message.id, parsed.request_id) {
+            if !seen.insert(format!("{}:{}", id, request_id)) {
+                continue;
+            }
+        }
+
+        entries.push(TokenEntry {
+            timestamp,
+            tokens: TokenTotals {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_tokens: usage.cache_creation_input_tokens,
+                cache_read_tokens: usage.cache_read_input_tokens,
+            },
+        });
+    }
+}
+
+/// The block still running at `now`, if any. A block starts at the hour of its
+/// first message and lasts 5 hours; the first message after it starts the next.
+fn current_block(entries: &[TokenEntry], now: DateTime<Utc>) -> Option<Block> {
+    let mut block: Option<Block> = None;
+
+    for entry in entries {
+        let starts_new = block
+            .as_ref()
+            .map_or(true, |block| entry.timestamp >= block.end);
+        if starts_new {
+            let start = entry
+                .timestamp
+                .duration_trunc(Duration::hours(1))
+                .unwrap_or(entry.timestamp);
+            block = Some(Block {
+                start,
+                end: start + Duration::hours(BLOCK_HOURS),
+                tokens: TokenTotals::default(),
+            });
+        }
+        if let Some(ref mut block) = block {
+            block.tokens.add(&entry.tokens);
+        }
+    }
+
+    block.filter(|block| now < block.end)
+}
+
+/// Monday 00:00 UTC of the week containing `now`
+fn week_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let days = now.weekday().num_days_from_monday() as i64;
+    (now.date_naive() - Duration::days(days))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
+/// Build limits from parsed entries (sorted by timestamp)
+fn build_usage(
+    entries: &[TokenEntry],
+    now: DateTime<Utc>,
+    five_hour_cap: Option<u64>,
+    weekly_cap: Option<u64>,
+    files_scanned: usize,
+) -> UsageData {
+    let week_start = week_start(now);
+    let mut week = TokenTotals::default();
+    for entry in entries.iter().filter(|e| e.timestamp >= week_start) {
+        week.add(&entry.tokens);
+    }
+
+    let five_hour = current_block(entries, now);
+    let mut limits = Vec::new();
+
+    // Like the web limits, the 5-hour limit is absent when no block is running
+    if let (Some(cap), Some(ref block)) = (five_hour_cap, &five_hour) {
+        limits.push(UsageLimit {
+            id: "five_hour".to_string(),
+            label: "5-Hour Block (est.)".to_string(),
+            utilization: block.tokens.total() as f64 / cap as f64 * 100.0,
+            resets_at: block.end,
+            category: None,
+        });
+    }
+
+    if let Some(cap) = weekly_cap {
+        limits.push(UsageLimit {
+            id: "seven_day".to_string(),
+            label: "Weekly (est.)".to_string(),
+            utilization: week.total() as f64 / cap as f64 * 100.0,
+            resets_at: week_start + Duration::days(7),
+            category: None,
+        });
+    }
+
+    let summary = TranscriptSummary {
+        five_hour,
+        week_start,
+        week,
+        files_scanned,
+    };
+
+    UsageData {
+        provider: ProviderId::ClaudeCode.as_str().to_string(),
+        account_id: String::new(),   // Will be set by caller
+        account_name: String::new(), // Will be set by caller
+        timestamp: now,
+        limits,
+        extra_usage: None,
+        raw: Some(serde_json::to_value(&summary).unwrap_or_default()),
+    }
+}
+
+/// Read all recent transcripts under `dir`
+fn scan(dir: &Path, since: DateTime<Utc>) -> (Vec<TokenEntry>, usize) {
+    let mut files = Vec::new();
+    collect_transcripts(dir, SystemTime::from(since), &mut files);
+
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for file in &files {
+        match fs::read_to_string(file) {
+            Ok(contents) => parse_transcript(&contents, since, &mut seen, &mut entries),
+            Err(e) => log::warn!("Failed to read transcript {}: {}", file.display(), e),
+        }
+    }
+
+    entries.sort_by_key(|e| e.timestamp);
+    (entries, files.len())
+}
+
+#[async_trait]
+impl UsageProvider for ClaudeCodeProvider {
+    fn id(&self) -> &str {
+        ProviderId::ClaudeCode.as_str()
+    }
+
+    fn name(&self) -> &str {
+        "Claude Code"
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            status: ProviderStatus::Available,
+            required_credentials: vec![
+                CredentialField {
+                    key: "five_hour_token_cap".to_string(),
+                    label: "5-Hour Token Cap".to_string(),
+                    placeholder: "20000000".to_string(),
+                    is_secret: false,
+                    is_numeric: true,
+                    is_optional: true,
+                },
+                CredentialField {
+                    key: "weekly_token_cap".to_string(),
+                    label: "Weekly Token Cap".to_string(),
+                    placeholder: "300000000".to_string(),
+                    is_secret: false,
+                    is_numeric: true,
+                    is_optional: true,
+                },
+                CredentialField {
+                    key: "projects_dir".to_string(),
+                    label: "Projects Directory".to_string(),
+                    placeholder: "~/.claude/projects".to_string(),
+                    is_secret: false,
+                    is_numeric: false,
+                    is_optional: true,
+                },
+            ],
+            description: Some(
+                "Estimate usage from local Claude Code transcripts - no network or session key. \
+                 Set at least one token cap for your plan."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError> {
+        let five_hour_cap = credentials
+            .field::<u64>("five_hour_token_cap")
+            .filter(|cap| *cap > 0);
+        let weekly_cap = credentials
+            .field::<u64>("weekly_token_cap")
+            .filter(|cap| *cap > 0);
+        if five_hour_cap.is_none() && weekly_cap.is_none() {
+            return Err(ProviderError::MissingCredentials(
+                "five_hour_token_cap".to_string(),
+            ));
+        }
+
+        let dir = self
+            .projects_dir(credentials)
+            .ok_or_else(|| ProviderError::MissingCredentials("projects_dir".to_string()))?;
+        if !dir.is_dir() {
+            return Err(ProviderError::InvalidCredentials(format!(
+                "Claude Code projects directory not found: {}",
+                dir.display()
+            )));
+        }
+
+        let now = Utc::now();
+        // Enough history to place the current block and cover the whole week
+        let since = week_start(now).min(now - Duration::days(1));
+
+        log::info!("Scanning Claude Code transcripts in {}", dir.display());
+
+        let (entries, files_scanned) = tokio::task::spawn_blocking(move || scan(&dir, since))
+            .await
+            .map_err(|e| ProviderError::ParseError(format!("Transcript scan failed: {}", e)))?;
+
+        Ok(build_usage(
+            &entries,
+            now,
+            five_hour_cap,
+            weekly_cap,
+            files_scanned,
+        ))
+    }
+
+    fn validate_credentials(&self, credentials: &Credentials) -> bool {
+        ["five_hour_token_cap", "weekly_token_cap"]
+            .iter()
+            .any(|key| credentials.field::<u64>(key).is_some_and(|cap| cap > 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn line(timestamp: &str, id: &str, input: u64, output: u64) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "timestamp": timestamp,
+            "requestId": format!("req_{}", id),
+            "message": {
+                "id": format!("msg_{}", id),
+                "model": "claude-sonnet-4-20250514",
+                "usage": {
+                    "input_tokens": input,
+                    "output_tokens": output,
+                    "cache_creation_input_tokens": 10,
+                    "cache_read_input_tokens": 100
+                }
+            }
+        })
+        .to_string()
+    }
+
+    fn entries(contents: &str) -> Vec<TokenEntry> {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        let since = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        parse_transcript(contents, since, &mut seen, &mut entries);
+        entries
+    }
+
+    fn credentials(dir: &Path) -> Credentials {
+        Credentials {
+            extra: HashMap::from([
+                ("projects_dir".to_string(), json!(dir.to_string_lossy())),
+                ("five_hour_token_cap".to_string(), json!(1_000)),
+                ("weekly_token_cap".to_string(), json!(10_000)),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_transcript_sums_tokens_and_skips_duplicates() {
+        let contents = [
+            line("2025-01-15T10:05:00Z", "a", 100, 50),
+            line("2025-01-15T10:05:00Z", "a", 100, 50), // repeated by a resumed session
+            r#"{"type":"user","timestamp":"2025-01-15T10:06:00Z","message":{"role":"user","content":"hi"}}"#.to_string(),
+            "not json".to_string(),
+            line("2025-01-15T10:07:00Z", "b", 200, 25),
+        ]
+        .join("\n");
+
+        let parsed = entries(&contents);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].tokens.total(), 260);
+        assert_eq!(parsed[1].tokens.cache_read_tokens, 100);
+    }
+
+    #[test]
+    fn test_current_block() {
+        let contents = [
+            line("2025-01-15T03:30:00Z", "a", 100, 0), // earlier block, ended 08:00
+            line("2025-01-15T10:20:00Z", "b", 200, 0), // starts the 10:00-15:00 block
+            line("2025-01-15T14:59:00Z", "c", 300, 0),
+        ]
+        .join("\n");
+        let parsed = entries(&contents);
+
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        let block = current_block(&parsed, now).unwrap();
+        assert_eq!(
+            block.start,
+            Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap()
+        );
+        assert_eq!(
+            block.end,
+            Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap()
+        );
+        assert_eq!(block.tokens.input_tokens, 500);
+
+        // Once the block has ended nothing is running
+        let later = Utc.with_ymd_and_hms(2025, 1, 15, 15, 30, 0).unwrap();
+        assert!(current_block(&parsed, later).is_none());
+    }
+
+    #[test]
+    fn test_week_start() {
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        assert_eq!(
+            week_start(now),
+            Utc.with_ymd_and_hms(2025, 1, 13, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_usage_against_caps() {
+        let contents = [
+            line("2025-01-13T09:00:00Z", "a", 1_000, 890), // Monday, 2,000 tokens
+            line("2025-01-15T10:20:00Z", "b", 290, 100),   // 500 tokens
+        ]
+        .join("\n");
+        let parsed = entries(&contents);
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+
+        let usage = build_usage(&parsed, now, Some(1_000), Some(10_000), 1);
+        assert_eq!(usage.provider, "claude_code");
+
+        let five_hour = usage.limits.iter().find(|l| l.id == "five_hour").unwrap();
+        assert!((five_hour.utilization - 50.0).abs() < 0.001);
+        assert_eq!(
+            five_hour.resets_at,
+            Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap()
+        );
+
+        let weekly = usage.limits.iter().find(|l| l.id == "seven_day").unwrap();
+        assert!((weekly.utilization - 25.0).abs() < 0.001);
+        assert_eq!(
+            weekly.resets_at,
+            Utc.with_ymd_and_hms(2025, 1, 20, 0, 0, 0).unwrap()
+        );
+
+        // Without a weekly cap only the block is reported
+        let usage = build_usage(&parsed, now, Some(1_000), None, 1);
+        assert_eq!(usage.limits.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_reads_project_directories() {
+        let dir =
+            std::env::temp_dir().join(format!("ai-pulse-claude-code-{}", uuid::Uuid::new_v4()));
+        let project = dir.join("-Users-me-repo");
+        fs::create_dir_all(&project).unwrap();
+
+        let now = Utc::now().to_rfc3339();
+        fs::write(
+            project.join("session.jsonl"),
+            [line(&now, "a", 100, 40), line(&now, "b", 200, 40)].join("\n"),
+        )
+        .unwrap();
+        fs::write(project.join("notes.txt"), line(&now, "c", 5_000, 0)).unwrap();
+
+        let provider = ClaudeCodeProvider::new();
+        let usage = provider.fetch_usage(&credentials(&dir)).await.unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // 100 + 40 + 10 + 100 and 200 + 40 + 10 + 100 of a 1,000 token cap
+        let five_hour = usage.limits.iter().find(|l| l.id == "five_hour").unwrap();
+        assert!((five_hour.utilization - 60.0).abs() < 0.001);
+        assert_eq!(usage.raw.unwrap()["filesScanned"], 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_transcripts_skips_symlinked_directories() {
+        let dir =
+            std::env::temp_dir().join(format!("ai-pulse-claude-code-{}", uuid::Uuid::new_v4()));
+        let project = dir.join("-Users-me-repo");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("session.jsonl"), "").unwrap();
+        // A link back to the root would recurse forever if followed
+        std::os::unix::fs::symlink(&dir, project.join("loop")).unwrap();
+
+        let mut files = Vec::new();
+        collect_transcripts(&dir, SystemTime::UNIX_EPOCH, &mut files);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(files, vec![project.join("session.jsonl")]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_missing_directory() {
+        let dir = std::env::temp_dir().join("ai-pulse-claude-code-missing");
+        let provider = ClaudeCodeProvider::new();
+
+        match provider.fetch_usage(&credentials(&dir)).await.unwrap_err() {
+            ProviderError::InvalidCredentials(msg) => assert!(msg.contains("not found")),
+            err => panic!("Expected InvalidCredentials, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_validate_credentials_requires_a_cap() {
+        let provider = ClaudeCodeProvider::new();
+        assert!(!provider.validate_credentials(&Credentials::default()));
+        assert!(provider.validate_credentials(&Credentials {
+            extra: HashMap::from([("weekly_token_cap".to_string(), json!(1))]),
+            ..Default::default()
+        }));
+    }
+}
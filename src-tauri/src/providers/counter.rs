@@ -0,0 +1,338 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::ProviderError;
+use crate::models::{Account, CounterEvent, Credentials, UsageData, UsageLimit};
+use crate::providers::{CredentialField, ProviderMetadata, ProviderStatus, UsageProvider};
+use crate::services::CounterService;
+
+/// When a counter's uses stop counting
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Schedule {
+    /// Each use counts for this long after it was logged
+    Rolling(Duration),
+    /// Everything resets once a week at this day and time (UTC)
+    Weekly(Weekday, NaiveTime),
+}
+
+impl Schedule {
+    fn from_credentials(credentials: &Credentials) -> Result<Self, ProviderError> {
+        let window_hours = credentials
+            .field::<u64>("window_hours")
+            .filter(|hours| *hours > 0);
+        let weekly_reset = credentials.field::<String>("weekly_reset");
+        let weekly_reset = weekly_reset
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+
+        match (window_hours, weekly_reset) {
+            (Some(hours), None) if hours as i64 <= CounterService::MAX_RETENTION_HOURS => {
+                Ok(Self::Rolling(Duration::hours(hours as i64)))
+            }
+            (Some(_), None) => Err(ProviderError::InvalidCredentials(format!(
+                "Rolling window can be at most {} hours",
+                CounterService::MAX_RETENTION_HOURS
+            ))),
+            (None, Some(reset)) => parse_weekly_reset(reset).ok_or_else(|| {
+                ProviderError::InvalidCredentials(format!(
+                    "Invalid weekly reset \"{}\" (expected e.g. \"mon 09:00\")",
+                    reset
+                ))
+            }),
+            (Some(_), Some(_)) => Err(ProviderError::InvalidCredentials(
+                "Set either a rolling window or a weekly reset, not both".to_string(),
+            )),
+            (None, None) => Err(ProviderError::InvalidCredentials(
+                "Set a rolling window or a weekly reset".to_string(),
+            )),
+        }
+    }
+
+    /// Start of the window uses are counted in, and when it next resets
+    fn window(
+        &self,
+        events: &[CounterEvent],
+        now: DateTime<Utc>,
+    ) -> (DateTime<Utc>, DateTime<Utc>) {
+        match *self {
+            Self::Rolling(length) => {
+                let start = now - length;
+                // Utilization next drops when the oldest counted use falls out
+                let oldest = events.iter().map(|e| e.at).filter(|at| *at > start).min();
+                (start, oldest.unwrap_or(now) + length)
+            }
+            Self::Weekly(weekday, time) => {
+                let days_back =
+                    (7 + now.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+                let mut start =
+                    now.date_naive().and_time(time).and_utc() - Duration::days(days_back as i64);
+                if start > now {
+                    start -= Duration::days(7);
+                }
+                (start, start + Duration::days(7))
+            }
+        }
+    }
+
+    fn label(&self) -> String {
+        match *self {
+            Self::Rolling(length) => format!("Uses ({}h rolling)", length.num_hours()),
+            Self::Weekly(..) => "Uses (weekly)".to_string(),
+        }
+    }
+}
+
+/// Parse "mon 09:00" (day name or abbreviation, 24-hour time)
+fn parse_weekly_reset(value: &str) -> Option<Schedule> {
+    let mut parts = value.split_whitespace();
+    let weekday = parts.next()?.parse::<Weekday>().ok()?;
+    let time = NaiveTime::parse_from_str(parts.next()?, "%H:%M").ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Schedule::Weekly(weekday, time))
+}
+
+/// Summary stored in `UsageData.raw`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CounterSummary {
+    count: u64,
+    cap: u64,
+    window_start: DateTime<Utc>,
+}
+
+/// Build the usage for a counter from its logged uses
+fn counter_usage(
+    credentials: &Credentials,
+    events: &[CounterEvent],
+    now: DateTime<Utc>,
+) -> Result<UsageData, ProviderError> {
+    let cap = credentials
+        .field::<u64>("counter_cap")
+        .filter(|cap| *cap > 0)
+        .ok_or_else(|| ProviderError::MissingCredentials("counter_cap".to_string()))?;
+    let schedule = Schedule::from_credentials(credentials)?;
+
+    let (window_start, resets_at) = schedule.window(events, now);
+    let count: u64 = events
+        .iter()
+        .filter(|e| e.at > window_start && e.at <= now)
+        .map(|e| e.amount as u64)
+        .sum();
+
+    let summary = CounterSummary {
+        count,
+        cap,
+        window_start,
+    };
+
+    Ok(UsageData {
+        provider: CounterService::PROVIDER_ID.to_string(),
+        account_id: String::new(),   // Will be set by caller
+        account_name: String::new(), // Will be set by caller
+        timestamp: now,
+        limits: vec![UsageLimit {
+            id: "counter".to_string(),
+            label: schedule.label(),
+            utilization: count as f64 / cap as f64 * 100.0,
+            resets_at,
+            category: None,
+        }],
+        extra_usage: None,
+        raw: serde_json::to_value(summary).ok(),
+    })
+}
+
+/// Local counter for services without a usage API (ChatGPT, Gemini, ...)
+///
+/// Uses are logged by hand (Tauri command, `POST /counters/:id/increment` or
+/// `ai-pulse tick`) and compared against a user-defined cap.
+pub struct CounterProvider {
+    app: AppHandle,
+}
+
+impl CounterProvider {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait]
+impl UsageProvider for CounterProvider {
+    fn id(&self) -> &str {
+        CounterService::PROVIDER_ID
+    }
+
+    fn name(&self) -> &str {
+        "Manual Counter"
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            status: ProviderStatus::Available,
+            required_credentials: vec![
+                CredentialField {
+                    key: "counter_cap".to_string(),
+                    label: "Uses per Window".to_string(),
+                    placeholder: "40".to_string(),
+                    is_secret: false,
+                    is_numeric: true,
+                    is_optional: false,
+                },
+                CredentialField {
+                    key: "window_hours".to_string(),
+                    label: "Rolling Window (hours)".to_string(),
+                    placeholder: "3".to_string(),
+                    is_secret: false,
+                    is_numeric: true,
+                    is_optional: true,
+                },
+                CredentialField {
+                    key: "weekly_reset".to_string(),
+                    label: "Weekly Reset (UTC)".to_string(),
+                    placeholder: "mon 09:00".to_string(),
+                    is_secret: false,
+                    is_numeric: false,
+                    is_optional: true,
+                },
+            ],
+            description: Some(
+                "Counts uses you log yourself, for services without a usage API \
+                 (e.g., ChatGPT or Gemini). Set either a rolling window or a weekly reset."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError> {
+        // Without an account there is nothing logged yet
+        counter_usage(credentials, &[], Utc::now())
+    }
+
+    async fn fetch_account_usage(&self, account: &Account) -> Result<UsageData, ProviderError> {
+        let events = CounterService::events(&self.app, &account.id)
+            .map_err(|e| ProviderError::ParseError(format!("Failed to read counter: {}", e)))?;
+        counter_usage(&account.credentials, &events, Utc::now())
+    }
+
+    fn validate_credentials(&self, credentials: &Credentials) -> bool {
+        credentials
+            .field::<u64>("counter_cap")
+            .is_some_and(|cap| cap > 0)
+            && Schedule::from_credentials(credentials).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn credentials(fields: &[(&str, serde_json::Value)]) -> Credentials {
+        Credentials {
+            extra: fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect::<HashMap<_, _>>(),
+            ..Default::default()
+        }
+    }
+
+    fn rolling(hours: u64) -> Credentials {
+        credentials(&[("counter_cap", json!(40)), ("window_hours", json!(hours))])
+    }
+
+    fn event(at: DateTime<Utc>, amount: u32) -> CounterEvent {
+        CounterEvent { at, amount }
+    }
+
+    #[test]
+    fn test_rolling_window_counts_recent_uses() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        let events = vec![
+            event(now - Duration::hours(4), 5),
+            event(now - Duration::hours(2), 6),
+            event(now - Duration::minutes(10), 4),
+        ];
+
+        let usage = counter_usage(&rolling(3), &events, now).unwrap();
+        let limit = &usage.limits[0];
+
+        assert_eq!(limit.id, "counter");
+        assert_eq!(limit.label, "Uses (3h rolling)");
+        assert!((limit.utilization - 25.0).abs() < 0.001);
+        // The use logged 2 hours ago drops out in an hour
+        assert_eq!(limit.resets_at, now + Duration::hours(1));
+    }
+
+    #[test]
+    fn test_rolling_window_without_uses() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        let usage = counter_usage(&rolling(3), &[], now).unwrap();
+
+        assert_eq!(usage.limits[0].utilization, 0.0);
+        assert_eq!(usage.limits[0].resets_at, now + Duration::hours(3));
+    }
+
+    #[test]
+    fn test_weekly_window() {
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        let credentials = credentials(&[
+            ("counter_cap", json!(10)),
+            ("weekly_reset", json!("mon 09:00")),
+        ]);
+        let events = vec![
+            event(Utc.with_ymd_and_hms(2025, 1, 13, 8, 0, 0).unwrap(), 3),
+            event(Utc.with_ymd_and_hms(2025, 1, 13, 10, 0, 0).unwrap(), 2),
+        ];
+
+        let usage = counter_usage(&credentials, &events, now).unwrap();
+        let limit = &usage.limits[0];
+
+        assert!((limit.utilization - 20.0).abs() < 0.001);
+        assert_eq!(
+            limit.resets_at,
+            Utc.with_ymd_and_hms(2025, 1, 20, 9, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_weekly_reset_later_today_uses_last_week() {
+        // Monday 08:00, before the 09:00 reset
+        let now = Utc.with_ymd_and_hms(2025, 1, 13, 8, 0, 0).unwrap();
+        let schedule = parse_weekly_reset("Monday 09:00").unwrap();
+        let (start, next) = schedule.window(&[], now);
+
+        assert_eq!(start, Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap());
+        assert_eq!(next, Utc.with_ymd_and_hms(2025, 1, 13, 9, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_schedule_validation() {
+        assert!(parse_weekly_reset("funday 09:00").is_none());
+        assert!(parse_weekly_reset("mon 9am").is_none());
+
+        let both = credentials(&[
+            ("counter_cap", json!(10)),
+            ("window_hours", json!(3)),
+            ("weekly_reset", json!("mon 09:00")),
+        ]);
+        assert!(Schedule::from_credentials(&both).is_err());
+        assert!(Schedule::from_credentials(&rolling(24 * 40)).is_err());
+
+        let no_cap = credentials(&[("window_hours", json!(3))]);
+        match counter_usage(&no_cap, &[], Utc::now()).unwrap_err() {
+            ProviderError::MissingCredentials(field) => assert_eq!(field, "counter_cap"),
+            err => panic!("Expected MissingCredentials, got {:?}", err),
+        }
+    }
+}
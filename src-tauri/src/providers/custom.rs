@@ -0,0 +1,445 @@
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::Value;
+
+use crate::error::ProviderError;
+use crate::models::{Credentials, CustomLimitMapping, CustomProviderConfig, UsageData, UsageLimit};
+use crate::providers::{
+    host_of, parse_error, retry, schema, ProviderMetadata, ProviderStatus, UsageProvider,
+};
+
+/// Unix timestamps above this are treated as milliseconds
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// A provider defined in settings that reads limits from any HTTP/JSON endpoint
+pub struct CustomProvider {
+    config: CustomProviderConfig,
+    client: reqwest::Client,
+}
+
+impl CustomProvider {
+    pub fn new(config: CustomProviderConfig) -> Result<Self, ProviderError> {
+        let client = reqwest::Client::builder()
+            .build()
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        Ok(Self { config, client })
+    }
+
+    /// Parse the configured limits out of a response body
+    fn parse_limits(&self, body: &Value) -> Result<Vec<UsageLimit>, ProviderError> {
+        let mut limits = Vec::new();
+        for mapping in &self.config.limits {
+            if let Some(limit) = parse_limit(mapping, body)? {
+                limits.push(limit);
+            }
+        }
+        Ok(limits)
+    }
+}
+
+/// Value of a credential field: custom fields first, then the built-in ones
+fn credential_value(credentials: &Credentials, key: &str) -> Option<String> {
+    let value = match credentials.extra.get(key) {
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => match key {
+            "org_id" => credentials.org_id.clone(),
+            "session_key" => credentials.session_key.clone(),
+            "api_key" => credentials.api_key.clone(),
+            _ => None,
+        },
+    };
+    value.filter(|v| !v.is_empty())
+}
+
+/// Fill `{field}` placeholders from the account's credentials
+fn render_template(template: &str, credentials: &Credentials) -> Result<String, ProviderError> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let close = after.find('}');
+        let key = close.map(|close| &after[..close]);
+
+        match key {
+            Some(key)
+                if !key.is_empty()
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                let value = credential_value(credentials, key)
+                    .ok_or_else(|| ProviderError::MissingCredentials(key.to_string()))?;
+                out.push_str(&value);
+                rest = &after[key.len() + 1..];
+            }
+            // Not a placeholder (e.g. literal JSON braces) - keep as-is
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn parse_limit(
+    mapping: &CustomLimitMapping,
+    body: &Value,
+) -> Result<Option<UsageLimit>, ProviderError> {
+    let utilization = match body.pointer(&mapping.utilization_pointer) {
+        Some(Value::Number(n)) => n.as_f64(),
+        Some(Value::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        ProviderError::ParseError(format!(
+            "Limit {}: no number at {}",
+            mapping.id, mapping.utilization_pointer
+        ))
+    })?;
+
+    let resets_at = match body.pointer(&mapping.resets_at_pointer) {
+        // No reset time yet (e.g. nothing used) - skip, like Claude's idle limits
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::String(s)) => DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| {
+                ProviderError::ParseError(format!("Limit {}: invalid date: {}", mapping.id, e))
+            })?,
+        Some(Value::Number(n)) => {
+            let ts = n.as_i64().ok_or_else(|| {
+                ProviderError::ParseError(format!("Limit {}: invalid timestamp", mapping.id))
+            })?;
+            let parsed = if ts > MILLIS_THRESHOLD {
+                Utc.timestamp_millis_opt(ts).single()
+            } else {
+                Utc.timestamp_opt(ts, 0).single()
+            };
+            parsed.ok_or_else(|| {
+                ProviderError::ParseError(format!("Limit {}: invalid timestamp", mapping.id))
+            })?
+        }
+        Some(other) => {
+            return Err(ProviderError::ParseError(format!(
+                "Limit {}: unexpected reset value {}",
+                mapping.id, other
+            )))
+        }
+    };
+
+    let label = mapping
+        .label_pointer
+        .as_ref()
+        .and_then(|pointer| body.pointer(pointer))
+        .and_then(|value| value.as_str())
+        .map(String::from)
+        .or_else(|| mapping.label.clone())
+        .unwrap_or_else(|| mapping.id.clone());
+
+    Ok(Some(UsageLimit {
+        id: mapping.id.clone(),
+        label,
+        utilization: utilization * mapping.utilization_scale,
+        resets_at,
+        category: mapping.category.clone(),
+    }))
+}
+
+#[async_trait]
+impl UsageProvider for CustomProvider {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: self.config.id.clone(),
+            name: self.config.name.clone(),
+            status: ProviderStatus::Available,
+            required_credentials: self.config.credentials.clone(),
+            description: Some(
+                self.config
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| "Custom HTTP/JSON provider".to_string()),
+            ),
+        }
+    }
+
+    async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError> {
+        let url = render_template(&self.config.url, credentials)?;
+
+        let mut request = self.client.get(&url);
+        for (name, value) in &self.config.headers {
+            request = request.header(name, render_template(value, credentials)?);
+        }
+
+        log::info!("Fetching {} usage", self.config.name);
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ProviderError::Network(e.to_string()))?;
+
+        let status = response.status();
+        log::debug!("{} response status: {}", self.config.name, status);
+
+        if self
+            .config
+            .session_expired_statuses
+            .contains(&status.as_u16())
+        {
+            return Err(ProviderError::SessionExpired);
+        }
+        if self.config.rate_limited_statuses.contains(&status.as_u16()) {
+            return Err(retry::rate_limited(response.headers()));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(retry::status_error(status, body));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+        let body: Value = serde_json::from_str(&text).map_err(|e| parse_error(e, &text))?;
+        schema::observe(&self.config.id, "response", &body);
+
+        Ok(UsageData {
+            provider: self.config.id.clone(),
+            account_id: String::new(),   // Will be set by caller
+            account_name: String::new(), // Will be set by caller
+            timestamp: Utc::now(),
+            limits: self.parse_limits(&body)?,
+            extra_usage: None,
+            raw: Some(body),
+        })
+    }
+
+    fn validate_credentials(&self, credentials: &Credentials) -> bool {
+        self.config
+            .credentials
+            .iter()
+            .filter(|field| !field.is_optional)
+            .all(|field| credential_value(credentials, &field.key).is_some())
+    }
+
+    fn host(&self) -> Option<String> {
+        host_of(&self.config.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::CredentialField;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_config(base_url: &str) -> CustomProviderConfig {
+        serde_json::from_value(serde_json::json!({
+            "id": "gateway",
+            "name": "LLM Gateway",
+            "url": format!("{}/teams/{{team}}/quota", base_url),
+            "headers": { "Authorization": "Bearer {token}" },
+            "credentials": [
+                { "key": "team", "label": "Team" },
+                { "key": "token", "label": "Token", "isSecret": true }
+            ],
+            "limits": [
+                {
+                    "id": "daily",
+                    "labelPointer": "/quotas/0/name",
+                    "utilizationPointer": "/quotas/0/used_ratio",
+                    "utilizationScale": 100.0,
+                    "resetsAtPointer": "/quotas/0/resets_at"
+                },
+                {
+                    "id": "monthly",
+                    "label": "Monthly",
+                    "utilizationPointer": "/quotas/1/percent",
+                    "resetsAtPointer": "/quotas/1/reset_epoch",
+                    "category": "budget"
+                },
+                {
+                    "id": "idle",
+                    "utilizationPointer": "/quotas/2/percent",
+                    "resetsAtPointer": "/quotas/2/resets_at"
+                }
+            ],
+            "sessionExpiredStatuses": [401, 403]
+        }))
+        .unwrap()
+    }
+
+    fn make_credentials() -> Credentials {
+        let mut credentials = Credentials::default();
+        credentials
+            .extra
+            .insert("team".to_string(), Value::String("ml".to_string()));
+        credentials
+            .extra
+            .insert("token".to_string(), Value::String("secret".to_string()));
+        credentials
+    }
+
+    fn make_response() -> Value {
+        serde_json::json!({
+            "quotas": [
+                { "name": "Daily tokens", "used_ratio": 0.42, "resets_at": "2025-01-16T00:00:00Z" },
+                { "percent": "73.5", "reset_epoch": 1738368000 },
+                { "percent": 0, "resets_at": null }
+            ]
+        })
+    }
+
+    // ============================================================================
+    // Integration tests with mocked HTTP
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_fetch_usage_maps_limits() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/teams/ml/quota"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_response()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = CustomProvider::new(make_config(&mock_server.uri())).unwrap();
+        let usage = provider.fetch_usage(&make_credentials()).await.unwrap();
+
+        assert_eq!(usage.provider, "gateway");
+        // The idle limit has no reset time and is skipped
+        assert_eq!(usage.limits.len(), 2);
+
+        let daily = &usage.limits[0];
+        assert_eq!(daily.label, "Daily tokens");
+        assert!((daily.utilization - 42.0).abs() < 0.001);
+        assert_eq!(
+            daily.resets_at,
+            Utc.with_ymd_and_hms(2025, 1, 16, 0, 0, 0).unwrap()
+        );
+
+        let monthly = &usage.limits[1];
+        assert_eq!(monthly.label, "Monthly");
+        assert!((monthly.utilization - 73.5).abs() < 0.001);
+        assert_eq!(monthly.resets_at, Utc.timestamp_opt(1738368000, 0).unwrap());
+        assert_eq!(monthly.category, Some("budget".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_configured_statuses() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/teams/ml/quota"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = CustomProvider::new(make_config(&mock_server.uri())).unwrap();
+        match provider.fetch_usage(&make_credentials()).await.unwrap_err() {
+            ProviderError::SessionExpired => {}
+            err => panic!("Expected SessionExpired, got {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_missing_pointer() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/teams/ml/quota"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "quotas": []
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = CustomProvider::new(make_config(&mock_server.uri())).unwrap();
+        match provider.fetch_usage(&make_credentials()).await.unwrap_err() {
+            ProviderError::ParseError(msg) => assert!(msg.contains("/quotas/0/used_ratio")),
+            err => panic!("Expected ParseError, got {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_non_json_multibyte_body() {
+        let mock_server = MockServer::start().await;
+
+        // A 3-byte character straddles the 500-byte excerpt
+        let body = format!("{}€ maintenance", "x".repeat(499));
+        Mock::given(method("GET"))
+            .and(path("/teams/ml/quota"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body))
+            .mount(&mock_server)
+            .await;
+
+        let provider = CustomProvider::new(make_config(&mock_server.uri())).unwrap();
+        match provider.fetch_usage(&make_credentials()).await.unwrap_err() {
+            ProviderError::ParseError(msg) => assert!(msg.ends_with(&"x".repeat(499))),
+            err => panic!("Expected ParseError, got {:?}", err),
+        }
+    }
+
+    // ============================================================================
+    // Unit tests
+    // ============================================================================
+
+    #[test]
+    fn test_render_template() {
+        let credentials = make_credentials();
+        assert_eq!(
+            render_template("https://x/{team}?q={\"a\":1}", &credentials).unwrap(),
+            "https://x/ml?q={\"a\":1}"
+        );
+
+        match render_template("https://x/{org_id}", &credentials).unwrap_err() {
+            ProviderError::MissingCredentials(field) => assert_eq!(field, "org_id"),
+            err => panic!("Expected MissingCredentials, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_validate_credentials() {
+        let mut config = make_config("http://localhost");
+        config.credentials.push(CredentialField {
+            key: "region".to_string(),
+            label: "Region".to_string(),
+            placeholder: String::new(),
+            is_secret: false,
+            is_numeric: false,
+            is_optional: true,
+        });
+        let provider = CustomProvider::new(config).unwrap();
+
+        assert!(provider.validate_credentials(&make_credentials()));
+
+        let mut missing = make_credentials();
+        missing.extra.remove("token");
+        assert!(!provider.validate_credentials(&missing));
+    }
+
+    #[test]
+    fn test_metadata_uses_configured_fields() {
+        let provider = CustomProvider::new(make_config("http://localhost")).unwrap();
+        let metadata = provider.metadata();
+        assert_eq!(metadata.id, "gateway");
+        assert_eq!(metadata.required_credentials.len(), 2);
+        assert!(metadata.required_credentials[1].is_secret);
+    }
+}
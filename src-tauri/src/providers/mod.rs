@@ -1,29 +1,57 @@
+mod anthropic_api;
 mod claude;
+mod claude_code;
+mod counter;
+mod custom;
+mod openai_api;
+pub mod rate_limit;
+pub mod retry;
+pub mod schema;
 
+pub use anthropic_api::AnthropicApiProvider;
 pub use claude::ClaudeProvider;
+pub use claude_code::ClaudeCodeProvider;
+pub use counter::CounterProvider;
+pub use custom::CustomProvider;
+pub use openai_api::OpenAiApiProvider;
 
 use crate::error::ProviderError;
-use crate::models::{Credentials, UsageData};
+use crate::models::{Account, Credentials, CustomProviderConfig, UsageData};
+use crate::services::SettingsService;
 use async_trait::async_trait;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tauri::AppHandle;
 
 /// Trait for usage data providers
 #[async_trait]
 pub trait UsageProvider: Send + Sync {
     /// Provider identifier (e.g., "claude", "chatgpt", "gemini")
-    fn id(&self) -> &'static str;
+    fn id(&self) -> &str;
 
     /// Human-readable provider name (e.g., "Claude", "ChatGPT", "Gemini")
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 
     /// Fetch current usage data
     async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError>;
 
+    /// Fetch usage for a stored account (providers with per-account local state override this)
+    async fn fetch_account_usage(&self, account: &Account) -> Result<UsageData, ProviderError> {
+        self.fetch_usage(&account.credentials).await
+    }
+
     /// Validate that credentials have required fields
     fn validate_credentials(&self, credentials: &Credentials) -> bool;
 
+    /// Host the provider sends requests to, for per-host rate limiting
+    /// (`None` for providers that never leave the machine)
+    fn host(&self) -> Option<String> {
+        None
+    }
+
     /// Get metadata about this provider
     fn metadata(&self) -> ProviderMetadata {
         ProviderMetadata {
@@ -65,13 +93,105 @@ pub struct ProviderMetadata {
 pub struct CredentialField {
     pub key: String,
     pub label: String,
+    #[serde(default)]
     pub placeholder: String,
+    #[serde(default)]
     pub is_secret: bool,
+    /// Value is a number (stored as a number in `Credentials`)
+    #[serde(default)]
+    pub is_numeric: bool,
+    /// Field may be left empty
+    #[serde(default)]
+    pub is_optional: bool,
+}
+
+/// Start of the current UTC month and of the next one (when monthly budgets reset)
+pub(crate) fn month_bounds(now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = Utc
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .unwrap();
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    let next = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap();
+    (start, next)
+}
+
+/// Host (and port, if any) of a URL, used to key per-host rate limits
+pub(crate) fn host_of(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// Most bytes of a response body quoted in a parse error
+const RESPONSE_EXCERPT_LEN: usize = 500;
+
+/// Start of a response body for error messages, cut on a character boundary
+pub(crate) fn response_excerpt(text: &str) -> &str {
+    let end = (0..=text.len().min(RESPONSE_EXCERPT_LEN))
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0);
+    &text[..end]
+}
+
+/// Parse error quoting the start of the response it came from
+pub(crate) fn parse_error(error: impl std::fmt::Display, text: &str) -> ProviderError {
+    ProviderError::ParseError(format!("{} - Response: {}", error, response_excerpt(text)))
+}
+
+/// Send a request for one page of an Admin API usage report
+///
+/// Records the response shape under `provider` and `path` for drift detection.
+/// A 403 means the key is not an admin key; `forbidden` says which one is needed.
+pub(crate) async fn get_report_page<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    provider: &str,
+    path: &str,
+    forbidden: &str,
+) -> Result<T, ProviderError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ProviderError::Network(e.to_string()))?;
+
+    let status = response.status();
+    log::debug!("{} {} response status: {}", provider, path, status);
+
+    match status.as_u16() {
+        200 => {
+            let text = response
+                .text()
+                .await
+                .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+            let value: serde_json::Value =
+                serde_json::from_str(&text).map_err(|e| parse_error(e, &text))?;
+            schema::observe(provider, path, &value);
+            serde_json::from_value(value).map_err(|e| parse_error(e, &text))
+        }
+        401 => Err(ProviderError::InvalidCredentials(
+            "Admin API key was rejected (401)".to_string(),
+        )),
+        403 => Err(ProviderError::InvalidCredentials(forbidden.to_string())),
+        429 => Err(retry::rate_limited(response.headers())),
+        _ => {
+            let body = response.text().await.unwrap_or_default();
+            Err(retry::status_error(status, body))
+        }
+    }
 }
 
 /// Registry of all available providers
 pub struct ProviderRegistry {
     providers: HashMap<String, Arc<dyn UsageProvider>>,
+    /// Provider IDs in registration order
+    order: Vec<String>,
     /// Metadata for blocked/planned providers (no implementation yet)
     blocked_providers: Vec<ProviderMetadata>,
 }
@@ -79,14 +199,20 @@ pub struct ProviderRegistry {
 impl ProviderRegistry {
     /// Create a new registry with all available providers
     pub fn new() -> Result<Self, ProviderError> {
-        let mut providers: HashMap<String, Arc<dyn UsageProvider>> = HashMap::new();
+        let mut registry = Self {
+            providers: HashMap::new(),
+            order: Vec::new(),
+            blocked_providers: Vec::new(),
+        };
 
-        // Register Claude provider
-        let claude = ClaudeProvider::new()?;
-        providers.insert(claude.id().to_string(), Arc::new(claude));
+        // Register functional providers (the scheduler polls every account of each)
+        registry.register(ClaudeProvider::new()?);
+        registry.register(ClaudeCodeProvider::new());
+        registry.register(AnthropicApiProvider::new()?);
+        registry.register(OpenAiApiProvider::new()?);
 
         // Define blocked/planned providers for UI display
-        let blocked_providers = vec![
+        registry.blocked_providers = vec![
             ProviderMetadata {
                 id: "chatgpt".to_string(),
                 name: "ChatGPT".to_string(),
@@ -97,11 +223,15 @@ impl ProviderRegistry {
                         label: "Session Token".to_string(),
                         placeholder: "__Secure-next-auth.session-token cookie".to_string(),
                         is_secret: true,
+                        is_numeric: false,
+                        is_optional: false,
                     },
                 ],
                 description: Some(
-                    "Blocked: OpenAI does not expose a usage tracking API. \
-                     Only message cap limits are available, not current usage."
+                    "Blocked: OpenAI does not expose a usage tracking API for ChatGPT. \
+                     Only message cap limits are available, not current usage. \
+                     API usage is covered by the OpenAI API provider; \
+                     chat usage can be tracked with a Manual Counter."
                         .to_string(),
                 ),
             },
@@ -115,20 +245,69 @@ impl ProviderRegistry {
                         label: "GCP Project ID".to_string(),
                         placeholder: "my-gcp-project".to_string(),
                         is_secret: false,
+                        is_numeric: false,
+                        is_optional: false,
                     },
                 ],
                 description: Some(
                     "Blocked: Requires complex Google Cloud Monitoring API setup. \
-                     See docs/api-integration.md for details."
+                     See docs/api-integration.md for details. \
+                     Usage can be tracked with a Manual Counter."
                         .to_string(),
                 ),
             },
         ];
 
-        Ok(Self {
-            providers,
-            blocked_providers,
-        })
+        Ok(registry)
+    }
+
+    /// Create a registry with the built-in providers, the manual counter and the
+    /// custom providers from settings
+    pub fn for_app(app: &AppHandle) -> Result<Self, ProviderError> {
+        let mut registry = Self::new()?;
+        registry.register(CounterProvider::new(app.clone()));
+        match SettingsService::get(app) {
+            Ok(settings) => registry.register_custom(&settings.custom_providers),
+            Err(e) => log::warn!("Failed to load custom providers: {}", e),
+        }
+        Ok(registry)
+    }
+
+    /// Register enabled custom providers, skipping invalid IDs and ones that clash
+    pub fn register_custom(&mut self, configs: &[CustomProviderConfig]) {
+        for config in configs.iter().filter(|c| c.enabled) {
+            let valid_id = !config.id.is_empty()
+                && config
+                    .id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !valid_id {
+                log::warn!("Skipping custom provider with invalid ID {:?}", config.id);
+                continue;
+            }
+            let clashes = self.providers.contains_key(&config.id)
+                || self.blocked_providers.iter().any(|p| p.id == config.id);
+            if clashes {
+                log::warn!(
+                    "Skipping custom provider {}: ID is already registered",
+                    config.id
+                );
+                continue;
+            }
+
+            match CustomProvider::new(config.clone()) {
+                Ok(provider) => self.register(provider),
+                Err(e) => log::warn!("Failed to create custom provider {}: {}", config.id, e),
+            }
+        }
+    }
+
+    /// Add a provider, replacing any existing one with the same ID
+    pub fn register<P: UsageProvider + 'static>(&mut self, provider: P) {
+        let id = provider.id().to_string();
+        if self.providers.insert(id.clone(), Arc::new(provider)).is_none() {
+            self.order.push(id);
+        }
     }
 
     /// Get a provider by ID
@@ -136,43 +315,18 @@ impl ProviderRegistry {
         self.providers.get(id).cloned()
     }
 
-    /// Get all available (functional) provider IDs
-    #[allow(dead_code)]
+    /// Get all available (functional) provider IDs, in registration order
     pub fn available_ids(&self) -> Vec<&str> {
-        self.providers.keys().map(|s| s.as_str()).collect()
+        self.order.iter().map(|s| s.as_str()).collect()
     }
 
     /// Get metadata for all providers (including blocked ones)
     pub fn all_metadata(&self) -> Vec<ProviderMetadata> {
         let mut metadata: Vec<ProviderMetadata> = self
-            .providers
-            .values()
-            .map(|p| {
-                let mut meta = p.metadata();
-                // Add required credentials for Claude
-                if meta.id == "claude" {
-                    meta.required_credentials = vec![
-                        CredentialField {
-                            key: "org_id".to_string(),
-                            label: "Organization ID".to_string(),
-                            placeholder: "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx".to_string(),
-                            is_secret: false,
-                        },
-                        CredentialField {
-                            key: "session_key".to_string(),
-                            label: "Session Key".to_string(),
-                            placeholder: "sk-ant-sid01-...".to_string(),
-                            is_secret: true,
-                        },
-                    ];
-                    meta.description = Some(
-                        "Monitor your Claude Pro/Max usage limits. \
-                         Get credentials from claude.ai DevTools."
-                            .to_string(),
-                    );
-                }
-                meta
-            })
+            .available_ids()
+            .into_iter()
+            .filter_map(|id| self.providers.get(id))
+            .map(|p| p.metadata())
             .collect();
 
         // Add blocked providers
@@ -209,6 +363,96 @@ mod tests {
         assert!(registry.get("unknown").is_none());
     }
 
+    struct FakeProvider;
+
+    #[async_trait]
+    impl UsageProvider for FakeProvider {
+        fn id(&self) -> &str {
+            "acme"
+        }
+
+        fn name(&self) -> &str {
+            "Acme"
+        }
+
+        async fn fetch_usage(&self, _credentials: &Credentials) -> Result<UsageData, ProviderError> {
+            Err(ProviderError::RateLimited(None))
+        }
+
+        fn validate_credentials(&self, _credentials: &Credentials) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_registered_provider_is_available() {
+        let mut registry = ProviderRegistry::new().unwrap();
+        registry.register(FakeProvider);
+
+        assert!(registry.get("acme").is_some());
+        assert_eq!(
+            registry.available_ids(),
+            vec![
+                "claude",
+                "claude_code",
+                "anthropic_api",
+                "openai_api",
+                "acme"
+            ]
+        );
+
+        let metadata = registry.all_metadata();
+        let acme = metadata.iter().find(|m| m.id == "acme").unwrap();
+        assert_eq!(acme.status, ProviderStatus::Available);
+    }
+
+    #[test]
+    fn test_register_custom() {
+        let configs: Vec<CustomProviderConfig> = serde_json::from_value(serde_json::json!([
+            { "id": "gateway", "name": "Gateway", "url": "http://localhost/usage" },
+            { "id": "off", "name": "Off", "url": "http://localhost/usage", "enabled": false },
+            { "id": "claude", "name": "Shadow", "url": "http://localhost/usage" },
+            { "id": "gemini", "name": "Shadow", "url": "http://localhost/usage" },
+            { "id": "bad id", "name": "Bad", "url": "http://localhost/usage" }
+        ]))
+        .unwrap();
+
+        let mut registry = ProviderRegistry::new().unwrap();
+        registry.register_custom(&configs);
+
+        assert_eq!(registry.available_ids().last(), Some(&"gateway"));
+        assert_eq!(registry.available_ids().len(), 5);
+        assert_eq!(registry.get("claude").unwrap().name(), "Claude");
+    }
+
+    #[test]
+    fn test_month_bounds() {
+        let now = Utc.with_ymd_and_hms(2025, 12, 15, 9, 30, 0).unwrap();
+        let (start, next) = month_bounds(now);
+        assert_eq!(start, Utc.with_ymd_and_hms(2025, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(next, Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_response_excerpt() {
+        assert_eq!(response_excerpt("short"), "short");
+        assert_eq!(response_excerpt(&"a".repeat(600)).len(), 500);
+
+        // A multi-byte character straddling the cut is dropped, not split
+        let text = format!("{}é", "a".repeat(499));
+        assert_eq!(response_excerpt(&text), "a".repeat(499));
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("https://claude.ai/api").as_deref(), Some("claude.ai"));
+        assert_eq!(
+            host_of("http://127.0.0.1:8080/teams/{team}/quota").as_deref(),
+            Some("127.0.0.1:8080")
+        );
+        assert_eq!(host_of("not a url"), None);
+    }
+
     #[test]
     fn test_available_ids() {
         let registry = ProviderRegistry::new().unwrap();
@@ -224,9 +468,14 @@ mod tests {
         // Should have Claude (available), ChatGPT (blocked), Gemini (blocked)
         assert!(metadata.len() >= 3);
 
-        // Claude should be first (available)
+        // Claude should be first (available), with its credential fields
         assert_eq!(metadata[0].id, "claude");
         assert_eq!(metadata[0].status, ProviderStatus::Available);
+        assert_eq!(metadata[0].required_credentials.len(), 2);
+
+        let anthropic_api = metadata.iter().find(|m| m.id == "anthropic_api").unwrap();
+        assert_eq!(anthropic_api.status, ProviderStatus::Available);
+        assert!(anthropic_api.required_credentials.iter().any(|f| f.is_numeric));
 
         // ChatGPT and Gemini should be blocked
         let chatgpt = metadata.iter().find(|m| m.id == "chatgpt").unwrap();
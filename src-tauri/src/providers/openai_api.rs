@@ -0,0 +1,715 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ProviderError;
+use crate::models::{Credentials, ProviderId, UsageData, UsageLimit};
+use crate::providers::{
+    get_report_page, host_of, month_bounds, CredentialField, ProviderMetadata, ProviderStatus,
+    UsageProvider,
+};
+
+const OPENAI_API_BASE: &str = "https://api.openai.com/v1";
+
+/// Daily buckets per page (a month fits in one page)
+const PAGE_LIMIT: &str = "31";
+/// Upper bound on pages followed per report
+const MAX_PAGES: usize = 10;
+
+/// Label used for cost/usage rows outside any project
+const DEFAULT_PROJECT: &str = "default";
+
+/// Paginated organization usage/costs page
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    data: Vec<Bucket<T>>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Bucket<T> {
+    results: Vec<T>,
+}
+
+/// Row of /organization/costs grouped by line item and project
+#[derive(Debug, Deserialize)]
+struct CostResult {
+    amount: CostAmount,
+    /// e.g. "gpt-4o-2024-08-06, input"
+    #[serde(default)]
+    line_item: Option<String>,
+    #[serde(default)]
+    project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CostAmount {
+    /// Amount in dollars
+    value: f64,
+}
+
+impl CostResult {
+    /// Model named by the line item (the part before ", input"/", output")
+    fn model(&self) -> Option<&str> {
+        self.line_item
+            .as_deref()
+            .map(|item| item.split(", ").next().unwrap_or(item))
+            .filter(|model| !model.is_empty())
+    }
+}
+
+/// Row of /organization/usage/completions grouped by model
+#[derive(Debug, Deserialize)]
+struct CompletionsUsageResult {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+/// Month-to-date spend summary (stored as `UsageData.raw`)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpendSummary {
+    period_start: DateTime<Utc>,
+    budget_usd: f64,
+    spent_usd: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_budget: Option<u64>,
+    tokens: u64,
+    models: Vec<ModelSpend>,
+    projects: Vec<ProjectSpend>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelSpend {
+    model: String,
+    cost_usd: f64,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectSpend {
+    project_id: String,
+    cost_usd: f64,
+}
+
+/// OpenAI platform spend and tokens against monthly budgets, read with an admin key
+pub struct OpenAiApiProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl OpenAiApiProvider {
+    pub fn new() -> Result<Self, ProviderError> {
+        Self::with_base_url(OPENAI_API_BASE)
+    }
+
+    /// Create a provider with a custom base URL (for testing)
+    pub fn with_base_url(base_url: &str) -> Result<Self, ProviderError> {
+        let client = reqwest::Client::builder()
+            .build()
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.to_string(),
+        })
+    }
+
+    /// Fetch every page of an organization usage/costs endpoint
+    async fn fetch_pages<T: DeserializeOwned>(
+        &self,
+        api_key: &str,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Vec<T>, ProviderError> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut results = Vec::new();
+        let mut page: Option<String> = None;
+
+        for _ in 0..MAX_PAGES {
+            let mut request = self.client.get(&url).bearer_auth(api_key).query(params);
+            if let Some(ref page) = page {
+                request = request.query(&[("page", page)]);
+            }
+
+            let body: Page<T> = get_report_page(
+                request,
+                "openai_api",
+                path,
+                "Key lacks access to organization usage - an admin key (sk-admin-...) is required",
+            )
+            .await?;
+
+            results.extend(body.data.into_iter().flat_map(|bucket| bucket.results));
+
+            match body.next_page {
+                Some(next) if body.has_more => page = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Build limits from month-to-date costs and completion token usage
+    fn build_usage(
+        budget: f64,
+        token_budget: Option<u64>,
+        period_start: DateTime<Utc>,
+        resets_at: DateTime<Utc>,
+        costs: &[CostResult],
+        usage: &[CompletionsUsageResult],
+    ) -> UsageData {
+        let mut models: BTreeMap<String, ModelSpend> = BTreeMap::new();
+        let mut projects: BTreeMap<String, f64> = BTreeMap::new();
+        let mut spent = 0.0;
+        let mut tokens = 0;
+
+        for cost in costs {
+            spent += cost.amount.value;
+
+            if let Some(model) = cost.model() {
+                models.entry(model.to_string()).or_default().cost_usd += cost.amount.value;
+            }
+            let project = cost.project_id.as_deref().unwrap_or(DEFAULT_PROJECT);
+            *projects.entry(project.to_string()).or_default() += cost.amount.value;
+        }
+
+        for row in usage {
+            tokens += row.input_tokens + row.output_tokens;
+            if let Some(ref model) = row.model {
+                let entry = models.entry(model.clone()).or_default();
+                entry.input_tokens += row.input_tokens;
+                entry.output_tokens += row.output_tokens;
+            }
+        }
+
+        let utilization = |usd: f64| usd / budget * 100.0;
+
+        let mut limits = vec![UsageLimit {
+            id: "monthly_budget".to_string(),
+            label: "Monthly Budget".to_string(),
+            utilization: utilization(spent),
+            resets_at,
+            category: None,
+        }];
+
+        if let Some(token_budget) = token_budget {
+            limits.push(UsageLimit {
+                id: "monthly_tokens".to_string(),
+                label: "Monthly Tokens".to_string(),
+                utilization: tokens as f64 / token_budget as f64 * 100.0,
+                resets_at,
+                category: None,
+            });
+        }
+
+        for (model, spend) in &models {
+            limits.push(UsageLimit {
+                id: format!("model:{}", model),
+                label: model.clone(),
+                utilization: utilization(spend.cost_usd),
+                resets_at,
+                category: Some("model".to_string()),
+            });
+        }
+
+        for (project, usd) in &projects {
+            let label = if project == DEFAULT_PROJECT {
+                "Default Project".to_string()
+            } else {
+                format!("Project {}", project)
+            };
+            limits.push(UsageLimit {
+                id: format!("project:{}", project),
+                label,
+                utilization: utilization(*usd),
+                resets_at,
+                category: Some("project".to_string()),
+            });
+        }
+
+        let summary = SpendSummary {
+            period_start,
+            budget_usd: budget,
+            spent_usd: spent,
+            token_budget,
+            tokens,
+            models: models
+                .into_iter()
+                .map(|(model, spend)| ModelSpend { model, ..spend })
+                .collect(),
+            projects: projects
+                .into_iter()
+                .map(|(project_id, cost_usd)| ProjectSpend {
+                    project_id,
+                    cost_usd,
+                })
+                .collect(),
+        };
+
+        UsageData {
+            provider: ProviderId::OpenAiApi.as_str().to_string(),
+            account_id: String::new(),   // Will be set by caller
+            account_name: String::new(), // Will be set by caller
+            timestamp: Utc::now(),
+            limits,
+            extra_usage: None,
+            raw: Some(serde_json::to_value(&summary).unwrap_or_default()),
+        }
+    }
+}
+
+impl Default for OpenAiApiProvider {
+    fn default() -> Self {
+        Self::new().expect("Failed to create OpenAI API provider")
+    }
+}
+
+#[async_trait]
+impl UsageProvider for OpenAiApiProvider {
+    fn id(&self) -> &str {
+        ProviderId::OpenAiApi.as_str()
+    }
+
+    fn name(&self) -> &str {
+        "OpenAI API"
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            status: ProviderStatus::Available,
+            required_credentials: vec![
+                CredentialField {
+                    key: "api_key".to_string(),
+                    label: "Admin API Key".to_string(),
+                    placeholder: "sk-admin-...".to_string(),
+                    is_secret: true,
+                    is_numeric: false,
+                    is_optional: false,
+                },
+                CredentialField {
+                    key: "monthly_budget".to_string(),
+                    label: "Monthly Budget (USD)".to_string(),
+                    placeholder: "500".to_string(),
+                    is_secret: false,
+                    is_numeric: true,
+                    is_optional: false,
+                },
+                CredentialField {
+                    key: "monthly_token_budget".to_string(),
+                    label: "Monthly Token Budget".to_string(),
+                    placeholder: "50000000".to_string(),
+                    is_secret: false,
+                    is_numeric: true,
+                    is_optional: true,
+                },
+            ],
+            description: Some(
+                "Track month-to-date OpenAI platform spend and tokens per model and project. \
+                 Create an admin key in platform.openai.com → Organization → Admin Keys."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError> {
+        let api_key = credentials
+            .api_key
+            .as_ref()
+            .ok_or_else(|| ProviderError::MissingCredentials("api_key".to_string()))?;
+
+        let budget = credentials
+            .field::<f64>("monthly_budget")
+            .filter(|b| *b > 0.0)
+            .ok_or_else(|| ProviderError::MissingCredentials("monthly_budget".to_string()))?;
+
+        let token_budget = credentials
+            .field::<u64>("monthly_token_budget")
+            .filter(|b| *b > 0);
+
+        let (period_start, resets_at) = month_bounds(Utc::now());
+        let start_time = period_start.timestamp().to_string();
+
+        log::info!("Fetching OpenAI API costs and usage since {}", period_start);
+
+        let costs: Vec<CostResult> = self
+            .fetch_pages(
+                api_key,
+                "/organization/costs",
+                &[
+                    ("start_time", start_time.as_str()),
+                    ("bucket_width", "1d"),
+                    ("group_by", "line_item"),
+                    ("group_by", "project_id"),
+                    ("limit", PAGE_LIMIT),
+                ],
+            )
+            .await?;
+
+        let usage: Vec<CompletionsUsageResult> = self
+            .fetch_pages(
+                api_key,
+                "/organization/usage/completions",
+                &[
+                    ("start_time", start_time.as_str()),
+                    ("bucket_width", "1d"),
+                    ("group_by", "model"),
+                    ("limit", PAGE_LIMIT),
+                ],
+            )
+            .await?;
+
+        Ok(Self::build_usage(
+            budget,
+            token_budget,
+            period_start,
+            resets_at,
+            &costs,
+            &usage,
+        ))
+    }
+
+    fn validate_credentials(&self, credentials: &Credentials) -> bool {
+        credentials
+            .api_key
+            .as_ref()
+            .map(|s| !s.is_empty())
+            .unwrap_or(false)
+            && credentials
+                .field::<f64>("monthly_budget")
+                .is_some_and(|b| b > 0.0)
+    }
+
+    fn host(&self) -> Option<String> {
+        host_of(&self.base_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_credentials() -> Credentials {
+        Credentials {
+            api_key: Some("sk-admin-test".to_string()),
+            extra: HashMap::from([
+                ("monthly_budget".to_string(), serde_json::json!(200.0)),
+                (
+                    "monthly_token_budget".to_string(),
+                    serde_json::json!(10_000),
+                ),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn make_costs_page() -> serde_json::Value {
+        serde_json::json!({
+            "object": "page",
+            "data": [
+                {
+                    "object": "bucket",
+                    "start_time": 1735689600,
+                    "end_time": 1735776000,
+                    "results": [
+                        {
+                            "object": "organization.costs.result",
+                            "amount": { "value": 30.0, "currency": "usd" },
+                            "line_item": "gpt-4o-2024-08-06, input",
+                            "project_id": "proj_abc"
+                        },
+                        {
+                            "object": "organization.costs.result",
+                            "amount": { "value": 20.0, "currency": "usd" },
+                            "line_item": "gpt-4o-2024-08-06, output",
+                            "project_id": null
+                        },
+                        {
+                            "object": "organization.costs.result",
+                            "amount": { "value": 10.0, "currency": "usd" },
+                            "line_item": "o3-mini, input",
+                            "project_id": "proj_abc"
+                        }
+                    ]
+                }
+            ],
+            "has_more": false,
+            "next_page": null
+        })
+    }
+
+    fn make_completions_page() -> serde_json::Value {
+        serde_json::json!({
+            "object": "page",
+            "data": [
+                {
+                    "object": "bucket",
+                    "start_time": 1735689600,
+                    "end_time": 1735776000,
+                    "results": [
+                        {
+                            "object": "organization.usage.completions.result",
+                            "input_tokens": 3000,
+                            "output_tokens": 1000,
+                            "input_cached_tokens": 500,
+                            "num_model_requests": 12,
+                            "model": "gpt-4o-2024-08-06"
+                        },
+                        {
+                            "object": "organization.usage.completions.result",
+                            "input_tokens": 500,
+                            "output_tokens": 500,
+                            "num_model_requests": 3,
+                            "model": "o3-mini"
+                        }
+                    ]
+                }
+            ],
+            "has_more": false,
+            "next_page": null
+        })
+    }
+
+    async fn mount_pages(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .and(header("authorization", "Bearer sk-admin-test"))
+            .and(query_param("bucket_width", "1d"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_costs_page()))
+            .expect(1)
+            .mount(server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organization/usage/completions"))
+            .and(query_param("group_by", "model"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(make_completions_page()))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    // ============================================================================
+    // Integration tests with mocked HTTP
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_fetch_usage_maps_spend_and_tokens() {
+        let mock_server = MockServer::start().await;
+        mount_pages(&mock_server).await;
+
+        let provider = OpenAiApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let usage = provider.fetch_usage(&make_credentials()).await.unwrap();
+
+        assert_eq!(usage.provider, "openai_api");
+
+        // $60 of a $200 budget
+        let total = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "monthly_budget")
+            .unwrap();
+        assert!((total.utilization - 30.0).abs() < 0.001);
+
+        // 5,000 of 10,000 tokens
+        let tokens = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "monthly_tokens")
+            .unwrap();
+        assert!((tokens.utilization - 50.0).abs() < 0.001);
+
+        // Input and output line items roll up to the model
+        let gpt4o = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "model:gpt-4o-2024-08-06")
+            .unwrap();
+        assert!((gpt4o.utilization - 25.0).abs() < 0.001);
+        assert_eq!(gpt4o.category, Some("model".to_string()));
+
+        let project = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "project:proj_abc")
+            .unwrap();
+        assert!((project.utilization - 20.0).abs() < 0.001);
+
+        let default = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "project:default")
+            .unwrap();
+        assert_eq!(default.label, "Default Project");
+
+        let raw = usage.raw.unwrap();
+        assert_eq!(raw["tokens"], 5000);
+        let models = raw["models"].as_array().unwrap();
+        let o3 = models.iter().find(|m| m["model"] == "o3-mini").unwrap();
+        assert_eq!(o3["inputTokens"], 500);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_without_token_budget() {
+        let mock_server = MockServer::start().await;
+        mount_pages(&mock_server).await;
+
+        let provider = OpenAiApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let mut credentials = make_credentials();
+        credentials.extra.remove("monthly_token_budget");
+        let usage = provider.fetch_usage(&credentials).await.unwrap();
+
+        assert!(usage.limits.iter().all(|l| l.id != "monthly_tokens"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_follows_pages() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .and(query_param("page", "page_2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "results": [{ "amount": { "value": 50.0 } }] }],
+                "has_more": false,
+                "next_page": null
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "results": [{ "amount": { "value": 10.0 } }] }],
+                "has_more": true,
+                "next_page": "page_2"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/organization/usage/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [],
+                "has_more": false
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let provider = OpenAiApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let usage = provider.fetch_usage(&make_credentials()).await.unwrap();
+
+        let total = usage
+            .limits
+            .iter()
+            .find(|l| l.id == "monthly_budget")
+            .unwrap();
+        assert!((total.utilization - 30.0).abs() < 0.001);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_403_not_admin_key() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = OpenAiApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let result = provider.fetch_usage(&make_credentials()).await;
+
+        match result.unwrap_err() {
+            ProviderError::InvalidCredentials(msg) => assert!(msg.contains("admin key")),
+            err => panic!("Expected InvalidCredentials, got {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_429_rate_limited() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/organization/costs"))
+            .respond_with(ResponseTemplate::new(429))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let provider = OpenAiApiProvider::with_base_url(&mock_server.uri()).unwrap();
+        let result = provider.fetch_usage(&make_credentials()).await;
+
+        match result.unwrap_err() {
+            ProviderError::RateLimited(_) => {}
+            err => panic!("Expected RateLimited, got {:?}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_usage_missing_api_key() {
+        let provider = OpenAiApiProvider::new().unwrap();
+        let credentials = Credentials {
+            api_key: None,
+            ..make_credentials()
+        };
+
+        match provider.fetch_usage(&credentials).await.unwrap_err() {
+            ProviderError::MissingCredentials(field) => assert_eq!(field, "api_key"),
+            err => panic!("Expected MissingCredentials, got {:?}", err),
+        }
+    }
+
+    // ============================================================================
+    // Unit tests
+    // ============================================================================
+
+    #[test]
+    fn test_line_item_model() {
+        let cost = |line_item: Option<&str>| CostResult {
+            amount: CostAmount { value: 1.0 },
+            line_item: line_item.map(String::from),
+            project_id: None,
+        };
+        assert_eq!(cost(Some("gpt-4o, input")).model(), Some("gpt-4o"));
+        assert_eq!(cost(Some("web search")).model(), Some("web search"));
+        assert_eq!(cost(None).model(), None);
+    }
+
+    #[test]
+    fn test_validate_credentials() {
+        let provider = OpenAiApiProvider::new().unwrap();
+        assert!(provider.validate_credentials(&make_credentials()));
+
+        // Token budget is optional
+        let mut no_token_budget = make_credentials();
+        no_token_budget.extra.remove("monthly_token_budget");
+        assert!(provider.validate_credentials(&no_token_budget));
+
+        let mut no_budget = make_credentials();
+        no_budget.extra.remove("monthly_budget");
+        assert!(!provider.validate_credentials(&no_budget));
+    }
+}
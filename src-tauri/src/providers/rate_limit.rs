@@ -0,0 +1,146 @@
+//! Per-host request budget
+//!
+//! Every provider host gets its own token bucket: a few requests may go out
+//! back to back, after which they are spaced out at the refill rate. Accounts
+//! on different hosts never wait for each other, and accounts on the same host
+//! can be fetched in parallel without hammering it.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Size and refill rate of each host's bucket
+#[derive(Debug, Clone, Copy)]
+pub struct BucketPolicy {
+    /// Requests that may be sent back to back
+    pub capacity: u32,
+    /// Time to earn back one request
+    pub refill_every: Duration,
+}
+
+impl Default for BucketPolicy {
+    fn default() -> Self {
+        Self {
+            capacity: 4,
+            refill_every: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn full(policy: &BucketPolicy, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(policy.capacity),
+            updated: now,
+        }
+    }
+
+    /// Take a token, or return how long until one is available
+    fn take(&mut self, policy: &BucketPolicy, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated);
+        let earned = elapsed.as_secs_f64() / policy.refill_every.as_secs_f64();
+        self.tokens = (self.tokens + earned).min(f64::from(policy.capacity));
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(policy.refill_every.mul_f64(1.0 - self.tokens))
+        }
+    }
+}
+
+/// Token buckets keyed by host
+#[derive(Debug, Default)]
+pub struct HostBudget {
+    policy: BucketPolicy,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl HostBudget {
+    /// Wait until `host` has a request to spare, then spend it
+    pub async fn acquire(&self, host: &str) {
+        while let Err(wait) = self.try_acquire(host, Instant::now()) {
+            log::debug!("Request budget for {} spent, waiting {:?}", host, wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Spend a request for `host`, or return how long until one is available
+    fn try_acquire(&self, host: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        buckets
+            .entry(host.to_string())
+            .or_insert_with(|| TokenBucket::full(&self.policy, now))
+            .take(&self.policy, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget() -> HostBudget {
+        HostBudget {
+            policy: BucketPolicy {
+                capacity: 2,
+                refill_every: Duration::from_secs(4),
+            },
+            buckets: Mutex::default(),
+        }
+    }
+
+    #[test]
+    fn test_burst_then_wait_for_refill() {
+        let budget = budget();
+        let now = Instant::now();
+
+        assert!(budget.try_acquire("claude.ai", now).is_ok());
+        assert!(budget.try_acquire("claude.ai", now).is_ok());
+        assert_eq!(
+            budget.try_acquire("claude.ai", now),
+            Err(Duration::from_secs(4))
+        );
+
+        // Half a token earned: the wait shrinks accordingly
+        let later = now + Duration::from_secs(2);
+        assert_eq!(
+            budget.try_acquire("claude.ai", later),
+            Err(Duration::from_secs(2))
+        );
+        assert!(budget
+            .try_acquire("claude.ai", now + Duration::from_secs(4))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_hosts_have_separate_buckets() {
+        let budget = budget();
+        let now = Instant::now();
+
+        budget.try_acquire("claude.ai", now).unwrap();
+        budget.try_acquire("claude.ai", now).unwrap();
+
+        assert!(budget.try_acquire("claude.ai", now).is_err());
+        assert!(budget.try_acquire("api.openai.com", now).is_ok());
+    }
+
+    #[test]
+    fn test_refill_is_capped_at_capacity() {
+        let budget = budget();
+        let now = Instant::now();
+        budget.try_acquire("claude.ai", now).unwrap();
+
+        let later = now + Duration::from_secs(3600);
+        assert!(budget.try_acquire("claude.ai", later).is_ok());
+        assert!(budget.try_acquire("claude.ai", later).is_ok());
+        assert!(budget.try_acquire("claude.ai", later).is_err());
+    }
+}
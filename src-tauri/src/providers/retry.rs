@@ -0,0 +1,217 @@
+//! Retries for provider requests
+//!
+//! Transient failures (no response, 5xx) are retried with jittered exponential
+//! backoff. Everything else, including 401 and 429, is returned as is: a 429
+//! carries the server's `Retry-After` so the scheduler can hold the account
+//! back instead of hammering it.
+
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+use crate::error::ProviderError;
+
+/// How often and how patiently to retry a transient failure
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry (doubles each time)
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based): half the backoff plus up
+    /// to the other half at random, so clients don't retry in lockstep
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        let half = backoff / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+}
+
+/// Result of a retried request and how many attempts it took
+pub struct Retried<T> {
+    pub result: Result<T, ProviderError>,
+    pub attempts: u32,
+}
+
+/// Run `request` until it succeeds, fails with a non-transient error or runs
+/// out of attempts
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, mut request: F) -> Retried<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ProviderError>>,
+{
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match request().await {
+            Err(e) if e.is_transient() && attempts < policy.max_attempts => {
+                let delay = policy.delay(attempts);
+                log::info!(
+                    "Retrying in {:?} after attempt {}/{} failed: {}",
+                    delay,
+                    attempts,
+                    policy.max_attempts,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return Retried { result, attempts },
+        }
+    }
+}
+
+/// Error for a status the provider has no specific mapping for
+pub(crate) fn status_error(status: StatusCode, body: String) -> ProviderError {
+    if status.is_server_error() {
+        ProviderError::ServerError(status.as_u16(), body)
+    } else {
+        ProviderError::HttpError(format!("Unexpected status {}: {}", status, body))
+    }
+}
+
+/// `RateLimited` with the wait from the response's `Retry-After` header
+pub(crate) fn rate_limited(headers: &HeaderMap) -> ProviderError {
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, Utc::now()));
+    ProviderError::RateLimited(retry_after)
+}
+
+/// Parse `Retry-After` as delay seconds or an HTTP date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means "now"
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::cell::Cell;
+
+    fn no_wait(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_delay_grows_with_jitter_up_to_max() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(5),
+        };
+
+        for _ in 0..20 {
+            let first = policy.delay(1);
+            assert!(first >= Duration::from_secs(1) && first <= Duration::from_secs(2));
+            let capped = policy.delay(4);
+            assert!(capped >= Duration::from_millis(2500) && capped <= Duration::from_secs(5));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_until_success() {
+        let calls = Cell::new(0);
+        let retried = retry(&no_wait(3), || {
+            calls.set(calls.get() + 1);
+            let call = calls.get();
+            async move {
+                if call < 3 {
+                    Err(ProviderError::ServerError(502, String::new()))
+                } else {
+                    Ok(call)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(retried.result.unwrap(), 3);
+        assert_eq!(retried.attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let retried: Retried<()> = retry(&no_wait(2), || async {
+            Err(ProviderError::Network("connection reset".to_string()))
+        })
+        .await;
+
+        assert!(matches!(retried.result, Err(ProviderError::Network(_))));
+        assert_eq!(retried.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_never_retries_session_or_rate_limit_errors() {
+        for error in [
+            ProviderError::SessionExpired,
+            ProviderError::RateLimited(Some(Duration::from_secs(30))),
+        ] {
+            let error = Cell::new(Some(error));
+            let retried: Retried<()> = retry(&no_wait(3), || {
+                let error = error.take();
+                async move { Err(error.expect("called once")) }
+            })
+            .await;
+            assert_eq!(retried.attempts, 1);
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:30:00 GMT", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_status_error() {
+        assert!(matches!(
+            status_error(StatusCode::SERVICE_UNAVAILABLE, String::new()),
+            ProviderError::ServerError(503, _)
+        ));
+        assert!(matches!(
+            status_error(StatusCode::NOT_FOUND, String::new()),
+            ProviderError::HttpError(_)
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+//! Response-shape recording for schema drift detection
+//!
+//! Providers call `observe` with each raw JSON response. The shape (every
+//! key path and its value type) is picked up by `SchemaService`, which diffs
+//! it against the last known shape for that endpoint.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Key path -> value type, e.g. `"five_hour.utilization" -> "number"`
+///
+/// Array elements share one path ending in `[]` (`"data[].amount"`).
+pub type Shape = BTreeMap<String, String>;
+
+/// Shape of one provider endpoint's latest response
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub provider: String,
+    pub endpoint: String,
+    pub shape: Shape,
+}
+
+/// Responses observed since the last `take_observations` (latest per endpoint)
+static OBSERVED: Mutex<Vec<Observation>> = Mutex::new(Vec::new());
+
+/// Record the shape of a provider response
+pub fn observe(provider: &str, endpoint: &str, response: &Value) {
+    let observation = Observation {
+        provider: provider.to_string(),
+        endpoint: endpoint.to_string(),
+        shape: shape_of(response),
+    };
+
+    let mut observed = OBSERVED.lock().unwrap_or_else(|e| e.into_inner());
+    match observed
+        .iter_mut()
+        .find(|o| o.provider == provider && o.endpoint == endpoint)
+    {
+        // Pages of one endpoint add up to a single shape
+        Some(existing) => merge(&mut existing.shape, &observation.shape),
+        None => observed.push(observation),
+    }
+}
+
+/// Drain everything observed so far
+pub fn take_observations() -> Vec<Observation> {
+    std::mem::take(&mut *OBSERVED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Every key path in a JSON value and its type
+pub fn shape_of(value: &Value) -> Shape {
+    let mut shape = Shape::new();
+    collect(value, "", &mut shape);
+    shape
+}
+
+fn collect(value: &Value, path: &str, shape: &mut Shape) {
+    if !path.is_empty() {
+        insert(shape, path, type_name(value));
+    }
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                collect(child, &child_path, shape);
+            }
+        }
+        Value::Array(items) => {
+            let item_path = format!("{}[]", path);
+            for item in items {
+                collect(item, &item_path, shape);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Add a path, preferring a concrete type over null
+fn insert(shape: &mut Shape, path: &str, kind: &str) {
+    match shape.get(path) {
+        Some(existing) if existing != "null" || kind == "null" => {}
+        _ => {
+            shape.insert(path.to_string(), kind.to_string());
+        }
+    }
+}
+
+/// Fold `other` into `shape` (union of paths, concrete types win over null)
+pub fn merge(shape: &mut Shape, other: &Shape) {
+    for (path, kind) in other {
+        insert(shape, path, kind);
+    }
+}
+
+/// Fold `other` into `shape`, letting its concrete types replace the ones in `shape`
+///
+/// Paths only `shape` has are kept, since other accounts may still return them.
+pub fn overlay(shape: &mut Shape, other: &Shape) {
+    for (path, kind) in other {
+        if kind != "null" || !shape.contains_key(path) {
+            shape.insert(path.clone(), kind.clone());
+        }
+    }
+}
+
+/// A value whose type changed between two responses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeChange {
+    pub path: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Differences between the expected and actual response shape
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<TypeChange>,
+}
+
+impl SchemaDiff {
+    /// Whether the response changed shape
+    ///
+    /// Missing keys alone are not drift: accounts of one provider can return
+    /// different optional fields, and not every account is fetched every time.
+    pub fn is_drift(&self) -> bool {
+        !self.added.is_empty() || !self.changed.is_empty()
+    }
+}
+
+/// Compare two shapes
+///
+/// Nulls carry no type, so a value turning null (or back) is not drift, and
+/// neither are the paths under a value that is null or an empty array on the
+/// other side.
+pub fn diff(expected: &Shape, actual: &Shape) -> SchemaDiff {
+    let mut result = SchemaDiff::default();
+
+    for (path, kind) in actual {
+        match expected.get(path) {
+            None if !is_unknown_in(expected, path) => result.added.push(path.clone()),
+            Some(old) if old != kind && old != "null" && kind != "null" => {
+                result.changed.push(TypeChange {
+                    path: path.clone(),
+                    expected: old.clone(),
+                    actual: kind.clone(),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    for path in expected.keys() {
+        if !actual.contains_key(path) && !is_unknown_in(actual, path) {
+            result.removed.push(path.clone());
+        }
+    }
+
+    result
+}
+
+/// Whether `shape` says nothing about `path` because an ancestor is null or an empty array
+fn is_unknown_in(shape: &Shape, path: &str) -> bool {
+    ancestors(path).any(|ancestor| match shape.get(ancestor).map(String::as_str) {
+        Some("null") => true,
+        Some("array") => {
+            let items = format!("{}[]", ancestor);
+            !shape.contains_key(&items)
+        }
+        _ => false,
+    })
+}
+
+/// "a[].b.c" -> "a", "a[]", "a[].b"
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    let bytes = path.as_bytes();
+    (0..bytes.len()).filter_map(move |i| match bytes[i] {
+        // "a[].b" already yielded "a[]" at the bracket
+        b'.' if i > 0 && bytes[i - 1] != b']' => Some(&path[..i]),
+        b'[' => Some(&path[..i]),
+        b']' if i + 1 < bytes.len() => Some(&path[..=i]),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_shape_of() {
+        let shape = shape_of(&json!({
+            "five_hour": { "utilization": 12.0, "resets_at": null },
+            "data": [{ "amount": "1.5" }, { "amount": "2", "model": "opus" }],
+            "has_more": false
+        }));
+
+        assert_eq!(shape["five_hour"], "object");
+        assert_eq!(shape["five_hour.utilization"], "number");
+        assert_eq!(shape["five_hour.resets_at"], "null");
+        assert_eq!(shape["data"], "array");
+        assert_eq!(shape["data[]"], "object");
+        assert_eq!(shape["data[].model"], "string");
+        assert_eq!(shape["has_more"], "boolean");
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_changed() {
+        let expected = shape_of(&json!({
+            "five_hour": { "utilization": 12.0, "resets_at": "2025-01-15T17:00:00Z" },
+            "seven_day": { "utilization": 3.0, "resets_at": "2025-01-20T00:00:00Z" }
+        }));
+        let actual = shape_of(&json!({
+            "five_hour": { "utilization": "12", "resets_at": "2025-01-15T17:00:00Z" },
+            "weekly": { "utilization": 3.0, "resets_at": "2025-01-20T00:00:00Z" }
+        }));
+
+        let diff = diff(&expected, &actual);
+        assert_eq!(
+            diff.added,
+            vec!["weekly", "weekly.resets_at", "weekly.utilization"]
+        );
+        assert_eq!(
+            diff.removed,
+            vec!["seven_day", "seven_day.resets_at", "seven_day.utilization"]
+        );
+        assert_eq!(
+            diff.changed,
+            vec![TypeChange {
+                path: "five_hour.utilization".to_string(),
+                expected: "number".to_string(),
+                actual: "string".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_ignores_nulls_and_empty_arrays() {
+        let expected = shape_of(&json!({
+            "seven_day_opus": { "utilization": 1.0, "resets_at": "2025-01-20T00:00:00Z" },
+            "data": [{ "amount": "1.5" }]
+        }));
+        let actual = shape_of(&json!({
+            "seven_day_opus": null,
+            "data": []
+        }));
+
+        assert_eq!(diff(&expected, &actual), SchemaDiff::default());
+        assert_eq!(diff(&actual, &expected), SchemaDiff::default());
+    }
+
+    #[test]
+    fn test_missing_keys_are_not_drift() {
+        let expected = shape_of(&json!({ "five_hour": { "utilization": 1.0 }, "extra": 1 }));
+        let actual = shape_of(&json!({ "five_hour": { "utilization": 2.0 } }));
+
+        let diff = diff(&expected, &actual);
+        assert_eq!(diff.removed, vec!["extra"]);
+        assert!(!diff.is_drift());
+    }
+
+    #[test]
+    fn test_overlay_replaces_types_and_keeps_other_paths() {
+        let mut shape = shape_of(&json!({ "a": 1, "b": "x", "c": true }));
+        overlay(&mut shape, &shape_of(&json!({ "a": "1", "b": null })));
+
+        assert_eq!(shape["a"], "string");
+        assert_eq!(shape["b"], "string");
+        assert_eq!(shape["c"], "boolean");
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let found: Vec<&str> = ancestors("data[].results[].amount").collect();
+        assert_eq!(
+            found,
+            vec!["data", "data[]", "data[].results", "data[].results[]"]
+        );
+    }
+}
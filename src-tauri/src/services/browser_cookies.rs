@@ -0,0 +1,525 @@
+//! Cookies from local browser profiles
+//!
+//! Firefox keeps cookies in a plain `cookies.sqlite`. Chromium-family browsers
+//! on Linux encrypt cookie values with AES-128-CBC, keyed from a password the
+//! browser keeps in the Secret Service (`v11` values) or a fixed one (`v10`).
+//! Browsers hold their databases open, so each is copied (with its
+//! write-ahead log) into a private directory before it is read.
+
+use crate::error::AppError;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+
+/// A cookie found in a browser profile
+#[derive(Debug, Clone)]
+pub struct BrowserCookie {
+    /// Browser the cookie was found in (e.g. "Firefox")
+    pub browser: String,
+    /// Profile directory name
+    pub profile: String,
+    pub value: String,
+    /// None for session cookies
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Value and expiry of a cookie
+type CookieRow = (String, Option<DateTime<Utc>>);
+
+/// Firefox stored `expiry` in seconds until it switched to milliseconds;
+/// values past this are milliseconds
+const FIREFOX_EXPIRY_MILLIS_FROM: i64 = 100_000_000_000;
+
+/// Find the cookie `name` set for `domain` (or `.domain`) in every browser
+/// profile on this machine, latest expiry first
+///
+/// Databases are copied under `scratch` (the app's cache directory) while
+/// they are read. Profiles that can't be read are logged and skipped.
+pub fn find(scratch: &Path, domain: &str, name: &str) -> Vec<BrowserCookie> {
+    let mut cookies = Vec::new();
+
+    for profile in firefox_profiles() {
+        let db = profile.join("cookies.sqlite");
+        match with_copy(scratch, &db, |conn| read_firefox(conn, domain, name)) {
+            Ok(found) => {
+                cookies.extend(found.into_iter().map(|(value, expires_at)| BrowserCookie {
+                    browser: "Firefox".to_string(),
+                    profile: dir_name(&profile),
+                    value,
+                    expires_at,
+                }))
+            }
+            Err(e) => log::warn!("Failed to read Firefox cookies in {}: {}", db.display(), e),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    cookies.extend(chromium::find(scratch, domain, name));
+
+    cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.expires_at));
+    cookies
+}
+
+/// Directories holding Firefox profiles, relative to the home directory
+#[cfg(target_os = "linux")]
+const FIREFOX_ROOTS: &[&str] = &[
+    ".mozilla/firefox",
+    "snap/firefox/common/.mozilla/firefox",
+    ".var/app/org.mozilla.firefox/.mozilla/firefox",
+];
+#[cfg(target_os = "macos")]
+const FIREFOX_ROOTS: &[&str] = &["Library/Application Support/Firefox/Profiles"];
+#[cfg(windows)]
+const FIREFOX_ROOTS: &[&str] = &["AppData/Roaming/Mozilla/Firefox/Profiles"];
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const FIREFOX_ROOTS: &[&str] = &[];
+
+/// Firefox profile directories that have a cookie database
+fn firefox_profiles() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+
+    FIREFOX_ROOTS
+        .iter()
+        .filter_map(|root| std::fs::read_dir(home.join(root)).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|dir| dir.join("cookies.sqlite").is_file())
+        .collect()
+}
+
+fn read_firefox(conn: &Connection, domain: &str, name: &str) -> Result<Vec<CookieRow>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT value, expiry FROM moz_cookies
+         WHERE name = ?1 AND (host = ?2 OR host = '.' || ?2)",
+    )?;
+    let rows = stmt.query_map(params![name, domain], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })?;
+
+    let mut cookies = Vec::new();
+    for row in rows {
+        let (value, expiry) = row?;
+        let expires_at = if expiry >= FIREFOX_EXPIRY_MILLIS_FROM {
+            DateTime::from_timestamp_millis(expiry)
+        } else {
+            DateTime::from_timestamp(expiry, 0)
+        };
+        cookies.push((value, expires_at));
+    }
+    Ok(cookies)
+}
+
+/// Run `f` on a copy of the database at `path`, so a browser's lock on the
+/// original doesn't get in the way
+///
+/// The copy lives in a new directory under `scratch` that only the current
+/// user can open, and is removed afterwards.
+fn with_copy<T>(
+    scratch: &Path,
+    path: &Path,
+    f: impl FnOnce(&Connection) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let dir = scratch.join(format!("cookies-{}", uuid::Uuid::new_v4()));
+    create_private_dir(&dir)
+        .map_err(|e| AppError::Store(format!("Failed to create {}: {}", dir.display(), e)))?;
+
+    let result = (|| {
+        let copy = dir.join("cookies.sqlite");
+        std::fs::copy(path, &copy)
+            .map_err(|e| AppError::Store(format!("Failed to copy {}: {}", path.display(), e)))?;
+
+        // Recent changes may only be in the write-ahead log
+        let wal = PathBuf::from(format!("{}-wal", path.display()));
+        if wal.is_file() {
+            std::fs::copy(&wal, dir.join("cookies.sqlite-wal"))
+                .map_err(|e| AppError::Store(format!("Failed to copy {}: {}", wal.display(), e)))?;
+        }
+
+        let conn = Connection::open(&copy)?;
+        f(&conn)
+    })();
+
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Chromium-family browsers on Linux
+#[cfg(target_os = "linux")]
+mod chromium {
+    use super::{dir_name, with_copy, BrowserCookie};
+    use crate::error::AppError;
+    use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+    use chrono::{DateTime, Utc};
+    use dbus_secret_service::{EncryptionType, SecretService};
+    use rusqlite::{params, Connection};
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+    /// Browser name, config directory under `~/.config` and the `application`
+    /// attribute of its Secret Service password
+    const BROWSERS: &[(&str, &str, &str)] = &[
+        ("Google Chrome", "google-chrome", "chrome"),
+        ("Chromium", "chromium", "chromium"),
+        ("Brave", "BraveSoftware/Brave-Browser", "brave"),
+        ("Microsoft Edge", "microsoft-edge", "microsoft-edge"),
+        ("Vivaldi", "vivaldi", "vivaldi"),
+    ];
+
+    /// Password of `v10` values (and of `v11` ones without a stored password)
+    const FALLBACK_PASSWORD: &[u8] = b"peanuts";
+    const SALT: &[u8] = b"saltysalt";
+    const IV: [u8; 16] = [b' '; 16];
+
+    /// From this database version on, decrypted values start with the
+    /// SHA-256 of the cookie's host
+    const HOST_HASH_VERSION: i64 = 24;
+
+    /// Microseconds between 1601-01-01 (Chromium's epoch) and 1970-01-01
+    const EPOCH_OFFSET_MICROS: i64 = 11_644_473_600_000_000;
+
+    pub(super) fn find(scratch: &Path, domain: &str, name: &str) -> Vec<BrowserCookie> {
+        let Some(config) = dirs::config_dir() else {
+            return Vec::new();
+        };
+
+        let mut cookies = Vec::new();
+        for (browser, dir, application) in BROWSERS {
+            let mut passwords = None;
+            for profile in profiles(&config.join(dir)) {
+                let Some(db) = ["Network/Cookies", "Cookies"]
+                    .iter()
+                    .map(|file| profile.join(file))
+                    .find(|path| path.is_file())
+                else {
+                    continue;
+                };
+
+                let found = with_copy(scratch, &db, |conn| {
+                    let rows = read(conn, domain, name)?;
+                    // Only ask the Secret Service when a value needs it
+                    let none = Vec::new();
+                    let passwords = if rows.iter().any(|row| row.encrypted.starts_with(b"v11")) {
+                        passwords.get_or_insert_with(|| stored_passwords(application))
+                    } else {
+                        &none
+                    };
+                    let strip_hash = version(conn) >= HOST_HASH_VERSION;
+                    Ok(rows
+                        .into_iter()
+                        .filter_map(|row| {
+                            let value = if row.encrypted.is_empty() {
+                                Some(row.value)
+                            } else {
+                                decrypt(&row.encrypted, passwords, strip_hash)
+                            };
+                            if value.is_none() {
+                                log::debug!("Could not decrypt a {} cookie", browser);
+                            }
+                            value.map(|value| (value, row.expires_at))
+                        })
+                        .collect::<Vec<_>>())
+                });
+
+                match found {
+                    Ok(found) => {
+                        cookies.extend(found.into_iter().map(|(value, expires_at)| BrowserCookie {
+                            browser: browser.to_string(),
+                            profile: dir_name(&profile),
+                            value,
+                            expires_at,
+                        }))
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "Failed to read {} cookies in {}: {}",
+                            browser,
+                            db.display(),
+                            e
+                        )
+                    }
+                }
+            }
+        }
+        cookies
+    }
+
+    /// `Default` and `Profile N` directories of a browser
+    fn profiles(config: &std::path::Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(config) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                let name = dir_name(path);
+                name == "Default" || name.starts_with("Profile ")
+            })
+            .collect()
+    }
+
+    pub(super) struct Row {
+        pub value: String,
+        pub encrypted: Vec<u8>,
+        pub expires_at: Option<DateTime<Utc>>,
+    }
+
+    pub(super) fn read(conn: &Connection, domain: &str, name: &str) -> Result<Vec<Row>, AppError> {
+        let mut stmt = conn.prepare(
+            "SELECT value, encrypted_value, expires_utc FROM cookies
+             WHERE name = ?1 AND (host_key = ?2 OR host_key = '.' || ?2)",
+        )?;
+        let rows = stmt.query_map(params![name, domain], |row| {
+            let expires_utc: i64 = row.get(2)?;
+            Ok(Row {
+                value: row.get(0)?,
+                encrypted: row.get(1)?,
+                // 0 marks a session cookie
+                expires_at: (expires_utc > 0)
+                    .then(|| DateTime::from_timestamp_micros(expires_utc - EPOCH_OFFSET_MICROS))
+                    .flatten(),
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Cookie database version (0 if unknown)
+    pub(super) fn version(conn: &Connection) -> i64 {
+        conn.query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .ok()
+        .and_then(|version| version.parse().ok())
+        .unwrap_or(0)
+    }
+
+    /// Passwords the browser stored in the Secret Service (unlocked items only;
+    /// a locked keyring is never prompted for)
+    fn stored_passwords(application: &str) -> Vec<Vec<u8>> {
+        let service = match SecretService::connect(EncryptionType::Dh) {
+            Ok(service) => service,
+            Err(e) => {
+                log::debug!("Secret Service unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+        match service.search_items(HashMap::from([("application", application)])) {
+            Ok(items) => items
+                .unlocked
+                .iter()
+                .filter_map(|item| item.get_secret().ok())
+                .collect(),
+            Err(e) => {
+                log::debug!(
+                    "Failed to look up the {} cookie password: {}",
+                    application,
+                    e
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// Decrypt a `v10`/`v11` cookie value
+    pub(super) fn decrypt(
+        encrypted: &[u8],
+        passwords: &[Vec<u8>],
+        strip_hash: bool,
+    ) -> Option<String> {
+        if encrypted.len() < 3 {
+            return None;
+        }
+        let (prefix, ciphertext) = encrypted.split_at(3);
+        let candidates: Vec<&[u8]> = match prefix {
+            b"v10" => vec![FALLBACK_PASSWORD],
+            b"v11" => passwords
+                .iter()
+                .map(Vec::as_slice)
+                .chain([FALLBACK_PASSWORD, b"".as_slice()])
+                .collect(),
+            _ => return None,
+        };
+
+        candidates.into_iter().find_map(|password| {
+            let mut buf = ciphertext.to_vec();
+            let plaintext = Aes128CbcDec::new(&key(password).into(), &IV.into())
+                .decrypt_padded_mut::<Pkcs7>(&mut buf)
+                .ok()?;
+            let plaintext = if strip_hash {
+                plaintext.get(32..)?
+            } else {
+                plaintext
+            };
+            String::from_utf8(plaintext.to_vec()).ok()
+        })
+    }
+
+    pub(super) fn key(password: &[u8]) -> [u8; 16] {
+        let mut key = [0u8; 16];
+        pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password, SALT, 1, &mut key);
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn firefox_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_cookies (host TEXT, name TEXT, value TEXT, expiry INTEGER);
+             INSERT INTO moz_cookies VALUES ('.claude.ai', 'sessionKey', 'sk-ant-sid01-a', 1900000000);
+             INSERT INTO moz_cookies VALUES ('claude.ai', 'sessionKey', 'sk-ant-sid01-b', 1900000000000);
+             INSERT INTO moz_cookies VALUES ('.claude.ai', 'other', 'x', 1900000000);
+             INSERT INTO moz_cookies VALUES ('evilclaude.ai', 'sessionKey', 'y', 1900000000);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_read_firefox() {
+        let cookies = read_firefox(&firefox_db(), "claude.ai", "sessionKey").unwrap();
+
+        let expires_at = DateTime::from_timestamp(1_900_000_000, 0);
+        assert_eq!(
+            cookies,
+            vec![
+                ("sk-ant-sid01-a".to_string(), expires_at),
+                ("sk-ant-sid01-b".to_string(), expires_at),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_copy_reads_the_write_ahead_log() {
+        let dir = std::env::temp_dir().join(format!("ai-pulse-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cookies.sqlite");
+
+        // Keep the original open, like a running browser, so the row stays in the WAL
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA wal_autocheckpoint = 0;
+             CREATE TABLE moz_cookies (host TEXT, name TEXT, value TEXT, expiry INTEGER);
+             INSERT INTO moz_cookies VALUES ('.claude.ai', 'sessionKey', 'sk-ant-sid01-a', 1900000000);",
+        )
+        .unwrap();
+
+        let scratch = dir.join("scratch");
+        let cookies = with_copy(&scratch, &path, |copy| {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let dir = std::fs::read_dir(&scratch)
+                    .unwrap()
+                    .next()
+                    .unwrap()
+                    .unwrap();
+                let mode = dir.metadata().unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o700);
+            }
+            read_firefox(copy, "claude.ai", "sessionKey")
+        });
+        assert_eq!(cookies.unwrap().len(), 1);
+        // The copy is gone afterwards
+        assert_eq!(std::fs::read_dir(&scratch).unwrap().count(), 0);
+
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    mod chromium {
+        use super::super::chromium::*;
+        use aes::cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit};
+        use rusqlite::{params, Connection};
+
+        fn encrypt(prefix: &str, password: &[u8], plaintext: &[u8]) -> Vec<u8> {
+            let ciphertext =
+                cbc::Encryptor::<aes::Aes128>::new(&key(password).into(), &[b' '; 16].into())
+                    .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+            [prefix.as_bytes(), &ciphertext].concat()
+        }
+
+        #[test]
+        fn test_decrypt() {
+            let v10 = encrypt("v10", b"peanuts", b"sk-ant-sid01-a");
+            assert_eq!(decrypt(&v10, &[], false).as_deref(), Some("sk-ant-sid01-a"));
+
+            let v11 = encrypt("v11", b"stored", b"sk-ant-sid01-b");
+            assert_eq!(
+                decrypt(&v11, &[b"other".to_vec(), b"stored".to_vec()], false).as_deref(),
+                Some("sk-ant-sid01-b")
+            );
+            assert_eq!(decrypt(&v11, &[], false), None);
+
+            let hashed = encrypt(
+                "v10",
+                b"peanuts",
+                &[[0u8; 32].as_slice(), b"sk-ant-sid01-c"].concat(),
+            );
+            assert_eq!(
+                decrypt(&hashed, &[], true).as_deref(),
+                Some("sk-ant-sid01-c")
+            );
+
+            assert_eq!(decrypt(b"plain", &[], false), None);
+        }
+
+        #[test]
+        fn test_read() {
+            let conn = Connection::open_in_memory().unwrap();
+            conn.execute_batch(
+                "CREATE TABLE meta (key TEXT, value TEXT);
+                 INSERT INTO meta VALUES ('version', '24');
+                 CREATE TABLE cookies (host_key TEXT, name TEXT, value TEXT, encrypted_value BLOB, expires_utc INTEGER);",
+            )
+            .unwrap();
+            let encrypted = encrypt("v10", b"peanuts", b"sk-ant-sid01-a");
+            conn.execute(
+                "INSERT INTO cookies VALUES ('.claude.ai', 'sessionKey', '', ?1, 13380000000000000)",
+                params![encrypted],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO cookies VALUES ('claude.ai', 'sessionKey', 'sk-ant-sid01-b', x'', 0)",
+                [],
+            )
+            .unwrap();
+
+            assert_eq!(version(&conn), 24);
+            let rows = read(&conn, "claude.ai", "sessionKey").unwrap();
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0].encrypted, encrypted);
+            assert_eq!(
+                rows[0].expires_at.map(|at| at.timestamp()),
+                Some(1_735_526_400)
+            );
+            assert_eq!(rows[1].value, "sk-ant-sid01-b");
+            assert_eq!(rows[1].expires_at, None);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::error::{AppError, ProviderError};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Whether an account is being polled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Polled normally
+    #[default]
+    Closed,
+    /// Too many failures; only probed on a slow schedule
+    Open,
+    /// A probe is due or in flight; one success closes the circuit
+    HalfOpen,
+}
+
+/// Kinds of failure, each with its own breaker policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// Expired session or rejected credentials
+    Session,
+    /// Blocked by Cloudflare
+    Blocked,
+    /// Rate limited by the provider
+    RateLimited,
+    /// Network errors, server errors and unexpected responses
+    Transient,
+}
+
+/// When to open the circuit and how often to probe it while open
+#[derive(Debug, Clone, Copy)]
+pub struct BreakerPolicy {
+    /// Consecutive failures of the class that open the circuit
+    pub failure_threshold: u32,
+    /// Wait before the first probe
+    pub probe_after: Duration,
+    /// Longest wait between probes (the wait doubles after each failed probe)
+    pub max_probe_after: Duration,
+}
+
+impl ErrorClass {
+    /// Class of a fetch error (`None` for app-side errors that say nothing about the account)
+    pub fn of(error: &AppError) -> Option<Self> {
+        let AppError::Provider(error) = error else {
+            return None;
+        };
+        Some(match error {
+            ProviderError::SessionExpired
+            | ProviderError::MissingCredentials(_)
+            | ProviderError::InvalidCredentials(_) => Self::Session,
+            ProviderError::CloudflareBlocked => Self::Blocked,
+            ProviderError::RateLimited(_) => Self::RateLimited,
+            ProviderError::HttpError(_)
+            | ProviderError::Network(_)
+            | ProviderError::ServerError(..)
+            | ProviderError::ParseError(_) => Self::Transient,
+        })
+    }
+
+    pub fn policy(self) -> BreakerPolicy {
+        match self {
+            Self::Session => BreakerPolicy {
+                failure_threshold: 3,
+                probe_after: Duration::minutes(15),
+                max_probe_after: Duration::hours(2),
+            },
+            Self::Blocked => BreakerPolicy {
+                failure_threshold: 2,
+                probe_after: Duration::minutes(10),
+                max_probe_after: Duration::hours(2),
+            },
+            Self::RateLimited => BreakerPolicy {
+                failure_threshold: 3,
+                probe_after: Duration::minutes(5),
+                max_probe_after: Duration::hours(1),
+            },
+            Self::Transient => BreakerPolicy {
+                failure_threshold: 5,
+                probe_after: Duration::minutes(2),
+                max_probe_after: Duration::minutes(30),
+            },
+        }
+    }
+}
+
+/// Per-account circuit breaker
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitBreaker {
+    pub state: CircuitState,
+    /// Class of the current run of failures
+    pub error_class: Option<ErrorClass>,
+    /// Consecutive failures of `error_class`
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// When the state last changed
+    pub changed_at: Option<DateTime<Utc>>,
+    /// When the next probe is allowed (open circuits only)
+    pub next_probe_at: Option<DateTime<Utc>>,
+    /// Current wait between probes
+    #[serde(skip)]
+    probe_interval: Option<Duration>,
+}
+
+/// Event payload for a circuit state change
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitStateEvent {
+    pub account_id: String,
+    pub previous: CircuitState,
+    #[serde(flatten)]
+    pub circuit: CircuitBreaker,
+}
+
+impl CircuitBreaker {
+    /// Whether the account may be fetched now
+    ///
+    /// An open circuit whose probe is due moves to half-open and lets the probe through.
+    pub fn allow(&mut self, now: DateTime<Utc>) -> bool {
+        match self.state {
+            CircuitState::Closed | CircuitState::HalfOpen => true,
+            CircuitState::Open => {
+                if self.next_probe_at.map_or(true, |at| now >= at) {
+                    self.transition(CircuitState::HalfOpen, now);
+                    self.next_probe_at = None;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Probe an open circuit on the next fetch (e.g., after the system wakes)
+    pub fn probe_now(&mut self, now: DateTime<Utc>) {
+        if self.state == CircuitState::Open {
+            self.next_probe_at = Some(now);
+        }
+    }
+
+    /// A fetch succeeded: close the circuit
+    pub fn record_success(&mut self, now: DateTime<Utc>) {
+        if self.state != CircuitState::Closed {
+            self.transition(CircuitState::Closed, now);
+        }
+        self.error_class = None;
+        self.consecutive_failures = 0;
+        self.last_error = None;
+        self.next_probe_at = None;
+        self.probe_interval = None;
+    }
+
+    /// A fetch failed: open the circuit once the class's threshold is reached,
+    /// or reopen it with a longer wait if a probe failed
+    pub fn record_failure(&mut self, class: ErrorClass, error: String, now: DateTime<Utc>) {
+        if self.error_class == Some(class) {
+            self.consecutive_failures += 1;
+        } else {
+            self.error_class = Some(class);
+            self.consecutive_failures = 1;
+        }
+        self.last_error = Some(error);
+
+        let policy = class.policy();
+        let interval = match (self.state, self.probe_interval) {
+            (CircuitState::HalfOpen, Some(previous)) => (previous * 2).min(policy.max_probe_after),
+            (CircuitState::HalfOpen, None) => policy.probe_after,
+            (CircuitState::Closed, _) if self.consecutive_failures >= policy.failure_threshold => {
+                policy.probe_after
+            }
+            _ => return,
+        };
+
+        self.probe_interval = Some(interval);
+        self.next_probe_at = Some(now + interval);
+        self.transition(CircuitState::Open, now);
+    }
+
+    fn transition(&mut self, state: CircuitState, now: DateTime<Utc>) {
+        self.state = state;
+        self.changed_at = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fail(breaker: &mut CircuitBreaker, class: ErrorClass, now: DateTime<Utc>) {
+        breaker.record_failure(class, "boom".to_string(), now);
+    }
+
+    #[test]
+    fn test_opens_at_class_threshold() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::default();
+
+        fail(&mut breaker, ErrorClass::Blocked, now);
+        assert_eq!(breaker.state, CircuitState::Closed);
+        fail(&mut breaker, ErrorClass::Blocked, now);
+
+        assert_eq!(breaker.state, CircuitState::Open);
+        assert_eq!(breaker.next_probe_at, Some(now + Duration::minutes(10)));
+        assert!(!breaker.allow(now + Duration::minutes(5)));
+    }
+
+    #[test]
+    fn test_different_classes_count_separately() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::default();
+
+        fail(&mut breaker, ErrorClass::Session, now);
+        fail(&mut breaker, ErrorClass::Session, now);
+        fail(&mut breaker, ErrorClass::Transient, now);
+        fail(&mut breaker, ErrorClass::Session, now);
+
+        assert_eq!(breaker.state, CircuitState::Closed);
+        assert_eq!(breaker.consecutive_failures, 1);
+    }
+
+    #[test]
+    fn test_probe_success_closes_circuit() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::default();
+        for _ in 0..3 {
+            fail(&mut breaker, ErrorClass::Session, now);
+        }
+
+        let probe_at = now + Duration::minutes(15);
+        assert!(breaker.allow(probe_at));
+        assert_eq!(breaker.state, CircuitState::HalfOpen);
+
+        breaker.record_success(probe_at);
+        assert_eq!(breaker.state, CircuitState::Closed);
+        assert_eq!(breaker.consecutive_failures, 0);
+        assert_eq!(breaker.changed_at, Some(probe_at));
+    }
+
+    #[test]
+    fn test_failed_probe_backs_off_up_to_max() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::default();
+        for _ in 0..5 {
+            fail(&mut breaker, ErrorClass::Transient, now);
+        }
+
+        let mut waits = Vec::new();
+        for _ in 0..6 {
+            let at = breaker.next_probe_at.unwrap();
+            assert!(breaker.allow(at));
+            fail(&mut breaker, ErrorClass::Transient, at);
+            assert_eq!(breaker.state, CircuitState::Open);
+            waits.push((breaker.next_probe_at.unwrap() - at).num_minutes());
+        }
+
+        assert_eq!(waits, vec![4, 8, 16, 30, 30, 30]);
+        assert!(!breaker.allow(breaker.changed_at.unwrap()));
+    }
+
+    #[test]
+    fn test_probe_now() {
+        let now = Utc::now();
+        let mut breaker = CircuitBreaker::default();
+        for _ in 0..3 {
+            fail(&mut breaker, ErrorClass::RateLimited, now);
+        }
+        assert!(!breaker.allow(now));
+
+        breaker.probe_now(now);
+        assert!(breaker.allow(now));
+    }
+
+    #[test]
+    fn test_error_class_of() {
+        assert_eq!(
+            ErrorClass::of(&ProviderError::SessionExpired.into()),
+            Some(ErrorClass::Session)
+        );
+        assert_eq!(
+            ErrorClass::of(&ProviderError::ServerError(502, String::new()).into()),
+            Some(ErrorClass::Transient)
+        );
+        assert_eq!(ErrorClass::of(&AppError::Store("locked".to_string())), None);
+    }
+}
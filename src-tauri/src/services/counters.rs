@@ -0,0 +1,114 @@
+use crate::error::AppError;
+use crate::models::CounterEvent;
+use crate::services::CredentialService;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "counters.json";
+const EVENTS_KEY: &str = "events";
+
+/// Serializes read-modify-write of the event log
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct CounterService;
+
+impl CounterService {
+    /// Provider ID of counter accounts
+    pub const PROVIDER_ID: &'static str = "counter";
+
+    /// Events older than this are dropped (longest window a counter can use)
+    pub const MAX_RETENTION_HOURS: i64 = 31 * 24;
+
+    /// Logged uses of a counter account, oldest first
+    pub fn events(app: &AppHandle, account_id: &str) -> Result<Vec<CounterEvent>, AppError> {
+        let mut all = Self::load(app)?;
+        Ok(all.remove(account_id).unwrap_or_default())
+    }
+
+    /// Log `amount` uses for a counter account
+    pub fn increment(app: &AppHandle, account_id: &str, amount: u32) -> Result<(), AppError> {
+        let account = CredentialService::get_account(app, account_id)?
+            .ok_or_else(|| AppError::InvalidInput(format!("Account not found: {}", account_id)))?;
+        if account.provider != Self::PROVIDER_ID {
+            return Err(AppError::InvalidInput(format!(
+                "Account {} is not a counter",
+                account.name
+            )));
+        }
+        if amount == 0 {
+            return Err(AppError::InvalidInput(
+                "Amount must be at least 1".to_string(),
+            ));
+        }
+
+        let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        let mut all = Self::load(app)?;
+        let events = all.entry(account_id.to_string()).or_default();
+        events.push(CounterEvent { at: now, amount });
+        prune(events, now);
+        Self::save(app, &all)
+    }
+
+    /// Forget every logged use of an account (e.g., when it is deleted)
+    pub fn clear(app: &AppHandle, account_id: &str) -> Result<(), AppError> {
+        let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut all = Self::load(app)?;
+        if all.remove(account_id).is_some() {
+            Self::save(app, &all)?;
+        }
+        Ok(())
+    }
+
+    fn load(app: &AppHandle) -> Result<HashMap<String, Vec<CounterEvent>>, AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+        match store.get(EVENTS_KEY) {
+            Some(value) => Ok(serde_json::from_value(value.clone())?),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    fn save(app: &AppHandle, all: &HashMap<String, Vec<CounterEvent>>) -> Result<(), AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+        store.set(EVENTS_KEY, serde_json::to_value(all)?);
+        store.save().map_err(|e| AppError::Store(e.to_string()))
+    }
+}
+
+/// Drop events that no counter window can reach anymore
+fn prune(events: &mut Vec<CounterEvent>, now: DateTime<Utc>) {
+    let cutoff = now - Duration::hours(CounterService::MAX_RETENTION_HOURS);
+    events.retain(|event| event.at > cutoff);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_drops_old_events() {
+        let now = Utc::now();
+        let mut events = vec![
+            CounterEvent {
+                at: now - Duration::days(40),
+                amount: 3,
+            },
+            CounterEvent {
+                at: now - Duration::hours(2),
+                amount: 1,
+            },
+        ];
+
+        prune(&mut events, now);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].amount, 1);
+    }
+}
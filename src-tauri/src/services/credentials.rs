@@ -1,19 +1,23 @@
 use crate::error::AppError;
-use crate::models::{Account, Credentials};
+use crate::models::{Account, Credentials, CredentialsIssue};
 use crate::services::crypto;
+use crate::services::secrets::{SecretBackend, SecretStore};
+use crate::services::vault::{VaultKey, VaultService};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tauri::AppHandle;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager, State};
 use tauri_plugin_store::StoreExt;
 
 const STORE_FILE: &str = "credentials.json";
 const ACCOUNTS_KEY: &str = "accounts";
 const VERSION_KEY: &str = "version";
-const CURRENT_VERSION: u32 = 3; // v3: encrypted credentials
+/// Whether every secret is sealed with the vault passphrase
+const SEALED_KEY: &str = "sealed";
+const CURRENT_VERSION: u32 = 4; // v4: secrets in the platform secret store
 
-/// Prefix to identify encrypted values
-const ENCRYPTED_PREFIX: &str = "enc:v1:";
+/// Prefix of values encrypted with the legacy machine-derived key (v3)
+const LEGACY_ENCRYPTED_PREFIX: &str = "enc:v1:";
 
 /// Storage format for credential store (v2)
 /// This struct documents the storage schema but is not directly constructed.
@@ -27,72 +31,247 @@ pub struct CredentialStore {
 pub struct CredentialService;
 
 impl CredentialService {
-    /// Encrypt sensitive credential fields
-    fn encrypt_credentials(credentials: &Credentials) -> Credentials {
-        Credentials {
-            org_id: credentials.org_id.clone(),
-            session_key: credentials.session_key.as_ref().map(|key| {
-                if key.starts_with(ENCRYPTED_PREFIX) {
-                    // Already encrypted
-                    key.clone()
-                } else {
-                    match crypto::encrypt(key) {
-                        Ok(encrypted) => format!("{}{}", ENCRYPTED_PREFIX, encrypted),
-                        Err(e) => {
-                            log::error!("Failed to encrypt session_key: {}", e);
-                            key.clone() // Fall back to plaintext on error
-                        }
-                    }
+    /// The secret store, opened on first use
+    fn secrets(app: &AppHandle) -> Result<State<'_, SecretStore>, AppError> {
+        if app.try_state::<SecretStore>().is_none() {
+            let dir = app.path().app_data_dir()?;
+            app.manage(SecretStore::open(&dir));
+        }
+        Ok(app.state::<SecretStore>())
+    }
+
+    /// Name of an account's secret in the secret store
+    fn secret_name(account_id: &str, field: &str) -> String {
+        format!("{}/{}", account_id, field)
+    }
+
+    /// Apply `f` to every secret credential field (session key, API key and
+    /// the string values of custom provider fields); `None` removes the field
+    fn map_secrets(
+        credentials: &Credentials,
+        mut f: impl FnMut(&str, &str) -> Result<Option<String>, AppError>,
+    ) -> Result<Credentials, AppError> {
+        let mut mapped = credentials.clone();
+        mapped.session_key = match &credentials.session_key {
+            Some(value) => f("session_key", value)?,
+            None => None,
+        };
+        mapped.api_key = match &credentials.api_key {
+            Some(value) => f("api_key", value)?,
+            None => None,
+        };
+        for (key, value) in &credentials.extra {
+            let serde_json::Value::String(value) = value else {
+                continue;
+            };
+            match f(&format!("extra.{}", key), value)? {
+                Some(value) => {
+                    mapped
+                        .extra
+                        .insert(key.clone(), serde_json::Value::String(value));
                 }
-            }),
-            api_key: credentials.api_key.as_ref().map(|key| {
-                if key.starts_with(ENCRYPTED_PREFIX) {
-                    key.clone()
-                } else {
-                    match crypto::encrypt(key) {
-                        Ok(encrypted) => format!("{}{}", ENCRYPTED_PREFIX, encrypted),
-                        Err(e) => {
-                            log::error!("Failed to encrypt api_key: {}", e);
-                            key.clone()
-                        }
-                    }
+                None => {
+                    mapped.extra.remove(key);
                 }
-            }),
+            }
         }
+        Ok(mapped)
     }
 
-    /// Decrypt sensitive credential fields
-    fn decrypt_credentials(credentials: &Credentials) -> Credentials {
-        Credentials {
-            org_id: credentials.org_id.clone(),
-            session_key: credentials.session_key.as_ref().map(|key| {
-                if let Some(encrypted) = key.strip_prefix(ENCRYPTED_PREFIX) {
-                    match crypto::decrypt(encrypted) {
-                        Ok(decrypted) => decrypted,
-                        Err(e) => {
-                            log::error!("Failed to decrypt session_key: {}", e);
-                            key.clone() // Return as-is if decryption fails
-                        }
-                    }
-                } else {
-                    // Not encrypted (legacy or plaintext)
-                    key.clone()
+    /// Secret fields of stored credentials and the backend each one is in
+    fn stored_secrets(credentials: &Credentials) -> Vec<(String, SecretBackend)> {
+        let mut stored = Vec::new();
+        let _ = Self::map_secrets(credentials, |field, value| {
+            if let Some(backend) = SecretBackend::from_reference(value) {
+                stored.push((field.to_string(), backend));
+            }
+            Ok(None)
+        });
+        stored
+    }
+
+    /// Move secret fields into the secret store, returning the credentials to
+    /// keep in credentials.json
+    ///
+    /// Values may be plaintext, legacy `enc:v1:` values or references to
+    /// secrets that are already stored. New secrets are sealed with the vault
+    /// passphrase if one is set. Secrets of `previous` that are no longer set,
+    /// or live in another backend now, are deleted.
+    fn store_secrets(
+        secrets: &SecretStore,
+        vault: &VaultKey,
+        account_id: &str,
+        credentials: &Credentials,
+        previous: Option<&Credentials>,
+    ) -> Result<Credentials, AppError> {
+        let stored = Self::map_secrets(credentials, |field, value| {
+            if SecretBackend::from_reference(value).is_some() {
+                // Already stored
+                return Ok(Some(value.to_string()));
+            }
+            let value = match value.strip_prefix(LEGACY_ENCRYPTED_PREFIX) {
+                Some(encrypted) => crypto::decrypt(&crypto::legacy_key(), encrypted)?,
+                None => value.to_string(),
+            };
+            secrets
+                .put(&Self::secret_name(account_id, field), &vault.seal(&value)?)
+                .map(Some)
+        })?;
+
+        let current = Self::stored_secrets(&stored);
+        for (field, backend) in previous.map(Self::stored_secrets).unwrap_or_default() {
+            if !current.contains(&(field.clone(), backend)) {
+                secrets.delete(backend, &Self::secret_name(account_id, &field))?;
+            }
+        }
+
+        Ok(stored)
+    }
+
+    /// Fields of `credentials` that hold a secret
+    fn secret_fields(credentials: &Credentials) -> Vec<String> {
+        let mut fields = Vec::new();
+        let _ = Self::map_secrets(credentials, |field, _| {
+            fields.push(field.to_string());
+            Ok(None)
+        });
+        fields
+    }
+
+    /// Record that `field` of an account can't be read (the first reason is kept)
+    fn flag_unreadable(issue: &mut Option<CredentialsIssue>, field: &str, reason: &str) {
+        let issue = issue.get_or_insert_with(|| CredentialsIssue {
+            fields: Vec::new(),
+            reason: reason.to_string(),
+        });
+        if !issue.fields.iter().any(|f| f == field) {
+            issue.fields.push(field.to_string());
+        }
+    }
+
+    /// Read secret fields back from the secret store, unsealing them if they
+    /// were sealed with the vault passphrase
+    ///
+    /// Fails closed: secrets that are missing or can't be decrypted are left
+    /// out and reported in the returned issue, never replaced by what is stored.
+    /// A locked vault or an unreachable secret store is an error.
+    fn load_secrets(
+        secrets: &SecretStore,
+        vault: &VaultKey,
+        account_id: &str,
+        credentials: &Credentials,
+    ) -> Result<(Credentials, Option<CredentialsIssue>), AppError> {
+        let mut issue = None;
+        let loaded = Self::map_secrets(credentials, |field, value| {
+            let Some(backend) = SecretBackend::from_reference(value) else {
+                if value.starts_with(LEGACY_ENCRYPTED_PREFIX) {
+                    // Ciphertext must never be handed out as the secret
+                    log::error!("{} of account {} was never migrated", field, account_id);
+                    Self::flag_unreadable(&mut issue, field, "Not moved to the secret store");
+                    return Ok(None);
                 }
-            }),
-            api_key: credentials.api_key.as_ref().map(|key| {
-                if let Some(encrypted) = key.strip_prefix(ENCRYPTED_PREFIX) {
-                    match crypto::decrypt(encrypted) {
-                        Ok(decrypted) => decrypted,
-                        Err(e) => {
-                            log::error!("Failed to decrypt api_key: {}", e);
-                            key.clone()
-                        }
-                    }
-                } else {
-                    key.clone()
+                // Not migrated yet
+                return Ok(Some(value.to_string()));
+            };
+            let secret = secrets
+                .get(backend, &Self::secret_name(account_id, field))
+                .and_then(|secret| secret.map(|secret| vault.open(&secret)).transpose());
+            match secret {
+                Ok(Some(secret)) => Ok(Some(secret)),
+                Ok(None) => {
+                    log::error!("Missing {} secret for account {}", field, account_id);
+                    Self::flag_unreadable(&mut issue, field, "Missing from the secret store");
+                    Ok(None)
+                }
+                Err(AppError::Crypto(e)) => {
+                    log::error!("Can't read {} of account {}: {}", field, account_id, e);
+                    Self::flag_unreadable(&mut issue, field, &e.to_string());
+                    Ok(None)
                 }
-            }),
+                Err(e) => Err(e),
+            }
+        })?;
+        Ok((loaded, issue))
+    }
+
+    /// Read the stored (not yet decrypted) accounts
+    fn load_accounts(app: &AppHandle) -> Result<HashMap<String, Account>, AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+
+        Ok(store
+            .get(ACCOUNTS_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default())
+    }
+
+    /// Replace the credentials of a stored account with its secrets
+    fn with_secrets(
+        app: &AppHandle,
+        vault: &VaultKey,
+        mut account: Account,
+    ) -> Result<Account, AppError> {
+        let secrets = Self::secrets(app)?;
+        let (credentials, issue) =
+            Self::load_secrets(&secrets, vault, &account.id, &account.credentials)?;
+        account.credentials = credentials;
+        if let Some(issue) = issue {
+            for field in &issue.fields {
+                Self::flag_unreadable(&mut account.needs_reentry, field, &issue.reason);
+            }
+        }
+        Ok(account)
+    }
+
+    /// Load an account's secrets and store them again with `to`
+    ///
+    /// Unreadable secrets are deleted and the account is marked as needing
+    /// re-entry
+    fn restore_account(
+        secrets: &SecretStore,
+        from: &VaultKey,
+        to: &VaultKey,
+        account: &mut Account,
+    ) -> Result<(), AppError> {
+        let (loaded, issue) = Self::load_secrets(secrets, from, &account.id, &account.credentials)?;
+        account.credentials = Self::store_secrets(
+            secrets,
+            to,
+            &account.id,
+            &loaded,
+            Some(&account.credentials),
+        )?;
+        if let Some(issue) = issue {
+            for field in &issue.fields {
+                Self::flag_unreadable(&mut account.needs_reentry, field, &issue.reason);
+            }
+        }
+        Ok(())
+    }
+
+    /// Store every secret again, unsealed with `from` and sealed with `to`
+    pub(crate) fn reseal(app: &AppHandle, from: &VaultKey, to: &VaultKey) -> Result<(), AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+
+        let secrets = Self::secrets(app)?;
+        let mut accounts = Self::load_accounts(app)?;
+
+        for account in accounts.values_mut() {
+            Self::restore_account(&secrets, from, to, account)?;
         }
+
+        store.set(ACCOUNTS_KEY.to_string(), serde_json::to_value(&accounts)?);
+        store.set(
+            SEALED_KEY.to_string(),
+            serde_json::to_value(matches!(to, VaultKey::Unlocked(_)))?,
+        );
+        store.save().map_err(|e| AppError::Store(e.to_string()))?;
+
+        log::info!("Resealed secrets of {} accounts", accounts.len());
+        Ok(())
     }
 
     // =========================================================================
@@ -116,33 +295,70 @@ impl CredentialService {
             Self::migrate_v1_to_v2(app)?;
         }
 
-        if version < 3 {
-            log::info!("Migrating credentials from v2 to v3 (encrypting)");
-            Self::migrate_v2_to_v3(app)?;
+        // v2 (plaintext) and v3 (enc:v1: values) both move straight to the secret store
+        if version < 4 {
+            log::info!(
+                "Migrating credentials from v{} to v4 (secret store)",
+                version.max(2)
+            );
+            Self::migrate_to_v4(app)?;
+        }
+
+        // Once the vault is unlocked, seal whatever was stored without it
+        // (including values carried over from legacy `enc:v1:` storage)
+        if let vault @ VaultKey::Unlocked(_) = VaultService::current(app)? {
+            let sealed = store
+                .get(SEALED_KEY)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            if !sealed {
+                log::info!("Sealing credentials with the vault passphrase");
+                Self::reseal(app, &vault, &vault)?;
+            }
         }
 
         Ok(())
     }
 
-    /// Migrate from v2 (plaintext) to v3 (encrypted credentials)
-    fn migrate_v2_to_v3(app: &AppHandle) -> Result<(), AppError> {
+    /// Migrate from v2 (plaintext) or v3 (encrypted with the legacy key) to v4
+    /// (secrets in the secret store)
+    fn migrate_to_v4(app: &AppHandle) -> Result<(), AppError> {
         let store = app
             .store(STORE_FILE)
             .map_err(|e| AppError::Store(e.to_string()))?;
 
-        let mut accounts: HashMap<String, Account> = store
-            .get(ACCOUNTS_KEY)
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        // Encrypt all existing credentials
-        for (_, account) in accounts.iter_mut() {
-            account.credentials = Self::encrypt_credentials(&account.credentials);
+        let secrets = Self::secrets(app)?;
+        let vault = VaultService::current(app)?;
+        let mut accounts = Self::load_accounts(app)?;
+
+        // Move every secret into the secret store
+        for (id, account) in accounts.iter_mut() {
+            // Legacy values the key no longer opens (e.g. after the user name
+            // changed) are dropped rather than stored as if they were secrets
+            let mut issue = account.needs_reentry.take();
+            let readable = Self::map_secrets(&account.credentials, |field, value| {
+                match value.strip_prefix(LEGACY_ENCRYPTED_PREFIX) {
+                    Some(encrypted) => match crypto::decrypt(&crypto::legacy_key(), encrypted) {
+                        Ok(_) => Ok(Some(value.to_string())),
+                        Err(e) => {
+                            log::error!("Can't decrypt {} of account {}: {}", field, id, e);
+                            Self::flag_unreadable(&mut issue, field, &e.to_string());
+                            Ok(None)
+                        }
+                    },
+                    None => Ok(Some(value.to_string())),
+                }
+            })?;
+            account.needs_reentry = issue;
+            account.credentials = Self::store_secrets(&secrets, &vault, id, &readable, None)?;
         }
 
-        // Save encrypted accounts
+        // Save references instead of secrets
         store.set(ACCOUNTS_KEY.to_string(), serde_json::to_value(&accounts)?);
-        store.set(VERSION_KEY.to_string(), serde_json::to_value(CURRENT_VERSION)?);
+        store.set(
+            VERSION_KEY.to_string(),
+            serde_json::to_value(CURRENT_VERSION)?,
+        );
 
         // Clean up any leftover legacy keys (may exist from incomplete v1->v2 migration)
         store.delete("claude");
@@ -151,7 +367,11 @@ impl CredentialService {
 
         store.save().map_err(|e| AppError::Store(e.to_string()))?;
 
-        log::info!("Migration to v3 complete. {} accounts encrypted.", accounts.len());
+        log::info!(
+            "Migration to v4 complete. Secrets of {} accounts moved to the {:?} backend.",
+            accounts.len(),
+            secrets.backend()
+        );
         Ok(())
     }
 
@@ -174,6 +394,7 @@ impl CredentialService {
                         provider: "claude".to_string(),
                         credentials: creds,
                         created_at: Utc::now(),
+                        needs_reentry: None,
                     };
                     log::info!("Migrating Claude credentials to account: {}", account.id);
                     accounts.insert(account.id.clone(), account);
@@ -183,7 +404,7 @@ impl CredentialService {
 
         // Save new format
         store.set(ACCOUNTS_KEY.to_string(), serde_json::to_value(&accounts)?);
-        store.set(VERSION_KEY.to_string(), serde_json::to_value(CURRENT_VERSION)?);
+        store.set(VERSION_KEY.to_string(), serde_json::to_value(2)?);
 
         // Clean up old format keys
         store.delete("claude");
@@ -196,51 +417,66 @@ impl CredentialService {
         Ok(())
     }
 
-    /// List all accounts for a provider (decrypts credentials)
+    /// List all accounts for a provider (with their secrets)
     pub fn list_accounts(app: &AppHandle, provider: &str) -> Result<Vec<Account>, AppError> {
         Self::ensure_migrated(app)?;
 
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
+        let vault = VaultService::current(app)?;
+        Self::load_accounts(app)?
+            .into_values()
+            .filter(|a| a.provider == provider)
+            .map(|a| Self::with_secrets(app, &vault, a))
+            .collect()
+    }
 
-        let accounts: HashMap<String, Account> = store
-            .get(ACCOUNTS_KEY)
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
+    /// List the accounts of every provider (with their secrets)
+    pub fn list_all_accounts(app: &AppHandle) -> Result<Vec<Account>, AppError> {
+        Self::ensure_migrated(app)?;
+
+        let vault = VaultService::current(app)?;
+        Self::load_accounts(app)?
+            .into_values()
+            .map(|a| Self::with_secrets(app, &vault, a))
+            .collect()
+    }
 
-        let filtered: Vec<Account> = accounts
+    /// List all accounts for a provider without their credentials
+    ///
+    /// Doesn't touch the secret store, so it works while the vault is locked.
+    pub fn list_account_metadata(
+        app: &AppHandle,
+        provider: &str,
+    ) -> Result<Vec<Account>, AppError> {
+        Self::ensure_migrated(app)?;
+
+        Ok(Self::load_accounts(app)?
             .into_values()
             .filter(|a| a.provider == provider)
-            .map(|mut a| {
-                a.credentials = Self::decrypt_credentials(&a.credentials);
-                a
+            .map(|a| Account {
+                credentials: Credentials::default(),
+                ..a
             })
-            .collect();
+            .collect())
+    }
 
-        Ok(filtered)
+    /// IDs of all stored accounts
+    pub fn account_ids(app: &AppHandle) -> Result<HashSet<String>, AppError> {
+        Self::ensure_migrated(app)?;
+        Ok(Self::load_accounts(app)?.into_keys().collect())
     }
 
-    /// Get a specific account by ID (decrypts credentials)
+    /// Get a specific account by ID (with its secrets)
     pub fn get_account(app: &AppHandle, account_id: &str) -> Result<Option<Account>, AppError> {
         Self::ensure_migrated(app)?;
 
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
-
-        let accounts: HashMap<String, Account> = store
-            .get(ACCOUNTS_KEY)
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        Ok(accounts.get(account_id).cloned().map(|mut a| {
-            a.credentials = Self::decrypt_credentials(&a.credentials);
-            a
-        }))
+        let vault = VaultService::current(app)?;
+        Self::load_accounts(app)?
+            .remove(account_id)
+            .map(|a| Self::with_secrets(app, &vault, a))
+            .transpose()
     }
 
-    /// Save (create or update) an account (encrypts credentials)
+    /// Save (create or update) an account (secrets go to the secret store)
     pub fn save_account(app: &AppHandle, account: &Account) -> Result<(), AppError> {
         Self::ensure_migrated(app)?;
 
@@ -248,15 +484,37 @@ impl CredentialService {
             .store(STORE_FILE)
             .map_err(|e| AppError::Store(e.to_string()))?;
 
-        let mut accounts: HashMap<String, Account> = store
-            .get(ACCOUNTS_KEY)
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        // Encrypt credentials before storing
-        let mut encrypted_account = account.clone();
-        encrypted_account.credentials = Self::encrypt_credentials(&account.credentials);
-        accounts.insert(account.id.clone(), encrypted_account);
+        let secrets = Self::secrets(app)?;
+        let vault = VaultService::current(app)?;
+        let mut accounts = Self::load_accounts(app)?;
+
+        // Store secrets before referencing them
+        let previous = accounts.get(&account.id).map(|a| &a.credentials);
+        let mut stored_account = account.clone();
+        stored_account.credentials = Self::store_secrets(
+            &secrets,
+            &vault,
+            &account.id,
+            &account.credentials,
+            previous,
+        )?;
+
+        // Whatever needed re-entry and still isn't set keeps needing it
+        let provided = Self::secret_fields(&account.credentials);
+        let mut needs_reentry = None;
+        let issues = [
+            account.needs_reentry.as_ref(),
+            accounts
+                .get(&account.id)
+                .and_then(|a| a.needs_reentry.as_ref()),
+        ];
+        for issue in issues.into_iter().flatten() {
+            for field in issue.fields.iter().filter(|f| !provided.contains(f)) {
+                Self::flag_unreadable(&mut needs_reentry, field, &issue.reason);
+            }
+        }
+        stored_account.needs_reentry = needs_reentry;
+        accounts.insert(account.id.clone(), stored_account);
 
         store.set(ACCOUNTS_KEY.to_string(), serde_json::to_value(&accounts)?);
         store.save().map_err(|e| AppError::Store(e.to_string()))?;
@@ -265,7 +523,46 @@ impl CredentialService {
         Ok(())
     }
 
-    /// Delete an account by ID
+    /// Delete secrets that can't be read back and mark their accounts as
+    /// needing re-entry, returning those accounts
+    pub fn repair(app: &AppHandle) -> Result<Vec<Account>, AppError> {
+        Self::ensure_migrated(app)?;
+
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+
+        let secrets = Self::secrets(app)?;
+        let vault = VaultService::current(app)?;
+        let mut accounts = Self::load_accounts(app)?;
+
+        let mut repaired = Vec::new();
+        for account in accounts.values_mut() {
+            let (_, issue) =
+                Self::load_secrets(&secrets, &vault, &account.id, &account.credentials)?;
+            if issue.is_some() {
+                Self::restore_account(&secrets, &vault, &vault, account)?;
+                repaired.push(account.id.clone());
+            }
+        }
+
+        if !repaired.is_empty() {
+            store.set(ACCOUNTS_KEY.to_string(), serde_json::to_value(&accounts)?);
+            store.save().map_err(|e| AppError::Store(e.to_string()))?;
+            log::warn!(
+                "{} accounts have unreadable credentials and need re-entry",
+                repaired.len()
+            );
+        }
+
+        repaired
+            .iter()
+            .filter_map(|id| accounts.remove(id))
+            .map(|a| Self::with_secrets(app, &vault, a))
+            .collect()
+    }
+
+    /// Delete an account by ID (and its secrets)
     pub fn delete_account(app: &AppHandle, account_id: &str) -> Result<(), AppError> {
         Self::ensure_migrated(app)?;
 
@@ -273,14 +570,23 @@ impl CredentialService {
             .store(STORE_FILE)
             .map_err(|e| AppError::Store(e.to_string()))?;
 
-        let mut accounts: HashMap<String, Account> = store
-            .get(ACCOUNTS_KEY)
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
+        let mut accounts = Self::load_accounts(app)?;
 
-        if accounts.remove(account_id).is_some() {
+        if let Some(account) = accounts.remove(account_id) {
             store.set(ACCOUNTS_KEY.to_string(), serde_json::to_value(&accounts)?);
             store.save().map_err(|e| AppError::Store(e.to_string()))?;
+
+            let secrets = Self::secrets(app)?;
+            for (field, backend) in Self::stored_secrets(&account.credentials) {
+                if let Err(e) = secrets.delete(backend, &Self::secret_name(account_id, &field)) {
+                    log::warn!(
+                        "Failed to delete {} of account {}: {}",
+                        field,
+                        account_id,
+                        e
+                    );
+                }
+            }
             log::info!("Deleted account: {}", account_id);
         }
 
@@ -360,6 +666,7 @@ impl CredentialService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::vault::UnlockedVault;
 
     #[test]
     fn validate_claude_with_valid_credentials() {
@@ -367,6 +674,7 @@ mod tests {
             org_id: Some("org-123".to_string()),
             session_key: Some("sk-ant-xxx".to_string()),
             api_key: None,
+            ..Default::default()
         };
         assert!(CredentialService::validate_claude(&creds));
     }
@@ -377,6 +685,7 @@ mod tests {
             org_id: None,
             session_key: Some("sk-ant-xxx".to_string()),
             api_key: None,
+            ..Default::default()
         };
         assert!(!CredentialService::validate_claude(&creds));
     }
@@ -387,6 +696,7 @@ mod tests {
             org_id: Some("org-123".to_string()),
             session_key: None,
             api_key: None,
+            ..Default::default()
         };
         assert!(!CredentialService::validate_claude(&creds));
     }
@@ -397,6 +707,7 @@ mod tests {
             org_id: Some("".to_string()),
             session_key: Some("sk-ant-xxx".to_string()),
             api_key: None,
+            ..Default::default()
         };
         assert!(!CredentialService::validate_claude(&creds));
     }
@@ -407,6 +718,7 @@ mod tests {
             org_id: Some("   ".to_string()),
             session_key: Some("sk-ant-xxx".to_string()),
             api_key: None,
+            ..Default::default()
         };
         assert!(!CredentialService::validate_claude(&creds));
     }
@@ -416,4 +728,216 @@ mod tests {
         let creds = Credentials::default();
         assert!(!CredentialService::validate_claude(&creds));
     }
+
+    fn secret_store() -> (SecretStore, std::path::PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("ai-pulse-credentials-{}", uuid::Uuid::new_v4()));
+        (SecretStore::file_only(&dir), dir)
+    }
+
+    #[test]
+    fn store_secrets_migrates_plaintext_and_legacy_values() {
+        let (secrets, dir) = secret_store();
+        let legacy = crypto::encrypt(&crypto::legacy_key(), "sk-ant-legacy").unwrap();
+        let credentials = Credentials {
+            org_id: Some("org-123".to_string()),
+            session_key: Some(format!("{}{}", LEGACY_ENCRYPTED_PREFIX, legacy)),
+            api_key: Some("sk-plain".to_string()),
+            extra: HashMap::from([
+                ("team".to_string(), serde_json::json!("core")),
+                ("seats".to_string(), serde_json::json!(5)),
+            ]),
+        };
+
+        let stored = CredentialService::store_secrets(
+            &secrets,
+            &VaultKey::Disabled,
+            "acc-1",
+            &credentials,
+            None,
+        )
+        .unwrap();
+
+        let reference = Some(SecretBackend::File.reference());
+        assert_eq!(stored.org_id.as_deref(), Some("org-123"));
+        assert_eq!(stored.session_key, reference);
+        assert_eq!(stored.api_key, reference);
+        assert_eq!(stored.extra["team"], serde_json::json!(reference));
+        assert_eq!(stored.extra["seats"], serde_json::json!(5));
+
+        let (loaded, issue) =
+            CredentialService::load_secrets(&secrets, &VaultKey::Disabled, "acc-1", &stored)
+                .unwrap();
+        assert_eq!(issue, None);
+        assert_eq!(loaded.session_key.as_deref(), Some("sk-ant-legacy"));
+        assert_eq!(loaded.api_key.as_deref(), Some("sk-plain"));
+        assert_eq!(loaded.extra["team"], serde_json::json!("core"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn store_secrets_deletes_cleared_fields() {
+        let (secrets, dir) = secret_store();
+        let credentials = Credentials {
+            session_key: Some("sk-ant-1".to_string()),
+            api_key: Some("sk-plain".to_string()),
+            ..Default::default()
+        };
+        let previous = CredentialService::store_secrets(
+            &secrets,
+            &VaultKey::Disabled,
+            "acc-1",
+            &credentials,
+            None,
+        )
+        .unwrap();
+
+        let updated = Credentials {
+            session_key: Some("sk-ant-2".to_string()),
+            ..Default::default()
+        };
+        let stored = CredentialService::store_secrets(
+            &secrets,
+            &VaultKey::Disabled,
+            "acc-1",
+            &updated,
+            Some(&previous),
+        )
+        .unwrap();
+
+        assert_eq!(
+            secrets
+                .get(SecretBackend::File, "acc-1/session_key")
+                .unwrap()
+                .as_deref(),
+            Some("sk-ant-2")
+        );
+        assert_eq!(
+            secrets.get(SecretBackend::File, "acc-1/api_key").unwrap(),
+            None
+        );
+        assert_eq!(
+            CredentialService::stored_secrets(&stored),
+            vec![("session_key".to_string(), SecretBackend::File)]
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn store_secrets_seals_with_the_vault() {
+        let (secrets, dir) = secret_store();
+        let vault = VaultKey::Unlocked(UnlockedVault::for_test("correct horse"));
+        let credentials = Credentials {
+            session_key: Some("sk-ant-secret".to_string()),
+            ..Default::default()
+        };
+
+        let stored =
+            CredentialService::store_secrets(&secrets, &vault, "acc-1", &credentials, None)
+                .unwrap();
+
+        let at_rest = secrets
+            .get(SecretBackend::File, "acc-1/session_key")
+            .unwrap()
+            .unwrap();
+        assert!(at_rest.starts_with(crypto::PASSPHRASE_PREFIX));
+
+        let (loaded, _) =
+            CredentialService::load_secrets(&secrets, &vault, "acc-1", &stored).unwrap();
+        assert_eq!(loaded.session_key.as_deref(), Some("sk-ant-secret"));
+
+        // Fails closed while the passphrase hasn't been entered
+        assert!(matches!(
+            CredentialService::load_secrets(&secrets, &VaultKey::Locked, "acc-1", &stored),
+            Err(AppError::VaultLocked)
+        ));
+
+        // Once the passphrase is gone the secret has to be entered again
+        let (loaded, issue) =
+            CredentialService::load_secrets(&secrets, &VaultKey::Disabled, "acc-1", &stored)
+                .unwrap();
+        assert!(loaded.session_key.is_none());
+        assert!(issue.is_some());
+
+        assert!(matches!(
+            CredentialService::store_secrets(
+                &secrets,
+                &VaultKey::Locked,
+                "acc-2",
+                &credentials,
+                None
+            ),
+            Err(AppError::VaultLocked)
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_secrets_fails_closed_on_unreadable_secrets() {
+        let (secrets, dir) = secret_store();
+        let vault = VaultKey::Unlocked(UnlockedVault::for_test("correct horse"));
+        let legacy = format!(
+            "{}{}",
+            LEGACY_ENCRYPTED_PREFIX,
+            crypto::encrypt(&crypto::legacy_key(), "sk-legacy").unwrap()
+        );
+        let credentials = Credentials {
+            session_key: Some("sk-ant-secret".to_string()),
+            api_key: Some("sk-plain".to_string()),
+            ..Default::default()
+        };
+        let mut stored =
+            CredentialService::store_secrets(&secrets, &vault, "acc-1", &credentials, None)
+                .unwrap();
+        stored
+            .extra
+            .insert("token".to_string(), serde_json::json!(legacy));
+        secrets
+            .delete(SecretBackend::File, "acc-1/api_key")
+            .unwrap();
+
+        // Sealed with another passphrase, missing, and never migrated
+        let other = VaultKey::Unlocked(UnlockedVault::for_test("correct horse"));
+        let (loaded, issue) =
+            CredentialService::load_secrets(&secrets, &other, "acc-1", &stored).unwrap();
+
+        assert_eq!(loaded.session_key, None);
+        assert_eq!(loaded.api_key, None);
+        assert!(!loaded.extra.contains_key("token"));
+        let issue = issue.unwrap();
+        assert_eq!(issue.fields.len(), 3);
+        assert!(issue.reason.starts_with("Decryption failed"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn store_secrets_rejects_undecryptable_legacy_values() {
+        // Nothing is written, so there is no directory to clean up
+        let (secrets, _) = secret_store();
+        let wrong = crypto::encrypt(&crypto::generate_key(), "sk-ant-legacy").unwrap();
+        let credentials = Credentials {
+            session_key: Some(format!("{}{}", LEGACY_ENCRYPTED_PREFIX, wrong)),
+            ..Default::default()
+        };
+
+        let result = CredentialService::store_secrets(
+            &secrets,
+            &VaultKey::Disabled,
+            "acc-1",
+            &credentials,
+            None,
+        );
+
+        assert!(matches!(result, Err(AppError::Crypto(_))));
+        assert_eq!(
+            secrets
+                .get(SecretBackend::File, "acc-1/session_key")
+                .unwrap(),
+            None
+        );
+    }
 }
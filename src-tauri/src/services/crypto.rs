@@ -1,17 +1,187 @@
+use crate::error::CryptoError;
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Nonce,
 };
+use argon2::{Algorithm, Argon2, Params, Version};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::env;
 
+/// 256-bit AES key
+pub type Key = [u8; 32];
+
+/// Prefix of values encrypted with a passphrase-derived key
+pub const PASSPHRASE_PREFIX: &str = "enc:v2:";
+
+/// Length of passphrase salts in bytes
+const SALT_LEN: usize = 16;
+
+/// Most KDF memory stored parameters may ask for (1 GiB)
+const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+/// Most KDF passes stored parameters may ask for
+const MAX_KDF_ITERATIONS: u32 = 16;
+/// Most KDF lanes stored parameters may ask for
+const MAX_KDF_PARALLELISM: u32 = 16;
+
+/// Argon2id cost parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdfParams {
+    /// Memory in KiB
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// OWASP's recommended minimum for Argon2id
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Whether deriving a key with these parameters stays affordable
+    ///
+    /// Parameters read from a file are checked before use, so a crafted file
+    /// can't tie up memory or CPU before the passphrase is even checked.
+    pub fn within_limits(&self) -> bool {
+        self.memory_kib <= MAX_KDF_MEMORY_KIB
+            && self.iterations <= MAX_KDF_ITERATIONS
+            && self.parallelism <= MAX_KDF_PARALLELISM
+    }
+}
+
+/// Generate a random salt for `derive_passphrase_key`
+pub fn generate_salt() -> Vec<u8> {
+    let mut salt = vec![0u8; SALT_LEN];
+    rand::thread_rng().fill(&mut salt[..]);
+    salt
+}
+
+/// Derives a key from a passphrase with Argon2id
+pub fn derive_passphrase_key(
+    passphrase: &str,
+    salt: &[u8],
+    params: &KdfParams,
+) -> Result<Key, CryptoError> {
+    let argon_params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| CryptoError::KeyDerivation(format!("invalid parameters: {}", e)))?;
+
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| CryptoError::KeyDerivation(e.to_string()))?;
+    Ok(key)
+}
+
+/// A value encrypted with a passphrase-derived key, with what it takes to
+/// derive the key again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sealed {
+    pub params: KdfParams,
+    pub salt: Vec<u8>,
+    /// Output of `encrypt`
+    pub ciphertext: String,
+}
+
+impl Sealed {
+    /// Encrypt `plaintext` with a key derived from `params` and `salt`
+    pub fn seal(
+        key: &Key,
+        params: KdfParams,
+        salt: &[u8],
+        plaintext: &str,
+    ) -> Result<Self, CryptoError> {
+        Ok(Self {
+            params,
+            salt: salt.to_vec(),
+            ciphertext: encrypt(key, plaintext)?,
+        })
+    }
+
+    pub fn open(&self, key: &Key) -> Result<String, CryptoError> {
+        decrypt(key, &self.ciphertext)
+    }
+
+    /// Parse an `enc:v2:` value (`None` for anything else)
+    ///
+    /// Format: `enc:v2:argon2id$m=<KiB>,t=<iterations>,p=<lanes>$<salt>$<ciphertext>`
+    pub fn parse(value: &str) -> Option<Self> {
+        let rest = value.strip_prefix(PASSPHRASE_PREFIX)?;
+        let mut parts = rest.split('$');
+        if parts.next()? != "argon2id" {
+            return None;
+        }
+
+        let mut params = KdfParams::default();
+        for param in parts.next()?.split(',') {
+            let (name, value) = param.split_once('=')?;
+            let value: u32 = value.parse().ok()?;
+            match name {
+                "m" => params.memory_kib = value,
+                "t" => params.iterations = value,
+                "p" => params.parallelism = value,
+                _ => return None,
+            }
+        }
+
+        let salt = BASE64.decode(parts.next()?).ok()?;
+        let ciphertext = parts.next()?.to_string();
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            params,
+            salt,
+            ciphertext,
+        })
+    }
+}
+
+impl std::fmt::Display for Sealed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}argon2id$m={},t={},p={}${}${}",
+            PASSPHRASE_PREFIX,
+            self.params.memory_kib,
+            self.params.iterations,
+            self.params.parallelism,
+            BASE64.encode(&self.salt),
+            self.ciphertext
+        )
+    }
+}
+
 /// Fixed app-specific salt for key derivation
 const APP_SALT: &[u8] = b"ai-pulse-credential-encryption-v1";
 
-/// Derives a 256-bit encryption key from machine-specific info
-/// This provides encryption at rest without requiring user interaction
-fn derive_key() -> [u8; 32] {
+/// Generate a random key
+pub fn generate_key() -> Key {
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill(&mut key);
+    key
+}
+
+/// Derives the key of legacy `enc:v1:` values from machine-specific info
+///
+/// Anyone who knows the user name and home directory can recompute it, so it
+/// is only used to read values written before credentials moved to the
+/// secret store.
+pub fn legacy_key() -> Key {
     // Combine multiple sources for key material:
     // 1. App-specific salt
     // 2. Username (machine-specific)
@@ -59,10 +229,8 @@ fn derive_key() -> [u8; 32] {
 
 /// Encrypts a string value using AES-256-GCM
 /// Returns a base64-encoded string containing the nonce and ciphertext
-pub fn encrypt(plaintext: &str) -> Result<String, String> {
-    let key = derive_key();
-    let cipher = Aes256Gcm::new_from_slice(&key)
-        .map_err(|e| format!("Failed to create cipher: {}", e))?;
+pub fn encrypt(key: &Key, plaintext: &str) -> Result<String, CryptoError> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| CryptoError::Encrypt(e.to_string()))?;
 
     // Generate a random 12-byte nonce
     let mut nonce_bytes = [0u8; 12];
@@ -72,7 +240,7 @@ pub fn encrypt(plaintext: &str) -> Result<String, String> {
     // Encrypt the plaintext
     let ciphertext = cipher
         .encrypt(nonce, plaintext.as_bytes())
-        .map_err(|e| format!("Encryption failed: {}", e))?;
+        .map_err(|e| CryptoError::Encrypt(e.to_string()))?;
 
     // Combine nonce + ciphertext and encode as base64
     let mut combined = nonce_bytes.to_vec();
@@ -82,40 +250,41 @@ pub fn encrypt(plaintext: &str) -> Result<String, String> {
 }
 
 /// Decrypts a base64-encoded encrypted string
-pub fn decrypt(encrypted: &str) -> Result<String, String> {
-    let key = derive_key();
-    let cipher = Aes256Gcm::new_from_slice(&key)
-        .map_err(|e| format!("Failed to create cipher: {}", e))?;
+pub fn decrypt(key: &Key, encrypted: &str) -> Result<String, CryptoError> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| CryptoError::Decrypt(e.to_string()))?;
 
     // Decode from base64
     let combined = BASE64
         .decode(encrypted)
-        .map_err(|e| format!("Invalid base64: {}", e))?;
+        .map_err(|e| CryptoError::Malformed(format!("invalid base64: {}", e)))?;
 
     // Split into nonce (first 12 bytes) and ciphertext (rest)
     if combined.len() < 12 {
-        return Err("Encrypted data too short".to_string());
+        return Err(CryptoError::Malformed("too short".to_string()));
     }
 
     let (nonce_bytes, ciphertext) = combined.split_at(12);
     let nonce = Nonce::from_slice(nonce_bytes);
 
-    // Decrypt
+    // Decrypt (fails with the wrong key or tampered data)
     let plaintext = cipher
         .decrypt(nonce, ciphertext)
-        .map_err(|e| format!("Decryption failed: {}", e))?;
+        .map_err(|_| CryptoError::Decrypt("wrong key or corrupted data".to_string()))?;
 
-    String::from_utf8(plaintext).map_err(|e| format!("Invalid UTF-8: {}", e))
+    String::from_utf8(plaintext)
+        .map_err(|e| CryptoError::Malformed(format!("invalid UTF-8: {}", e)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const KEY: Key = [7u8; 32];
+
     #[test]
     fn test_encrypt_decrypt_roundtrip() {
         let original = "sk-ant-REDACTED";
-        let encrypted = encrypt(original).unwrap();
+        let encrypted = encrypt(&KEY, original).unwrap();
 
         // Encrypted should be different from original
         assert_ne!(encrypted, original);
@@ -124,40 +293,40 @@ mod tests {
         assert!(BASE64.decode(&encrypted).is_ok());
 
         // Should decrypt back to original
-        let decrypted = decrypt(&encrypted).unwrap();
+        let decrypted = decrypt(&KEY, &encrypted).unwrap();
         assert_eq!(decrypted, original);
     }
 
     #[test]
     fn test_encrypt_produces_different_output() {
         let original = "test-value";
-        let encrypted1 = encrypt(original).unwrap();
-        let encrypted2 = encrypt(original).unwrap();
+        let encrypted1 = encrypt(&KEY, original).unwrap();
+        let encrypted2 = encrypt(&KEY, original).unwrap();
 
         // Due to random nonce, each encryption should produce different output
         assert_ne!(encrypted1, encrypted2);
 
         // But both should decrypt to the same value
-        assert_eq!(decrypt(&encrypted1).unwrap(), original);
-        assert_eq!(decrypt(&encrypted2).unwrap(), original);
+        assert_eq!(decrypt(&KEY, &encrypted1).unwrap(), original);
+        assert_eq!(decrypt(&KEY, &encrypted2).unwrap(), original);
     }
 
     #[test]
     fn test_decrypt_invalid_base64() {
-        let result = decrypt("not-valid-base64!!!");
+        let result = decrypt(&KEY, "not-valid-base64!!!");
         assert!(result.is_err());
     }
 
     #[test]
     fn test_decrypt_too_short() {
-        let result = decrypt(&BASE64.encode([0u8; 5]));
+        let result = decrypt(&KEY, &BASE64.encode([0u8; 5]));
         assert!(result.is_err());
     }
 
     #[test]
     fn test_decrypt_tampered_data() {
         let original = "secret-value";
-        let encrypted = encrypt(original).unwrap();
+        let encrypted = encrypt(&KEY, original).unwrap();
 
         // Tamper with the encrypted data
         let mut bytes = BASE64.decode(&encrypted).unwrap();
@@ -167,7 +336,93 @@ mod tests {
         let tampered = BASE64.encode(&bytes);
 
         // Decryption should fail due to authentication tag mismatch
-        let result = decrypt(&tampered);
+        let result = decrypt(&KEY, &tampered);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_decrypt_with_wrong_key() {
+        let encrypted = encrypt(&KEY, "secret-value").unwrap();
+        assert!(matches!(
+            decrypt(&generate_key(), &encrypted),
+            Err(CryptoError::Decrypt(_))
+        ));
+    }
+
+    /// Cheap parameters so tests stay fast
+    const TEST_PARAMS: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn test_passphrase_key_depends_on_passphrase_and_salt() {
+        let salt = generate_salt();
+        let key = derive_passphrase_key("correct horse", &salt, &TEST_PARAMS).unwrap();
+
+        assert_eq!(
+            derive_passphrase_key("correct horse", &salt, &TEST_PARAMS).unwrap(),
+            key
+        );
+        assert_ne!(
+            derive_passphrase_key("wrong horse", &salt, &TEST_PARAMS).unwrap(),
+            key
+        );
+        assert_ne!(
+            derive_passphrase_key("correct horse", &generate_salt(), &TEST_PARAMS).unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn test_sealed_round_trip() {
+        let salt = generate_salt();
+        let key = derive_passphrase_key("correct horse", &salt, &TEST_PARAMS).unwrap();
+
+        let sealed = Sealed::seal(&key, TEST_PARAMS, &salt, "sk-ant-secret").unwrap();
+        let value = sealed.to_string();
+        assert!(value.starts_with("enc:v2:argon2id$m=64,t=1,p=1$"));
+
+        let parsed = Sealed::parse(&value).unwrap();
+        assert_eq!(parsed, sealed);
+        assert_eq!(parsed.open(&key).unwrap(), "sk-ant-secret");
+    }
+
+    #[test]
+    fn test_sealed_parse_rejects_other_values() {
+        assert!(Sealed::parse("sk-ant-secret").is_none());
+        assert!(Sealed::parse("enc:v1:abc").is_none());
+        assert!(Sealed::parse("enc:v2:scrypt$m=64,t=1,p=1$AAAA$abc").is_none());
+        assert!(Sealed::parse("enc:v2:argon2id$m=64,x=1$AAAA$abc").is_none());
+    }
+
+    #[test]
+    fn test_kdf_params_within_limits() {
+        assert!(KdfParams::default().within_limits());
+
+        let limits = [
+            KdfParams {
+                memory_kib: 4 * 1024 * 1024,
+                ..KdfParams::default()
+            },
+            KdfParams {
+                iterations: 1_000_000,
+                ..KdfParams::default()
+            },
+            KdfParams {
+                parallelism: 1_000,
+                ..KdfParams::default()
+            },
+        ];
+        for params in limits {
+            assert!(!params.within_limits(), "{:?}", params);
+        }
+    }
+
+    #[test]
+    fn test_legacy_key_is_stable() {
+        let encrypted = encrypt(&legacy_key(), "secret-value").unwrap();
+        assert_eq!(decrypt(&legacy_key(), &encrypted).unwrap(), "secret-value");
+    }
 }
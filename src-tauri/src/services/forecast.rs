@@ -0,0 +1,257 @@
+use crate::error::AppError;
+use crate::models::{AccountForecast, LimitForecast, UsageData, UsageLimit};
+use crate::services::HistoryService;
+use chrono::{DateTime, Duration, Utc};
+use tauri::AppHandle;
+
+/// Samples whose reset time is this close to the current one belong to the same window
+const RESET_TOLERANCE_MINUTES: i64 = 15;
+
+/// z-score for the 95% confidence band around the burn rate
+const CONFIDENCE_Z: f64 = 1.96;
+
+pub struct ForecastService;
+
+impl ForecastService {
+    /// Forecast every limit in a usage snapshot
+    pub fn forecast_usage(app: &AppHandle, usage: &UsageData) -> Result<AccountForecast, AppError> {
+        Ok(AccountForecast {
+            account_id: usage.account_id.clone(),
+            account_name: usage.account_name.clone(),
+            provider: usage.provider.clone(),
+            as_of: usage.timestamp,
+            limits: Self::forecast_limits(app, &usage.account_id, &usage.limits, usage.timestamp)?,
+        })
+    }
+
+    /// Forecast limits from the samples recorded since each limit's window began
+    pub fn forecast_limits(
+        app: &AppHandle,
+        account_id: &str,
+        limits: &[UsageLimit],
+        as_of: DateTime<Utc>,
+    ) -> Result<Vec<LimitForecast>, AppError> {
+        limits
+            .iter()
+            .map(|limit| {
+                let mut samples = HistoryService::window_samples(
+                    app,
+                    account_id,
+                    &limit.id,
+                    limit.resets_at,
+                    Duration::minutes(RESET_TOLERANCE_MINUTES),
+                )?;
+
+                // The current snapshot may not have been recorded yet
+                if !samples.iter().any(|(t, _)| *t == as_of) {
+                    samples.push((as_of, limit.utilization));
+                }
+
+                Ok(Self::forecast_limit(limit, &samples, as_of))
+            })
+            .collect()
+    }
+
+    /// Fit a least-squares line through the window's samples and project when it crosses 100%
+    pub fn forecast_limit(
+        limit: &UsageLimit,
+        samples: &[(DateTime<Utc>, f64)],
+        as_of: DateTime<Utc>,
+    ) -> LimitForecast {
+        let mut forecast = LimitForecast {
+            limit_id: limit.id.clone(),
+            utilization: limit.utilization,
+            resets_at: limit.resets_at,
+            sample_count: samples.len(),
+            burn_rate_per_hour: None,
+            burn_rate_low: None,
+            burn_rate_high: None,
+            hits_limit_at: None,
+            hits_limit_earliest: None,
+            hits_limit_latest: None,
+            projected_at_reset: None,
+            hits_before_reset: false,
+        };
+
+        if limit.utilization >= 100.0 {
+            forecast.hits_limit_at = Some(as_of);
+            forecast.hits_limit_earliest = Some(as_of);
+            forecast.hits_limit_latest = Some(as_of);
+            forecast.hits_before_reset = as_of < limit.resets_at;
+            return forecast;
+        }
+
+        let Some((rate, margin)) = fit_burn_rate(samples) else {
+            return forecast;
+        };
+
+        forecast.burn_rate_per_hour = Some(rate);
+        if let Some(margin) = margin {
+            forecast.burn_rate_low = Some(rate - margin);
+            forecast.burn_rate_high = Some(rate + margin);
+        }
+
+        let hours_to_reset = (limit.resets_at - as_of).num_milliseconds() as f64 / 3_600_000.0;
+        forecast.projected_at_reset = Some(limit.utilization + rate * hours_to_reset.max(0.0));
+
+        let remaining = 100.0 - limit.utilization;
+        forecast.hits_limit_at = project(as_of, remaining, rate);
+        forecast.hits_limit_earliest = forecast
+            .burn_rate_high
+            .map_or(forecast.hits_limit_at, |high| {
+                project(as_of, remaining, high)
+            });
+        forecast.hits_limit_latest = forecast
+            .burn_rate_low
+            .map_or(forecast.hits_limit_at, |low| project(as_of, remaining, low));
+        forecast.hits_before_reset = forecast
+            .hits_limit_at
+            .is_some_and(|at| at < limit.resets_at);
+
+        forecast
+    }
+}
+
+/// Slope (percentage points per hour) of a least-squares fit, plus the 95%
+/// confidence margin when there are enough samples to estimate it
+fn fit_burn_rate(samples: &[(DateTime<Utc>, f64)]) -> Option<(f64, Option<f64>)> {
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let origin = samples[0].0;
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|(t, u)| ((*t - origin).num_milliseconds() as f64 / 3_600_000.0, *u))
+        .collect();
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+
+    // All samples at the same instant
+    if sxx <= f64::EPSILON {
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    let margin = if points.len() > 2 {
+        let intercept = mean_y - slope * mean_x;
+        let residuals: f64 = points
+            .iter()
+            .map(|(x, y)| (y - (intercept + slope * x)).powi(2))
+            .sum();
+        let std_error = (residuals / (n - 2.0) / sxx).sqrt();
+        Some(CONFIDENCE_Z * std_error)
+    } else {
+        None
+    };
+
+    Some((slope, margin))
+}
+
+/// When `remaining` percentage points run out at `rate` per hour (None if not increasing)
+fn project(from: DateTime<Utc>, remaining: f64, rate: f64) -> Option<DateTime<Utc>> {
+    if rate <= 0.0 {
+        return None;
+    }
+    let millis = remaining / rate * 3_600_000.0;
+    // Anything further out than the chrono range will never be reached in practice
+    Duration::try_milliseconds(millis as i64).and_then(|d| from.checked_add_signed(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn base() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap()
+    }
+
+    fn limit(utilization: f64) -> UsageLimit {
+        UsageLimit {
+            id: "five_hour".to_string(),
+            label: "5-Hour Limit".to_string(),
+            utilization,
+            resets_at: base() + Duration::hours(3),
+            category: None,
+        }
+    }
+
+    fn samples(points: &[(i64, f64)]) -> Vec<(DateTime<Utc>, f64)> {
+        points
+            .iter()
+            .map(|(minutes, u)| (base() + Duration::minutes(*minutes), *u))
+            .collect()
+    }
+
+    #[test]
+    fn linear_usage_projects_exact_time() {
+        // 10 points per hour, 40% now -> 100% in 6 hours
+        let samples = samples(&[(-120, 20.0), (-60, 30.0), (0, 40.0)]);
+        let forecast = ForecastService::forecast_limit(&limit(40.0), &samples, base());
+
+        let rate = forecast.burn_rate_per_hour.unwrap();
+        assert!((rate - 10.0).abs() < 1e-9);
+        assert_eq!(forecast.hits_limit_at, Some(base() + Duration::hours(6)));
+        // Perfect fit: the band collapses onto the estimate
+        assert_eq!(forecast.hits_limit_earliest, forecast.hits_limit_at);
+        assert_eq!(forecast.hits_limit_latest, forecast.hits_limit_at);
+        // Resets in 3 hours at 70%, before the limit is reached
+        assert!((forecast.projected_at_reset.unwrap() - 70.0).abs() < 1e-9);
+        assert!(!forecast.hits_before_reset);
+    }
+
+    #[test]
+    fn fast_burn_hits_before_reset() {
+        let samples = samples(&[(-30, 50.0), (-20, 60.0), (-10, 68.0), (0, 80.0)]);
+        let forecast = ForecastService::forecast_limit(&limit(80.0), &samples, base());
+
+        assert!(forecast.hits_before_reset);
+        let at = forecast.hits_limit_at.unwrap();
+        let earliest = forecast.hits_limit_earliest.unwrap();
+        let latest = forecast.hits_limit_latest.unwrap();
+        assert!(earliest < at && at < latest);
+        assert!(forecast.burn_rate_low.unwrap() < forecast.burn_rate_high.unwrap());
+    }
+
+    #[test]
+    fn flat_or_falling_usage_never_hits() {
+        let samples = samples(&[(-60, 30.0), (-30, 30.0), (0, 30.0)]);
+        let forecast = ForecastService::forecast_limit(&limit(30.0), &samples, base());
+        assert_eq!(forecast.burn_rate_per_hour, Some(0.0));
+        assert!(forecast.hits_limit_at.is_none());
+        assert!(!forecast.hits_before_reset);
+    }
+
+    #[test]
+    fn single_sample_has_no_burn_rate() {
+        let samples = samples(&[(0, 30.0)]);
+        let forecast = ForecastService::forecast_limit(&limit(30.0), &samples, base());
+        assert_eq!(forecast.sample_count, 1);
+        assert!(forecast.burn_rate_per_hour.is_none());
+        assert!(forecast.hits_limit_at.is_none());
+    }
+
+    #[test]
+    fn two_samples_have_no_confidence_band() {
+        let samples = samples(&[(-60, 20.0), (0, 40.0)]);
+        let forecast = ForecastService::forecast_limit(&limit(40.0), &samples, base());
+        assert!(forecast.burn_rate_low.is_none());
+        assert_eq!(forecast.hits_limit_earliest, forecast.hits_limit_at);
+        assert_eq!(forecast.hits_limit_at, Some(base() + Duration::hours(3)));
+    }
+
+    #[test]
+    fn exhausted_limit_hits_now() {
+        let forecast = ForecastService::forecast_limit(&limit(100.0), &[], base());
+        assert_eq!(forecast.hits_limit_at, Some(base()));
+        assert!(forecast.hits_before_reset);
+    }
+}
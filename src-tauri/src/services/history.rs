@@ -1,26 +1,100 @@
 use crate::error::AppError;
 use crate::models::{
-    HistoryMetadata, HistoryQuery, RetentionPolicy, UsageData, UsageHistoryEntry,
-    UsageLimitSnapshot, UsageStats,
+    HistoryMetadata, HistoryPage, HistoryQuery, HistoryResolution, RetentionPolicy, UsageData,
+    UsageHistoryEntry, UsageLimitSnapshot, UsageStats,
 };
-use chrono::{DateTime, Duration, Utc};
-use tauri::AppHandle;
+use crate::services::HistoryDb;
+use chrono::{DateTime, Duration, Months, Utc};
+use tauri::{AppHandle, Manager};
 use tauri_plugin_store::StoreExt;
 
-const STORE_FILE: &str = "history.json";
-const HISTORY_KEY: &str = "entries";
-const METADATA_KEY: &str = "metadata";
+const DB_FILE: &str = "history.db";
 const RETENTION_KEY: &str = "retention";
+const LAST_CLEANUP_KEY: &str = "last_cleanup";
+
+/// Legacy JSON store (migrated into the database on first launch)
+const LEGACY_STORE_FILE: &str = "history.json";
+const LEGACY_HISTORY_KEY: &str = "entries";
+const LEGACY_METADATA_KEY: &str = "metadata";
+const LEGACY_MIGRATED_KEY: &str = "legacy_json_migrated";
 
 pub struct HistoryService;
 
 impl HistoryService {
-    /// Add a new usage snapshot to history
-    pub fn add_entry(app: &AppHandle, usage_data: &UsageData) -> Result<(), AppError> {
+    /// Open the history database and migrate any legacy history.json data
+    pub fn init(app: &AppHandle) -> Result<(), AppError> {
+        if app.try_state::<HistoryDb>().is_some() {
+            return Ok(());
+        }
+
+        let dir = app.path().app_data_dir()?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::Store(format!("Failed to create data directory: {}", e)))?;
+
+        let db = HistoryDb::open(&dir.join(DB_FILE))?;
+        Self::migrate_legacy_json(app, &db)?;
+
+        app.manage(db);
+        log::info!("History database ready");
+        Ok(())
+    }
+
+    /// One-time import of entries, metadata and retention policy from history.json
+    fn migrate_legacy_json(app: &AppHandle, db: &HistoryDb) -> Result<(), AppError> {
+        if db.get_meta::<bool>(LEGACY_MIGRATED_KEY)?.unwrap_or(false) {
+            return Ok(());
+        }
+
         let store = app
-            .store(STORE_FILE)
+            .store(LEGACY_STORE_FILE)
             .map_err(|e| AppError::Store(e.to_string()))?;
 
+        let entries: Vec<UsageHistoryEntry> = match store.get(LEGACY_HISTORY_KEY) {
+            Some(v) => serde_json::from_value(v.clone())?,
+            None => Vec::new(),
+        };
+
+        if !entries.is_empty() {
+            log::info!("Migrating {} history entries from history.json", entries.len());
+            let inserted = db.insert_entries(&entries)?;
+            log::info!("Migrated {} history entries into SQLite", inserted);
+        }
+
+        if let Some(v) = store.get(RETENTION_KEY) {
+            let policy: RetentionPolicy = serde_json::from_value(v.clone())?;
+            db.set_meta(RETENTION_KEY, &policy)?;
+        }
+
+        if let Some(v) = store.get(LEGACY_METADATA_KEY) {
+            if let Ok(meta) = serde_json::from_value::<HistoryMetadata>(v.clone()) {
+                if let Some(last_cleanup) = meta.last_cleanup {
+                    db.set_meta(LAST_CLEANUP_KEY, &last_cleanup)?;
+                }
+            }
+        }
+
+        db.set_meta(LEGACY_MIGRATED_KEY, &true)?;
+
+        // Drop the migrated data so the JSON file no longer grows or gets rewritten
+        store.delete(LEGACY_HISTORY_KEY);
+        store.delete(LEGACY_METADATA_KEY);
+        store.delete(RETENTION_KEY);
+        store.save().map_err(|e| AppError::Store(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Get the history database (opening it if setup has not run yet)
+    fn db(app: &AppHandle) -> Result<tauri::State<'_, HistoryDb>, AppError> {
+        if app.try_state::<HistoryDb>().is_none() {
+            Self::init(app)?;
+        }
+        app.try_state::<HistoryDb>()
+            .ok_or_else(|| AppError::Store("History database not initialized".to_string()))
+    }
+
+    /// Add a new usage snapshot to history
+    pub fn add_entry(app: &AppHandle, usage_data: &UsageData) -> Result<(), AppError> {
         // Create history entry from usage data
         let entry = UsageHistoryEntry {
             id: format!(
@@ -40,169 +114,80 @@ impl HistoryService {
                     id: l.id.clone(),
                     utilization: l.utilization,
                     resets_at: l.resets_at,
+                    min_utilization: None,
+                    max_utilization: None,
                 })
                 .collect(),
         };
 
-        // Get existing entries
-        let mut entries = Self::get_all_entries(app)?;
-
-        // Avoid duplicate entries (same timestamp and provider)
-        if entries.iter().any(|e| e.id == entry.id) {
-            return Ok(());
+        // Duplicate entries (same timestamp, provider and account) are ignored
+        if Self::db(app)?.insert_entry(&entry)? {
+            log::debug!("Added history entry for provider: {}", usage_data.provider);
         }
 
-        entries.push(entry);
-
-        // Save entries
-        let value = serde_json::to_value(&entries)?;
-        store.set(HISTORY_KEY.to_string(), value);
-        store.save().map_err(|e| AppError::Store(e.to_string()))?;
-
-        // Update metadata
-        Self::update_metadata(app)?;
-
-        log::debug!(
-            "Added history entry for provider: {} (total: {})",
-            usage_data.provider,
-            entries.len()
-        );
-
         Ok(())
     }
 
     /// Get all history entries
     pub fn get_all_entries(app: &AppHandle) -> Result<Vec<UsageHistoryEntry>, AppError> {
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
-
-        match store.get(HISTORY_KEY) {
-            Some(v) => {
-                let entries: Vec<UsageHistoryEntry> = serde_json::from_value(v.clone())?;
-                Ok(entries)
-            }
-            None => Ok(Vec::new()),
-        }
+        Self::db(app)?.all_entries()
     }
 
-    /// Query history with filters
-    pub fn query(app: &AppHandle, query: &HistoryQuery) -> Result<Vec<UsageHistoryEntry>, AppError> {
-        let mut entries = Self::get_all_entries(app)?;
-
-        // Filter by provider
-        if let Some(ref provider) = query.provider {
-            entries.retain(|e| &e.provider == provider);
-        }
-
-        // Filter by account
-        if let Some(ref account_id) = query.account_id {
-            entries.retain(|e| &e.account_id == account_id);
-        }
+    /// Add entries from elsewhere (e.g. an account bundle), skipping ones
+    /// already recorded. Returns the number added.
+    pub fn import_entries(
+        app: &AppHandle,
+        entries: &[UsageHistoryEntry],
+    ) -> Result<usize, AppError> {
+        Self::db(app)?.insert_entries(entries)
+    }
 
-        // Filter by date range
-        if let Some(start) = query.start_date {
-            entries.retain(|e| e.timestamp >= start);
-        }
-        if let Some(end) = query.end_date {
-            entries.retain(|e| e.timestamp <= end);
+    /// Query one page of history with filters, including the total match count
+    pub fn query(app: &AppHandle, query: &HistoryQuery) -> Result<HistoryPage, AppError> {
+        let db = Self::db(app)?;
+        if query.resolution != HistoryResolution::Raw {
+            // Pick up any hours/days completed since the last cleanup
+            Self::build_rollups(&db, Utc::now())?;
         }
+        db.query(query)
+    }
 
-        // Sort by timestamp descending (newest first)
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-        // Apply offset
-        if let Some(offset) = query.offset {
-            if offset < entries.len() {
-                entries = entries.into_iter().skip(offset).collect();
-            } else {
-                entries.clear();
-            }
+    /// Bring hourly, then daily rollups up to date
+    fn build_rollups(db: &HistoryDb, now: DateTime<Utc>) -> Result<(), AppError> {
+        let hourly = db.build_rollups(HistoryResolution::Hourly, now)?;
+        let daily = db.build_rollups(HistoryResolution::Daily, now)?;
+        if hourly > 0 || daily > 0 {
+            log::debug!("Built {} hourly and {} daily rollups", hourly, daily);
         }
-
-        // Apply limit
-        let limit = query.limit.unwrap_or(1000);
-        entries.truncate(limit);
-
-        Ok(entries)
+        Ok(())
     }
 
     /// Get history metadata
     pub fn get_metadata(app: &AppHandle) -> Result<HistoryMetadata, AppError> {
-        let entries = Self::get_all_entries(app)?;
+        let db = Self::db(app)?;
         let policy = Self::get_retention_policy(app)?;
-
-        let oldest = entries.iter().map(|e| e.timestamp).min();
-        let newest = entries.iter().map(|e| e.timestamp).max();
-
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
-
-        let last_cleanup = match store.get(METADATA_KEY) {
-            Some(v) => {
-                let meta: HistoryMetadata = serde_json::from_value(v.clone()).unwrap_or_else(|_| {
-                    HistoryMetadata {
-                        entry_count: 0,
-                        oldest_entry: None,
-                        newest_entry: None,
-                        last_cleanup: None,
-                        retention_days: policy.retention_days,
-                    }
-                });
-                meta.last_cleanup
-            }
-            None => None,
-        };
+        let summary = db.summary()?;
+        let last_cleanup = db.get_meta::<DateTime<Utc>>(LAST_CLEANUP_KEY)?;
 
         Ok(HistoryMetadata {
-            entry_count: entries.len(),
-            oldest_entry: oldest,
-            newest_entry: newest,
+            entry_count: summary.entry_count,
+            oldest_entry: summary.oldest_entry,
+            newest_entry: summary.newest_entry,
             last_cleanup,
             retention_days: policy.retention_days,
         })
     }
 
-    /// Update metadata after changes
-    fn update_metadata(app: &AppHandle) -> Result<(), AppError> {
-        let metadata = Self::get_metadata(app)?;
-
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
-
-        let value = serde_json::to_value(&metadata)?;
-        store.set(METADATA_KEY.to_string(), value);
-        store.save().map_err(|e| AppError::Store(e.to_string()))?;
-
-        Ok(())
-    }
-
     /// Get retention policy
     pub fn get_retention_policy(app: &AppHandle) -> Result<RetentionPolicy, AppError> {
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
-
-        match store.get(RETENTION_KEY) {
-            Some(v) => {
-                let policy: RetentionPolicy = serde_json::from_value(v.clone())?;
-                Ok(policy)
-            }
-            None => Ok(RetentionPolicy::default()),
-        }
+        Ok(Self::db(app)?
+            .get_meta::<RetentionPolicy>(RETENTION_KEY)?
+            .unwrap_or_default())
     }
 
     /// Set retention policy
     pub fn set_retention_policy(app: &AppHandle, policy: &RetentionPolicy) -> Result<(), AppError> {
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
-
-        let value = serde_json::to_value(policy)?;
-        store.set(RETENTION_KEY.to_string(), value);
-        store.save().map_err(|e| AppError::Store(e.to_string()))?;
+        Self::db(app)?.set_meta(RETENTION_KEY, policy)?;
 
         log::info!(
             "Updated retention policy: {} days, auto_cleanup: {}",
@@ -213,42 +198,44 @@ impl HistoryService {
         Ok(())
     }
 
-    /// Clean up old entries based on retention policy
+    /// Roll up recent samples, then apply the tiered retention policy
+    ///
+    /// Raw samples are kept for `retention_days`, hourly rollups for
+    /// `hourly_retention_months` and daily rollups forever. Returns the number
+    /// of raw entries removed.
     pub fn cleanup(app: &AppHandle) -> Result<usize, AppError> {
         let policy = Self::get_retention_policy(app)?;
+        let db = Self::db(app)?;
+        let now = Utc::now();
 
-        // 0 means unlimited retention
-        if policy.retention_days == 0 {
-            return Ok(0);
-        }
-
-        let cutoff = Utc::now() - Duration::days(policy.retention_days as i64);
-        let mut entries = Self::get_all_entries(app)?;
-        let original_count = entries.len();
+        // Aggregate before deleting so no sample is lost from the rollups
+        Self::build_rollups(&db, now)?;
 
-        entries.retain(|e| e.timestamp >= cutoff);
-        let removed_count = original_count - entries.len();
-
-        if removed_count > 0 {
-            let store = app
-                .store(STORE_FILE)
-                .map_err(|e| AppError::Store(e.to_string()))?;
-
-            let value = serde_json::to_value(&entries)?;
-            store.set(HISTORY_KEY.to_string(), value);
+        // 0 means unlimited retention
+        let removed_count = if policy.retention_days > 0 {
+            db.delete_before(now - Duration::days(policy.retention_days as i64))?
+        } else {
+            0
+        };
 
-            // Update last_cleanup timestamp
-            let mut metadata = Self::get_metadata(app)?;
-            metadata.last_cleanup = Some(Utc::now());
-            let meta_value = serde_json::to_value(&metadata)?;
-            store.set(METADATA_KEY.to_string(), meta_value);
+        let hourly_cutoff = match policy.hourly_retention_months {
+            0 => None,
+            months => now.checked_sub_months(Months::new(months)),
+        };
+        let removed_hourly = match hourly_cutoff {
+            Some(cutoff) => db.delete_rollups_before(HistoryResolution::Hourly, cutoff)?,
+            None => 0,
+        };
 
-            store.save().map_err(|e| AppError::Store(e.to_string()))?;
+        if removed_count > 0 || removed_hourly > 0 {
+            db.set_meta(LAST_CLEANUP_KEY, &now)?;
 
             log::info!(
-                "Cleaned up {} history entries older than {} days",
+                "Cleaned up {} history entries older than {} days and {} hourly rollups older than {} months",
                 removed_count,
-                policy.retention_days
+                policy.retention_days,
+                removed_hourly,
+                policy.hourly_retention_months
             );
         }
 
@@ -263,51 +250,24 @@ impl HistoryService {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Option<UsageStats>, AppError> {
-        let query = HistoryQuery {
-            provider: Some(provider.to_string()),
-            account_id: None,
-            start_date: Some(start),
-            end_date: Some(end),
-            limit: None,
-            offset: None,
-        };
-
-        let entries = Self::query(app, &query)?;
-
-        // Find all utilization values for the specific limit
-        let utilizations: Vec<f64> = entries
-            .iter()
-            .flat_map(|e| e.limits.iter())
-            .filter(|l| l.id == limit_id)
-            .map(|l| l.utilization)
-            .collect();
-
-        if utilizations.is_empty() {
-            return Ok(None);
-        }
+        Self::db(app)?.stats(provider, limit_id, start, end)
+    }
 
-        let sum: f64 = utilizations.iter().sum();
-        let count = utilizations.len();
-        let avg = sum / count as f64;
-        let max = utilizations.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-        let min = utilizations.iter().cloned().fold(f64::INFINITY, f64::min);
-
-        Ok(Some(UsageStats {
-            provider: provider.to_string(),
-            limit_id: limit_id.to_string(),
-            period_start: start,
-            period_end: end,
-            avg_utilization: avg,
-            max_utilization: max,
-            min_utilization: min,
-            sample_count: count,
-        }))
+    /// Utilization samples of one limit taken during the window that resets at `resets_at`
+    pub fn window_samples(
+        app: &AppHandle,
+        account_id: &str,
+        limit_id: &str,
+        resets_at: DateTime<Utc>,
+        tolerance: Duration,
+    ) -> Result<Vec<(DateTime<Utc>, f64)>, AppError> {
+        Self::db(app)?.window_samples(account_id, limit_id, resets_at, tolerance)
     }
 
     /// Export history to JSON string
     pub fn export_json(app: &AppHandle, query: Option<&HistoryQuery>) -> Result<String, AppError> {
         let entries = match query {
-            Some(q) => Self::query(app, q)?,
+            Some(q) => Self::query(app, q)?.entries,
             None => Self::get_all_entries(app)?,
         };
 
@@ -318,7 +278,7 @@ impl HistoryService {
     /// Export history to CSV string
     pub fn export_csv(app: &AppHandle, query: Option<&HistoryQuery>) -> Result<String, AppError> {
         let entries = match query {
-            Some(q) => Self::query(app, q)?,
+            Some(q) => Self::query(app, q)?.entries,
             None => Self::get_all_entries(app)?,
         };
 
@@ -343,14 +303,7 @@ impl HistoryService {
 
     /// Clear all history data
     pub fn clear_all(app: &AppHandle) -> Result<(), AppError> {
-        let store = app
-            .store(STORE_FILE)
-            .map_err(|e| AppError::Store(e.to_string()))?;
-
-        store.set(HISTORY_KEY.to_string(), serde_json::json!([]));
-        store.save().map_err(|e| AppError::Store(e.to_string()))?;
-
-        Self::update_metadata(app)?;
+        Self::db(app)?.clear()?;
 
         log::info!("Cleared all history data");
         Ok(())
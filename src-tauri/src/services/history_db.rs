@@ -0,0 +1,1074 @@
+use crate::error::AppError;
+use crate::models::{
+    HistoryPage, HistoryQuery, HistoryResolution, UsageHistoryEntry, UsageLimitSnapshot, UsageStats,
+};
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// Default page size when a query does not specify a limit
+const DEFAULT_QUERY_LIMIT: usize = 1000;
+
+/// Largest page a query may ask for
+const MAX_QUERY_LIMIT: usize = 10_000;
+
+/// Current schema version (stored in PRAGMA user_version)
+const SCHEMA_VERSION: u32 = 2;
+
+/// Snapshot count and time range of the stored history
+pub struct HistorySummary {
+    pub entry_count: usize,
+    pub oldest_entry: Option<DateTime<Utc>>,
+    pub newest_entry: Option<DateTime<Utc>>,
+}
+
+/// SQLite-backed storage for usage history
+///
+/// Snapshots live in `snapshots`, one row per fetch. Each limit in a snapshot
+/// is a row in `limit_samples`, indexed by (provider, limit, timestamp) and
+/// (account, limit, timestamp) so stats and range queries never scan the table.
+///
+/// Hourly and daily rollups live in `rollups` / `rollup_limits` with the same
+/// shape, holding min/avg/max utilization per limit for each bucket.
+pub struct HistoryDb {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryDb {
+    /// Open (or create) the history database at the given path
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        Self::init(conn)
+    }
+
+    /// Open an in-memory database (for testing)
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, AppError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, AppError> {
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        Self::migrate(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Bring the schema up to date
+    fn migrate(conn: &Connection) -> Result<(), AppError> {
+        let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        if version < 1 {
+            log::info!("Creating history database schema v1");
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS snapshots (
+                     id TEXT PRIMARY KEY,
+                     provider TEXT NOT NULL,
+                     account_id TEXT NOT NULL,
+                     account_name TEXT NOT NULL,
+                     timestamp INTEGER NOT NULL
+                 );
+                 CREATE INDEX IF NOT EXISTS idx_snapshots_timestamp
+                     ON snapshots(timestamp);
+                 CREATE INDEX IF NOT EXISTS idx_snapshots_account_timestamp
+                     ON snapshots(account_id, timestamp);
+                 CREATE INDEX IF NOT EXISTS idx_snapshots_provider_timestamp
+                     ON snapshots(provider, timestamp);
+
+                 CREATE TABLE IF NOT EXISTS limit_samples (
+                     snapshot_id TEXT NOT NULL REFERENCES snapshots(id) ON DELETE CASCADE,
+                     limit_id TEXT NOT NULL,
+                     provider TEXT NOT NULL,
+                     account_id TEXT NOT NULL,
+                     timestamp INTEGER NOT NULL,
+                     utilization REAL NOT NULL,
+                     resets_at INTEGER NOT NULL,
+                     PRIMARY KEY (snapshot_id, limit_id)
+                 );
+                 CREATE INDEX IF NOT EXISTS idx_samples_provider_limit_timestamp
+                     ON limit_samples(provider, limit_id, timestamp);
+                 CREATE INDEX IF NOT EXISTS idx_samples_account_limit_timestamp
+                     ON limit_samples(account_id, limit_id, timestamp);
+
+                 CREATE TABLE IF NOT EXISTS meta (
+                     key TEXT PRIMARY KEY,
+                     value TEXT NOT NULL
+                 );",
+            )?;
+        }
+
+        if version < 2 {
+            log::info!("Migrating history database schema to v2 (rollups)");
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS rollups (
+                     id TEXT PRIMARY KEY,
+                     resolution TEXT NOT NULL,
+                     provider TEXT NOT NULL,
+                     account_id TEXT NOT NULL,
+                     account_name TEXT NOT NULL,
+                     timestamp INTEGER NOT NULL
+                 );
+                 CREATE INDEX IF NOT EXISTS idx_rollups_resolution_timestamp
+                     ON rollups(resolution, timestamp);
+                 CREATE INDEX IF NOT EXISTS idx_rollups_resolution_account_timestamp
+                     ON rollups(resolution, account_id, timestamp);
+
+                 CREATE TABLE IF NOT EXISTS rollup_limits (
+                     rollup_id TEXT NOT NULL REFERENCES rollups(id) ON DELETE CASCADE,
+                     limit_id TEXT NOT NULL,
+                     min_utilization REAL NOT NULL,
+                     avg_utilization REAL NOT NULL,
+                     max_utilization REAL NOT NULL,
+                     sample_count INTEGER NOT NULL,
+                     resets_at INTEGER NOT NULL,
+                     PRIMARY KEY (rollup_id, limit_id)
+                 );",
+            )?;
+        }
+
+        if version < SCHEMA_VERSION {
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+
+        Ok(())
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap()
+    }
+
+    /// Insert a snapshot. Returns false if an entry with the same ID already exists.
+    pub fn insert_entry(&self, entry: &UsageHistoryEntry) -> Result<bool, AppError> {
+        Ok(self.insert_entries(std::slice::from_ref(entry))? > 0)
+    }
+
+    /// Insert many snapshots in a single transaction. Returns the number inserted.
+    ///
+    /// Snapshots older than the rollups built so far (e.g. from an account
+    /// bundle) move the rollup progress back, so their buckets are aggregated
+    /// again before cleanup can delete them.
+    pub fn insert_entries(&self, entries: &[UsageHistoryEntry]) -> Result<usize, AppError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        let mut oldest: Option<i64> = None;
+        for entry in entries {
+            if Self::insert_entry_tx(&tx, entry)? {
+                inserted += 1;
+                let timestamp = entry.timestamp.timestamp_millis();
+                oldest = Some(oldest.map_or(timestamp, |oldest| oldest.min(timestamp)));
+            }
+        }
+        if let Some(oldest) = oldest {
+            Self::rewind_rollups(&tx, oldest)?;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Move each resolution's rollup progress back to the bucket holding `timestamp`
+    fn rewind_rollups(conn: &Connection, timestamp: i64) -> Result<(), AppError> {
+        for resolution in [HistoryResolution::Hourly, HistoryResolution::Daily] {
+            let bucket = resolution.bucket_millis().unwrap_or_default();
+            let watermark_key = format!("rollup_{}_until", resolution.as_str());
+            let start = timestamp - timestamp.rem_euclid(bucket);
+            let until: Option<i64> = read_meta(conn, &watermark_key)?;
+            if until.is_some_and(|until| until > start) {
+                write_meta(conn, &watermark_key, &start)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_entry_tx(conn: &Connection, entry: &UsageHistoryEntry) -> Result<bool, AppError> {
+        let timestamp = entry.timestamp.timestamp_millis();
+
+        let changed = conn
+            .prepare_cached(
+                "INSERT OR IGNORE INTO snapshots (id, provider, account_id, account_name, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?
+            .execute(params![
+                entry.id,
+                entry.provider,
+                entry.account_id,
+                entry.account_name,
+                timestamp
+            ])?;
+
+        if changed == 0 {
+            return Ok(false);
+        }
+
+        let mut stmt = conn.prepare_cached(
+            "INSERT OR IGNORE INTO limit_samples
+                 (snapshot_id, limit_id, provider, account_id, timestamp, utilization, resets_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        for limit in &entry.limits {
+            stmt.execute(params![
+                entry.id,
+                limit.id,
+                entry.provider,
+                entry.account_id,
+                timestamp,
+                limit.utilization,
+                limit.resets_at.timestamp_millis()
+            ])?;
+        }
+
+        Ok(true)
+    }
+
+    /// Query one page of snapshots (newest first) plus the total number of matches
+    ///
+    /// The page size is clamped to 1..=`MAX_QUERY_LIMIT`, so every page that
+    /// reports `has_more` carries a cursor past it.
+    pub fn query(&self, query: &HistoryQuery) -> Result<HistoryPage, AppError> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .clamp(1, MAX_QUERY_LIMIT);
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(HistoryCursor::parse)
+            .transpose()?;
+
+        let total = self.count(query)?;
+
+        // Fetch one extra row to find out whether another page exists
+        let mut entries = self.select_entries(
+            query,
+            cursor.as_ref(),
+            Some(limit + 1),
+            query.offset.unwrap_or(0),
+        )?;
+        let has_more = entries.len() > limit;
+        entries.truncate(limit);
+
+        let next_cursor = if has_more {
+            entries
+                .last()
+                .map(|e| HistoryCursor::from_entry(e).encode())
+        } else {
+            None
+        };
+
+        Ok(HistoryPage {
+            entries,
+            total,
+            next_cursor,
+            has_more,
+        })
+    }
+
+    /// Count snapshots matching the filters in `query` (ignores paging)
+    pub fn count(&self, query: &HistoryQuery) -> Result<usize, AppError> {
+        let conn = self.conn();
+        let (where_clause, values) = Self::filter_clause(query);
+        let sql = format!(
+            "SELECT COUNT(*) FROM {}{}",
+            Self::entries_table(query.resolution),
+            where_clause
+        );
+        let count: i64 = conn.query_row(&sql, params_from_iter(values), |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Get every snapshot (newest first)
+    pub fn all_entries(&self) -> Result<Vec<UsageHistoryEntry>, AppError> {
+        self.select_entries(&HistoryQuery::default(), None, None, 0)
+    }
+
+    fn select_entries(
+        &self,
+        query: &HistoryQuery,
+        cursor: Option<&HistoryCursor>,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<UsageHistoryEntry>, AppError> {
+        let conn = self.conn();
+        let (mut where_clause, mut values) = Self::filter_clause(query);
+
+        // Keyset pagination: continue strictly after the cursor in (timestamp, id) order
+        if let Some(cursor) = cursor {
+            where_clause.push_str(if where_clause.is_empty() {
+                " WHERE "
+            } else {
+                " AND "
+            });
+            where_clause.push_str("(timestamp < ? OR (timestamp = ? AND id < ?))");
+            values.push(Value::Integer(cursor.timestamp));
+            values.push(Value::Integer(cursor.timestamp));
+            values.push(Value::Text(cursor.id.clone()));
+        }
+
+        // SQLite treats a negative LIMIT as "no limit"
+        values.push(Value::Integer(limit.map(|l| l as i64).unwrap_or(-1)));
+        values.push(Value::Integer(offset as i64));
+
+        let sql = format!(
+            "SELECT id, provider, account_id, account_name, timestamp FROM {}{}
+             ORDER BY timestamp DESC, id DESC LIMIT ? OFFSET ?",
+            Self::entries_table(query.resolution),
+            where_clause
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut entries = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(UsageHistoryEntry {
+                    id: row.get(0)?,
+                    provider: row.get(1)?,
+                    account_id: row.get(2)?,
+                    account_name: row.get(3)?,
+                    timestamp: from_millis(row.get(4)?),
+                    limits: Vec::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut limits_stmt = conn.prepare_cached(match query.resolution {
+            HistoryResolution::Raw => {
+                "SELECT limit_id, utilization, resets_at, NULL, NULL FROM limit_samples
+                 WHERE snapshot_id = ?1 ORDER BY rowid"
+            }
+            HistoryResolution::Hourly | HistoryResolution::Daily => {
+                "SELECT limit_id, avg_utilization, resets_at, min_utilization, max_utilization
+                 FROM rollup_limits WHERE rollup_id = ?1 ORDER BY limit_id"
+            }
+        })?;
+        for entry in entries.iter_mut() {
+            entry.limits = limits_stmt
+                .query_map(params![entry.id], |row| {
+                    Ok(UsageLimitSnapshot {
+                        id: row.get(0)?,
+                        utilization: row.get(1)?,
+                        resets_at: from_millis(row.get(2)?),
+                        min_utilization: row.get(3)?,
+                        max_utilization: row.get(4)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        Ok(entries)
+    }
+
+    /// Table holding the entries for a resolution
+    fn entries_table(resolution: HistoryResolution) -> &'static str {
+        match resolution {
+            HistoryResolution::Raw => "snapshots",
+            HistoryResolution::Hourly | HistoryResolution::Daily => "rollups",
+        }
+    }
+
+    /// Build the WHERE clause (with leading space) and its bound values for a query
+    fn filter_clause(query: &HistoryQuery) -> (String, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if query.resolution != HistoryResolution::Raw {
+            conditions.push("resolution = ?");
+            values.push(Value::Text(query.resolution.as_str().to_string()));
+        }
+
+        if let Some(ref provider) = query.provider {
+            conditions.push("provider = ?");
+            values.push(Value::Text(provider.clone()));
+        }
+        if let Some(ref account_id) = query.account_id {
+            conditions.push("account_id = ?");
+            values.push(Value::Text(account_id.clone()));
+        }
+        if let Some(start) = query.start_date {
+            conditions.push("timestamp >= ?");
+            values.push(Value::Integer(start.timestamp_millis()));
+        }
+        if let Some(end) = query.end_date {
+            conditions.push("timestamp <= ?");
+            values.push(Value::Integer(end.timestamp_millis()));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!(" WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+
+    /// Aggregate utilization for one limit of a provider over a time range
+    pub fn stats(
+        &self,
+        provider: &str,
+        limit_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<UsageStats>, AppError> {
+        let conn = self.conn();
+        let (count, avg, min, max): (i64, Option<f64>, Option<f64>, Option<f64>) = conn.query_row(
+            "SELECT COUNT(*), AVG(utilization), MIN(utilization), MAX(utilization)
+             FROM limit_samples
+             WHERE provider = ?1 AND limit_id = ?2 AND timestamp >= ?3 AND timestamp <= ?4",
+            params![
+                provider,
+                limit_id,
+                start.timestamp_millis(),
+                end.timestamp_millis()
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+        if count == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(UsageStats {
+            provider: provider.to_string(),
+            limit_id: limit_id.to_string(),
+            period_start: start,
+            period_end: end,
+            avg_utilization: avg.unwrap_or(0.0),
+            max_utilization: max.unwrap_or(0.0),
+            min_utilization: min.unwrap_or(0.0),
+            sample_count: count as usize,
+        }))
+    }
+
+    /// Samples of one limit whose reset time lies within `tolerance` of `resets_at`,
+    /// i.e. those taken during the same window (oldest first)
+    pub fn window_samples(
+        &self,
+        account_id: &str,
+        limit_id: &str,
+        resets_at: DateTime<Utc>,
+        tolerance: chrono::Duration,
+    ) -> Result<Vec<(DateTime<Utc>, f64)>, AppError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare_cached(
+            "SELECT timestamp, utilization FROM limit_samples
+             WHERE account_id = ?1 AND limit_id = ?2 AND resets_at BETWEEN ?3 AND ?4
+             ORDER BY timestamp",
+        )?;
+        let samples = stmt
+            .query_map(
+                params![
+                    account_id,
+                    limit_id,
+                    (resets_at - tolerance).timestamp_millis(),
+                    (resets_at + tolerance).timestamp_millis()
+                ],
+                |row| Ok((from_millis(row.get(0)?), row.get(1)?)),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(samples)
+    }
+
+    /// Delete snapshots older than the cutoff. Returns the number removed.
+    pub fn delete_before(&self, cutoff: DateTime<Utc>) -> Result<usize, AppError> {
+        let conn = self.conn();
+        let removed = conn.execute(
+            "DELETE FROM snapshots WHERE timestamp < ?1",
+            params![cutoff.timestamp_millis()],
+        )?;
+        Ok(removed)
+    }
+
+    /// Aggregate every complete bucket up to `until` into rollups at `resolution`
+    ///
+    /// Hourly rollups are built from raw samples and daily rollups from hourly
+    /// ones, so hourly must be brought up to date first. Progress is tracked
+    /// per resolution in the meta table, so each bucket is aggregated once,
+    /// before cleanup can delete its source rows. Returns the number of
+    /// limit rollups written.
+    pub fn build_rollups(
+        &self,
+        resolution: HistoryResolution,
+        until: DateTime<Utc>,
+    ) -> Result<usize, AppError> {
+        let (bucket, source) = match resolution {
+            HistoryResolution::Raw => return Ok(0),
+            HistoryResolution::Hourly => (
+                resolution.bucket_millis().unwrap_or_default(),
+                "SELECT s.provider, s.account_id, n.account_name, s.limit_id, s.timestamp,
+                        s.utilization AS min_u, s.utilization AS avg_u, s.utilization AS max_u,
+                        1 AS n, s.resets_at
+                 FROM limit_samples s JOIN snapshots n ON n.id = s.snapshot_id",
+            ),
+            HistoryResolution::Daily => (
+                resolution.bucket_millis().unwrap_or_default(),
+                "SELECT r.provider, r.account_id, r.account_name, l.limit_id, r.timestamp,
+                        l.min_utilization AS min_u, l.avg_utilization AS avg_u,
+                        l.max_utilization AS max_u, l.sample_count AS n, l.resets_at
+                 FROM rollup_limits l JOIN rollups r ON r.id = l.rollup_id
+                 WHERE r.resolution = 'hourly'",
+            ),
+        };
+
+        let watermark_key = format!("rollup_{}_until", resolution.as_str());
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        let start: i64 = read_meta(&tx, &watermark_key)?.unwrap_or(0);
+        let end = until.timestamp_millis() - until.timestamp_millis().rem_euclid(bucket);
+        if end <= start {
+            return Ok(0);
+        }
+
+        let buckets = format!(
+            "SELECT provider, account_id, account_name, limit_id,
+                    timestamp - (timestamp % ?3) AS bucket,
+                    min_u, avg_u, max_u, n, resets_at
+             FROM ({}) WHERE timestamp >= ?1 AND timestamp < ?2",
+            source
+        );
+
+        tx.execute(
+            &format!(
+                "INSERT OR IGNORE INTO rollups
+                     (id, resolution, provider, account_id, account_name, timestamp)
+                 SELECT ?4 || '-' || bucket || '-' || provider || '-' || account_id,
+                        ?4, provider, account_id, MAX(account_name), bucket
+                 FROM ({}) GROUP BY provider, account_id, bucket",
+                buckets
+            ),
+            params![start, end, bucket, resolution.as_str()],
+        )?;
+
+        let written = tx.execute(
+            &format!(
+                "INSERT OR REPLACE INTO rollup_limits
+                     (rollup_id, limit_id, min_utilization, avg_utilization, max_utilization,
+                      sample_count, resets_at)
+                 SELECT ?4 || '-' || bucket || '-' || provider || '-' || account_id, limit_id,
+                        MIN(min_u), SUM(avg_u * n) / SUM(n), MAX(max_u), SUM(n), MAX(resets_at)
+                 FROM ({}) GROUP BY provider, account_id, limit_id, bucket",
+                buckets
+            ),
+            params![start, end, bucket, resolution.as_str()],
+        )?;
+
+        write_meta(&tx, &watermark_key, &end)?;
+        tx.commit()?;
+
+        Ok(written)
+    }
+
+    /// Delete rollups at `resolution` older than the cutoff. Returns the number removed.
+    pub fn delete_rollups_before(
+        &self,
+        resolution: HistoryResolution,
+        cutoff: DateTime<Utc>,
+    ) -> Result<usize, AppError> {
+        let conn = self.conn();
+        let removed = conn.execute(
+            "DELETE FROM rollups WHERE resolution = ?1 AND timestamp < ?2",
+            params![resolution.as_str(), cutoff.timestamp_millis()],
+        )?;
+        Ok(removed)
+    }
+
+    /// Delete all snapshots and rollups
+    pub fn clear(&self) -> Result<(), AppError> {
+        let conn = self.conn();
+        conn.execute_batch(
+            "DELETE FROM snapshots;
+             DELETE FROM rollups;
+             DELETE FROM meta WHERE key LIKE 'rollup_%';",
+        )?;
+        Ok(())
+    }
+
+    /// Number of snapshots plus the oldest and newest timestamps
+    pub fn summary(&self) -> Result<HistorySummary, AppError> {
+        let conn = self.conn();
+        let (count, oldest, newest): (i64, Option<i64>, Option<i64>) = conn.query_row(
+            "SELECT COUNT(*), MIN(timestamp), MAX(timestamp) FROM snapshots",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        Ok(HistorySummary {
+            entry_count: count as usize,
+            oldest_entry: oldest.map(from_millis),
+            newest_entry: newest.map(from_millis),
+        })
+    }
+
+    /// Read a JSON value from the meta table
+    pub fn get_meta<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, AppError> {
+        read_meta(&self.conn(), key)
+    }
+
+    /// Write a JSON value to the meta table
+    pub fn set_meta<T: Serialize>(&self, key: &str, value: &T) -> Result<(), AppError> {
+        write_meta(&self.conn(), key, value)
+    }
+}
+
+fn read_meta<T: DeserializeOwned>(conn: &Connection, key: &str) -> Result<Option<T>, AppError> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match value {
+        Some(v) => Ok(Some(serde_json::from_str(&v)?)),
+        None => Ok(None),
+    }
+}
+
+fn write_meta<T: Serialize>(conn: &Connection, key: &str, value: &T) -> Result<(), AppError> {
+    let json = serde_json::to_string(value)?;
+    conn.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, json],
+    )?;
+    Ok(())
+}
+
+/// Position of the last entry on a page, in (timestamp, id) sort order
+///
+/// Encoded as `<unix millis>:<entry id>` so it stays stable while new
+/// snapshots are inserted at the head of the list.
+struct HistoryCursor {
+    timestamp: i64,
+    id: String,
+}
+
+impl HistoryCursor {
+    fn from_entry(entry: &UsageHistoryEntry) -> Self {
+        Self {
+            timestamp: entry.timestamp.timestamp_millis(),
+            id: entry.id.clone(),
+        }
+    }
+
+    fn parse(cursor: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::InvalidInput(format!("Invalid history cursor: {}", cursor));
+        let (timestamp, id) = cursor.split_once(':').ok_or_else(invalid)?;
+        let timestamp = timestamp.parse::<i64>().map_err(|_| invalid())?;
+        if id.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            timestamp,
+            id: id.to_string(),
+        })
+    }
+
+    fn encode(&self) -> String {
+        format!("{}:{}", self.timestamp, self.id)
+    }
+}
+
+fn from_millis(millis: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(millis).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn make_entry(account_id: &str, minutes_ago: i64, utilization: f64) -> UsageHistoryEntry {
+        let timestamp =
+            Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap() - Duration::minutes(minutes_ago);
+        UsageHistoryEntry {
+            id: format!("{}-claude-{}", timestamp.timestamp(), account_id),
+            provider: "claude".to_string(),
+            account_id: account_id.to_string(),
+            account_name: "Personal".to_string(),
+            timestamp,
+            limits: vec![
+                UsageLimitSnapshot {
+                    id: "five_hour".to_string(),
+                    utilization,
+                    resets_at: timestamp + Duration::hours(5),
+                    min_utilization: None,
+                    max_utilization: None,
+                },
+                UsageLimitSnapshot {
+                    id: "seven_day".to_string(),
+                    utilization: utilization / 2.0,
+                    resets_at: timestamp + Duration::days(7),
+                    min_utilization: None,
+                    max_utilization: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn insert_and_query_roundtrip() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let entry = make_entry("acc-1", 0, 40.0);
+        assert!(db.insert_entry(&entry).unwrap());
+
+        let entries = db.all_entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, entry.id);
+        assert_eq!(entries[0].timestamp, entry.timestamp);
+        assert_eq!(entries[0].limits.len(), 2);
+        assert_eq!(entries[0].limits[0].id, "five_hour");
+        assert_eq!(entries[0].limits[1].id, "seven_day");
+        assert_eq!(entries[0].limits[1].resets_at, entry.limits[1].resets_at);
+    }
+
+    #[test]
+    fn duplicate_entries_are_ignored() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let entry = make_entry("acc-1", 0, 40.0);
+        assert!(db.insert_entry(&entry).unwrap());
+        assert!(!db.insert_entry(&entry).unwrap());
+        assert_eq!(db.summary().unwrap().entry_count, 1);
+    }
+
+    #[test]
+    fn query_filters_sorts_and_pages() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let entries: Vec<_> = (0..5)
+            .map(|i| make_entry("acc-1", i * 10, 10.0 * i as f64))
+            .chain(std::iter::once(make_entry("acc-2", 5, 80.0)))
+            .collect();
+        assert_eq!(db.insert_entries(&entries).unwrap(), 6);
+
+        let query = HistoryQuery {
+            account_id: Some("acc-1".to_string()),
+            limit: Some(2),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let page = db.query(&query).unwrap();
+        assert_eq!(page.entries.len(), 2);
+        // Newest first, skipping the newest one
+        assert_eq!(page.entries[0].id, entries[1].id);
+        assert_eq!(page.entries[1].id, entries[2].id);
+        // Total counts every match, not just this page
+        assert_eq!(page.total, 5);
+        assert!(page.has_more);
+
+        let query = HistoryQuery {
+            start_date: Some(entries[2].timestamp),
+            ..Default::default()
+        };
+        // acc-1 at 0, 10, 20 minutes ago plus acc-2 at 5 minutes ago
+        let page = db.query(&query).unwrap();
+        assert_eq!(page.entries.len(), 4);
+        assert_eq!(page.total, 4);
+        assert!(!page.has_more);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn cursor_walks_all_pages_without_overlap() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let entries: Vec<_> = (0..7).map(|i| make_entry("acc-1", i, 10.0)).collect();
+        db.insert_entries(&entries).unwrap();
+
+        let mut seen = Vec::new();
+        let mut query = HistoryQuery {
+            limit: Some(3),
+            ..Default::default()
+        };
+        loop {
+            let page = db.query(&query).unwrap();
+            assert_eq!(page.total, 7);
+            seen.extend(page.entries.into_iter().map(|e| e.id));
+            match page.next_cursor {
+                Some(cursor) => query.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        let expected: Vec<_> = entries.iter().map(|e| e.id.clone()).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn zero_limit_still_returns_a_page() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let entries: Vec<_> = (0..3).map(|i| make_entry("acc-1", i, 10.0)).collect();
+        db.insert_entries(&entries).unwrap();
+
+        let page = db
+            .query(&HistoryQuery {
+                limit: Some(0),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert!(page.has_more);
+        assert!(page.next_cursor.is_some());
+    }
+
+    #[test]
+    fn huge_limit_is_clamped() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let entries: Vec<_> = (0..3).map(|i| make_entry("acc-1", i, 10.0)).collect();
+        db.insert_entries(&entries).unwrap();
+
+        let page = db
+            .query(&HistoryQuery {
+                limit: Some(usize::MAX),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.entries.len(), 3);
+        assert!(!page.has_more);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn invalid_cursor_is_rejected() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let query = HistoryQuery {
+            cursor: Some("not-a-cursor".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(db.query(&query), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn stats_aggregate_single_limit() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let entries: Vec<_> = [10.0, 20.0, 60.0]
+            .iter()
+            .enumerate()
+            .map(|(i, u)| make_entry("acc-1", i as i64, *u))
+            .collect();
+        db.insert_entries(&entries).unwrap();
+
+        let start = entries[2].timestamp;
+        let end = entries[0].timestamp;
+        let stats = db
+            .stats("claude", "five_hour", start, end)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.sample_count, 3);
+        assert!((stats.avg_utilization - 30.0).abs() < 0.001);
+        assert!((stats.min_utilization - 10.0).abs() < 0.001);
+        assert!((stats.max_utilization - 60.0).abs() < 0.001);
+
+        assert!(db.stats("claude", "unknown", start, end).unwrap().is_none());
+    }
+
+    #[test]
+    fn delete_before_cascades_to_samples() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let old = make_entry("acc-1", 60 * 24 * 40, 10.0);
+        let recent = make_entry("acc-1", 0, 20.0);
+        db.insert_entries(&[old.clone(), recent.clone()]).unwrap();
+
+        let removed = db
+            .delete_before(recent.timestamp - Duration::days(30))
+            .unwrap();
+        assert_eq!(removed, 1);
+
+        let stats = db
+            .stats("claude", "five_hour", old.timestamp, recent.timestamp)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.sample_count, 1);
+    }
+
+    #[test]
+    fn meta_roundtrip() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        assert!(db.get_meta::<u32>("missing").unwrap().is_none());
+        db.set_meta("answer", &42u32).unwrap();
+        db.set_meta("answer", &43u32).unwrap();
+        assert_eq!(db.get_meta::<u32>("answer").unwrap(), Some(43));
+    }
+
+    #[test]
+    fn window_samples_match_reset_time() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        let mut previous_window = make_entry("acc-1", 400, 90.0);
+        for limit in previous_window.limits.iter_mut() {
+            limit.resets_at -= Duration::hours(5);
+        }
+        db.insert_entries(&[
+            previous_window,
+            make_entry("acc-1", 30, 10.0),
+            make_entry("acc-1", 20, 15.0),
+            make_entry("acc-2", 20, 50.0),
+        ])
+        .unwrap();
+
+        // Resets drift with the sample time in make_entry, so allow the spread
+        let resets_at = make_entry("acc-1", 0, 0.0).limits[0].resets_at;
+        let samples = db
+            .window_samples("acc-1", "five_hour", resets_at, Duration::minutes(45))
+            .unwrap();
+        let utilizations: Vec<f64> = samples.iter().map(|(_, u)| *u).collect();
+        assert_eq!(utilizations, vec![10.0, 15.0]);
+    }
+
+    fn rollup_query(resolution: HistoryResolution) -> HistoryQuery {
+        HistoryQuery {
+            resolution,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rollups_aggregate_min_avg_max() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        // 10:30, 10:40, 10:50 and 11:40 on 2025-01-15
+        db.insert_entries(&[
+            make_entry("acc-1", 90, 10.0),
+            make_entry("acc-1", 80, 20.0),
+            make_entry("acc-1", 70, 30.0),
+            make_entry("acc-1", 20, 50.0),
+        ])
+        .unwrap();
+
+        let next_day = Utc.with_ymd_and_hms(2025, 1, 16, 1, 0, 0).unwrap();
+        // Two hours x two limits
+        assert_eq!(
+            db.build_rollups(HistoryResolution::Hourly, next_day)
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            db.build_rollups(HistoryResolution::Daily, next_day)
+                .unwrap(),
+            2
+        );
+
+        let hourly = db.query(&rollup_query(HistoryResolution::Hourly)).unwrap();
+        assert_eq!(hourly.total, 2);
+        let ten = &hourly.entries[1];
+        assert_eq!(
+            ten.timestamp,
+            Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap()
+        );
+        let five_hour = ten.limits.iter().find(|l| l.id == "five_hour").unwrap();
+        assert_eq!(five_hour.min_utilization, Some(10.0));
+        assert_eq!(five_hour.utilization, 20.0);
+        assert_eq!(five_hour.max_utilization, Some(30.0));
+
+        // Daily average is weighted by the number of raw samples per hour
+        let daily = db.query(&rollup_query(HistoryResolution::Daily)).unwrap();
+        assert_eq!(daily.total, 1);
+        let five_hour = daily.entries[0]
+            .limits
+            .iter()
+            .find(|l| l.id == "five_hour")
+            .unwrap();
+        assert_eq!(five_hour.min_utilization, Some(10.0));
+        assert_eq!(five_hour.utilization, 27.5);
+        assert_eq!(five_hour.max_utilization, Some(50.0));
+    }
+
+    #[test]
+    fn rollups_skip_incomplete_and_already_built_buckets() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        db.insert_entries(&[make_entry("acc-1", 90, 10.0), make_entry("acc-1", 20, 50.0)])
+            .unwrap();
+
+        // 11:59 - the 11:00 bucket is still open
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 11, 59, 0).unwrap();
+        assert_eq!(db.build_rollups(HistoryResolution::Hourly, now).unwrap(), 2);
+        assert_eq!(db.build_rollups(HistoryResolution::Hourly, now).unwrap(), 0);
+        assert_eq!(
+            db.query(&rollup_query(HistoryResolution::Hourly))
+                .unwrap()
+                .total,
+            1
+        );
+
+        let later = now + Duration::hours(1);
+        assert_eq!(
+            db.build_rollups(HistoryResolution::Hourly, later).unwrap(),
+            2
+        );
+        assert_eq!(
+            db.query(&rollup_query(HistoryResolution::Hourly))
+                .unwrap()
+                .total,
+            2
+        );
+    }
+
+    #[test]
+    fn late_entries_are_rolled_up() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        db.insert_entry(&make_entry("acc-1", 20, 50.0)).unwrap();
+
+        let next_day = Utc.with_ymd_and_hms(2025, 1, 16, 1, 0, 0).unwrap();
+        db.build_rollups(HistoryResolution::Hourly, next_day)
+            .unwrap();
+        db.build_rollups(HistoryResolution::Daily, next_day)
+            .unwrap();
+
+        // An imported entry from a day that was already rolled up
+        db.insert_entries(&[make_entry("acc-2", 24 * 60, 10.0)])
+            .unwrap();
+        db.build_rollups(HistoryResolution::Hourly, next_day)
+            .unwrap();
+        db.build_rollups(HistoryResolution::Daily, next_day)
+            .unwrap();
+
+        let hourly = db.query(&rollup_query(HistoryResolution::Hourly)).unwrap();
+        assert_eq!(hourly.total, 2);
+        let daily = db.query(&rollup_query(HistoryResolution::Daily)).unwrap();
+        assert_eq!(daily.total, 2);
+        assert!(daily.entries.iter().any(|e| e.account_id == "acc-2"));
+    }
+
+    #[test]
+    fn rollups_survive_raw_and_hourly_retention() {
+        let db = HistoryDb::open_in_memory().unwrap();
+        db.insert_entries(&[make_entry("acc-1", 90, 10.0), make_entry("acc-2", 20, 50.0)])
+            .unwrap();
+
+        let next_day = Utc.with_ymd_and_hms(2025, 1, 16, 1, 0, 0).unwrap();
+        db.build_rollups(HistoryResolution::Hourly, next_day)
+            .unwrap();
+        db.build_rollups(HistoryResolution::Daily, next_day)
+            .unwrap();
+
+        assert_eq!(db.delete_before(next_day).unwrap(), 2);
+        assert_eq!(db.query(&HistoryQuery::default()).unwrap().total, 0);
+        assert_eq!(
+            db.query(&rollup_query(HistoryResolution::Hourly))
+                .unwrap()
+                .total,
+            2
+        );
+
+        assert_eq!(
+            db.delete_rollups_before(HistoryResolution::Hourly, next_day)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            db.query(&rollup_query(HistoryResolution::Hourly))
+                .unwrap()
+                .total,
+            0
+        );
+
+        // One daily bucket per account, filterable like raw entries
+        let query = HistoryQuery {
+            account_id: Some("acc-2".to_string()),
+            ..rollup_query(HistoryResolution::Daily)
+        };
+        let daily = db.query(&query).unwrap();
+        assert_eq!(daily.total, 1);
+        assert_eq!(daily.entries[0].account_id, "acc-2");
+    }
+}
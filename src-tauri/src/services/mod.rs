@@ -1,12 +1,32 @@
+mod browser_cookies;
+mod circuit_breaker;
+mod counters;
 mod credentials;
 mod crypto;
+mod forecast;
 mod history;
+mod history_db;
 mod notifications;
 mod scheduler;
+mod schema;
+mod secrets;
+mod session_import;
 mod settings;
+mod transfer;
+mod vault;
+mod webhooks;
 
+pub use circuit_breaker::{CircuitBreaker, CircuitState, CircuitStateEvent, ErrorClass};
+pub use counters::CounterService;
 pub use credentials::CredentialService;
+pub use forecast::ForecastService;
 pub use history::HistoryService;
+pub use history_db::HistoryDb;
 pub use notifications::{NotificationService, NotificationState};
 pub use scheduler::{SchedulerService, SchedulerState};
+pub use schema::{SchemaDriftEvent, SchemaService};
+pub use session_import::{BrowserSession, SessionImportService};
 pub use settings::SettingsService;
+pub use transfer::{ImportConflict, ImportSummary, TransferService};
+pub use vault::{VaultService, VaultStatus};
+pub use webhooks::WebhookService;
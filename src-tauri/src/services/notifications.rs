@@ -1,17 +1,35 @@
-use crate::models::{AppSettings, NotificationSettings, UsageData, UsageLimit};
-use crate::services::SettingsService;
-use chrono::{Duration, Local, NaiveTime, Utc};
-use std::collections::HashSet;
+use crate::models::{
+    AppSettings, LimitForecast, NotificationSettings, UsageData, UsageLimit, WebhookEventType,
+};
+use crate::services::{ForecastService, SettingsService, WebhookService};
+use chrono::{DateTime, Duration, Local, NaiveTime, Utc};
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter};
 use tauri_plugin_notification::NotificationExt;
 
+/// Reset times this close together belong to the same window
+const RESET_WINDOW_TOLERANCE_MINUTES: i64 = 15;
+
+/// Minimum samples in the current window before trusting a projected overrun
+const MIN_OVERRUN_SAMPLES: usize = 3;
+
+/// Where an alert is delivered; each channel remembers what it was sent on its
+/// own, so a desktop notification held back by Do Not Disturb still shows later
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Webhook,
+    Toast,
+}
+
 /// Tracks which notifications have been sent to avoid duplicates
 pub struct NotificationState {
-    /// Set of (account_id, limit_id, threshold) tuples that have been notified
-    sent_thresholds: Mutex<HashSet<(String, String, u32)>>,
+    /// Set of (channel, account_id, limit_id, threshold) tuples that have been notified
+    sent_thresholds: Mutex<HashSet<(Channel, String, String, u32)>>,
     /// Set of (account_id, limit_id) pairs that have been notified for upcoming reset
     sent_reset_warnings: Mutex<HashSet<(String, String)>>,
+    /// Reset time of the window each (channel, account_id, limit_id) was last warned about for a projected overrun
+    sent_overrun_warnings: Mutex<HashMap<(Channel, String, String), DateTime<Utc>>>,
 }
 
 impl Default for NotificationState {
@@ -19,6 +37,7 @@ impl Default for NotificationState {
         Self {
             sent_thresholds: Mutex::new(HashSet::new()),
             sent_reset_warnings: Mutex::new(HashSet::new()),
+            sent_overrun_warnings: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -28,28 +47,52 @@ impl NotificationState {
         Self::default()
     }
 
-    /// Check if a threshold notification was already sent for this account
-    pub fn was_threshold_notified(&self, account_id: &str, limit_id: &str, threshold: u32) -> bool {
+    /// Check if a threshold notification was already sent through a channel for this account
+    pub fn was_threshold_notified(
+        &self,
+        channel: Channel,
+        account_id: &str,
+        limit_id: &str,
+        threshold: u32,
+    ) -> bool {
         let sent = self.sent_thresholds.lock().unwrap();
-        sent.contains(&(account_id.to_string(), limit_id.to_string(), threshold))
+        sent.contains(&(
+            channel,
+            account_id.to_string(),
+            limit_id.to_string(),
+            threshold,
+        ))
     }
 
-    /// Mark a threshold notification as sent for this account
-    pub fn mark_threshold_notified(&self, account_id: &str, limit_id: &str, threshold: u32) {
+    /// Mark a threshold notification as sent through a channel for this account
+    pub fn mark_threshold_notified(
+        &self,
+        channel: Channel,
+        account_id: &str,
+        limit_id: &str,
+        threshold: u32,
+    ) {
         let mut sent = self.sent_thresholds.lock().unwrap();
-        sent.insert((account_id.to_string(), limit_id.to_string(), threshold));
+        sent.insert((
+            channel,
+            account_id.to_string(),
+            limit_id.to_string(),
+            threshold,
+        ));
     }
 
     /// Clear threshold notifications for a limit (called when usage drops or resets)
     pub fn clear_threshold(&self, account_id: &str, limit_id: &str, threshold: u32) {
         let mut sent = self.sent_thresholds.lock().unwrap();
-        sent.remove(&(account_id.to_string(), limit_id.to_string(), threshold));
+        sent.retain(|(_, acc, id, thresh)| {
+            !(acc == account_id && id == limit_id && *thresh == threshold)
+        });
     }
 
     /// Clear all thresholds above a certain value for a limit on this account
     pub fn clear_thresholds_above(&self, account_id: &str, limit_id: &str, current_percent: u32) {
         let mut sent = self.sent_thresholds.lock().unwrap();
-        sent.retain(|(acc, id, thresh)| {
+        sent.retain(|(_, acc, id, thresh)| {
             !(acc == account_id && id == limit_id && *thresh > current_percent)
         });
     }
@@ -71,30 +114,155 @@ impl NotificationState {
         let mut sent = self.sent_reset_warnings.lock().unwrap();
         sent.remove(&(account_id.to_string(), limit_id.to_string()));
     }
+
+    /// Check if a projected overrun warning was sent through a channel for this account's current window
+    pub fn was_overrun_warning_sent(
+        &self,
+        channel: Channel,
+        account_id: &str,
+        limit_id: &str,
+        resets_at: DateTime<Utc>,
+    ) -> bool {
+        let sent = self.sent_overrun_warnings.lock().unwrap();
+        sent.get(&(channel, account_id.to_string(), limit_id.to_string()))
+            .is_some_and(|warned| {
+                (*warned - resets_at).abs() <= Duration::minutes(RESET_WINDOW_TOLERANCE_MINUTES)
+            })
+    }
+
+    /// Mark a projected overrun warning as sent through a channel for this window (replaces any earlier window)
+    pub fn mark_overrun_warning_sent(
+        &self,
+        channel: Channel,
+        account_id: &str,
+        limit_id: &str,
+        resets_at: DateTime<Utc>,
+    ) {
+        let mut sent = self.sent_overrun_warnings.lock().unwrap();
+        sent.insert(
+            (channel, account_id.to_string(), limit_id.to_string()),
+            resets_at,
+        );
+    }
+}
+
+/// What a fired event is deduplicated by
+enum AlertKey {
+    Threshold(u32),
+    /// Reset time of the window the overrun is projected in
+    Overrun(DateTime<Utc>),
+    /// Fires on the drop itself, so there is nothing to remember
+    Reset,
+}
+
+/// An event for this usage update: delivered to webhooks when `webhook` is
+/// set, shown as a desktop notification when `toast` is set and notifications
+/// are allowed
+struct FiredEvent {
+    event: WebhookEventType,
+    limit_id: String,
+    key: AlertKey,
+    data: serde_json::Value,
+    title: String,
+    body: String,
+    /// Not yet delivered to webhooks
+    webhook: bool,
+    /// Wanted as a desktop notification and not yet shown
+    toast: bool,
 }
 
 pub struct NotificationService;
 
 impl NotificationService {
-    /// Process usage data and send appropriate notifications
+    /// Process usage data: deliver webhooks for every event that fired and
+    /// show desktop notifications for the ones the user wants to see
     pub fn process_usage(
         app: &AppHandle,
         state: &NotificationState,
         usage: &UsageData,
         previous_usage: Option<&UsageData>,
     ) {
-        // Get notification settings
         let settings = match SettingsService::get(app) {
             Ok(s) => s,
             Err(_) => return,
         };
 
-        if !settings.notifications.enabled {
-            return;
+        // Forecasts are only needed for predictive alerts
+        let wants_overrun = settings.notifications.notify_on_projected_overrun
+            || settings
+                .webhooks
+                .iter()
+                .any(|w| w.accepts(WebhookEventType::ProjectedOverrun));
+        let forecasts = if wants_overrun {
+            ForecastService::forecast_limits(app, &usage.account_id, &usage.limits, usage.timestamp)
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to forecast usage for {}: {}", usage.account_name, e);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+
+        let toasts = Self::toasts_allowed(&settings.notifications, Local::now().time());
+        for fired in Self::usage_events(state, &settings, usage, previous_usage, &forecasts) {
+            if fired.webhook {
+                WebhookService::dispatch(app, fired.event, &fired.data);
+
+                if fired.event == WebhookEventType::UsageReset {
+                    // Frontend confetti animation
+                    let _ = app.emit("usage-reset", &fired.limit_id);
+                }
+            }
+
+            // Toasts held back now stay pending and show on a later update
+            let shown =
+                toasts && fired.toast && Self::send_notification(app, &fired.title, &fired.body);
+            if shown {
+                Self::mark_toast_shown(state, &usage.account_id, &fired);
+            }
+
+            if fired.webhook || shown {
+                log::info!(
+                    "{} for {} ({})",
+                    fired.title,
+                    fired.limit_id,
+                    usage.account_name
+                );
+            }
+        }
+    }
+
+    /// Record that the desktop notification for an event was shown
+    fn mark_toast_shown(state: &NotificationState, account_id: &str, fired: &FiredEvent) {
+        match fired.key {
+            AlertKey::Threshold(threshold) => state.mark_threshold_notified(
+                Channel::Toast,
+                account_id,
+                &fired.limit_id,
+                threshold,
+            ),
+            AlertKey::Overrun(resets_at) => state.mark_overrun_warning_sent(
+                Channel::Toast,
+                account_id,
+                &fired.limit_id,
+                resets_at,
+            ),
+            AlertKey::Reset => {}
         }
+    }
 
+    /// Events for this usage update that a channel hasn't been sent yet; webhook
+    /// delivery is recorded in `state` here, toasts once they are shown
+    fn usage_events(
+        state: &NotificationState,
+        settings: &AppSettings,
+        usage: &UsageData,
+        previous_usage: Option<&UsageData>,
+        forecasts: &[LimitForecast],
+    ) -> Vec<FiredEvent> {
         let account_id = &usage.account_id;
         let account_name = &usage.account_name;
+        let mut events = Vec::new();
 
         for limit in &usage.limits {
             // utilization is already a percentage (0-100) from the API
@@ -103,25 +271,120 @@ impl NotificationService {
             // Clear thresholds that are now above current usage (usage dropped)
             state.clear_thresholds_above(account_id, &limit.id, current_percent);
 
-            // Check threshold notifications
-            Self::check_threshold_notifications(app, state, account_id, account_name, limit, &settings);
+            events.extend(Self::check_thresholds(
+                state,
+                account_id,
+                account_name,
+                limit,
+                settings,
+            ));
+
+            if let Some(fired) = Self::check_reset(
+                state,
+                account_id,
+                account_name,
+                limit,
+                previous_usage,
+                settings,
+            ) {
+                events.push(fired);
+            }
 
-            // Check for reset notifications
-            if settings.notifications.notify_on_reset {
-                Self::check_reset_notification(app, state, account_id, account_name, limit, previous_usage);
+            // Check for a projected overrun before the limit resets
+            if let Some(forecast) = forecasts.iter().find(|f| f.limit_id == limit.id) {
+                if let Some(fired) = Self::check_projected_overrun(
+                    state,
+                    account_id,
+                    account_name,
+                    limit,
+                    forecast,
+                    settings,
+                ) {
+                    events.push(fired);
+                }
             }
         }
+
+        events
     }
 
-    /// Check and send threshold notifications
-    fn check_threshold_notifications(
-        app: &AppHandle,
+    /// Whether a forecast projects the limit past 100% before it resets
+    fn is_projected_overrun(forecast: &LimitForecast) -> bool {
+        forecast.utilization < 100.0
+            && forecast.sample_count >= MIN_OVERRUN_SAMPLES
+            && forecast.projected_at_reset.is_some_and(|p| p > 100.0)
+    }
+
+    /// "On track to exceed", once per reset window
+    fn check_projected_overrun(
         state: &NotificationState,
         account_id: &str,
         account_name: &str,
         limit: &UsageLimit,
+        forecast: &LimitForecast,
         settings: &AppSettings,
-    ) {
+    ) -> Option<FiredEvent> {
+        if !Self::is_projected_overrun(forecast) {
+            return None;
+        }
+        let webhook = !state.was_overrun_warning_sent(
+            Channel::Webhook,
+            account_id,
+            &limit.id,
+            limit.resets_at,
+        );
+        let toast = settings.notifications.notify_on_projected_overrun
+            && !state.was_overrun_warning_sent(
+                Channel::Toast,
+                account_id,
+                &limit.id,
+                limit.resets_at,
+            );
+        if !webhook && !toast {
+            return None;
+        }
+        state.mark_overrun_warning_sent(Channel::Webhook, account_id, &limit.id, limit.resets_at);
+
+        let minutes = forecast
+            .hits_limit_at
+            .map(|at| at.signed_duration_since(Utc::now()).num_minutes().max(0))
+            .unwrap_or(0);
+        let current_percent = limit.utilization as u32;
+
+        let body = if account_name != "Default" && !account_name.is_empty() {
+            format!(
+                "[{}] {} will run out in {} minutes at the current pace, before it resets (currently at {}%)",
+                account_name, limit.label, minutes, current_percent
+            )
+        } else {
+            format!(
+                "{} will run out in {} minutes at the current pace, before it resets (currently at {}%)",
+                limit.label, minutes, current_percent
+            )
+        };
+
+        let mut data = limit_event_data(account_id, account_name, limit);
+        data["forecast"] = serde_json::to_value(forecast).unwrap_or_default();
+        Some(FiredEvent {
+            event: WebhookEventType::ProjectedOverrun,
+            limit_id: limit.id.clone(),
+            key: AlertKey::Overrun(limit.resets_at),
+            data,
+            title: "On Track to Exceed Limit".to_string(),
+            body,
+            webhook,
+            toast,
+        })
+    }
+
+    /// Thresholds the limit crossed since they were last cleared
+    fn check_thresholds(
+        state: &NotificationState,
+        account_id: &str,
+        account_name: &str,
+        limit: &UsageLimit,
+        settings: &AppSettings,
+    ) -> Vec<FiredEvent> {
         // utilization is already a percentage (0-100) from the API
         let current_percent = limit.utilization as u32;
 
@@ -133,87 +396,95 @@ impl NotificationService {
             current_percent
         );
 
+        let mut events = Vec::new();
         for &threshold in &settings.notifications.thresholds {
-            if current_percent >= threshold && !state.was_threshold_notified(account_id, &limit.id, threshold) {
-                // Include account name in notification if it's not "Default"
-                let title = format!("{}% Usage Alert", threshold);
-                let body = if account_name != "Default" && !account_name.is_empty() {
-                    format!(
-                        "[{}] {} is at {}% usage",
-                        account_name,
-                        limit.label,
-                        current_percent.min(100)
-                    )
-                } else {
-                    format!(
-                        "{} is at {}% usage",
-                        limit.label,
-                        current_percent.min(100)
-                    )
-                };
-
-                if Self::send_notification(app, &title, &body) {
-                    state.mark_threshold_notified(account_id, &limit.id, threshold);
-                    log::info!(
-                        "Sent {}% threshold notification for {} ({})",
-                        threshold,
-                        limit.id,
-                        account_name
-                    );
-                }
+            if current_percent < threshold {
+                continue;
+            }
+            let webhook =
+                !state.was_threshold_notified(Channel::Webhook, account_id, &limit.id, threshold);
+            let toast =
+                !state.was_threshold_notified(Channel::Toast, account_id, &limit.id, threshold);
+            if !webhook && !toast {
+                continue;
             }
+            state.mark_threshold_notified(Channel::Webhook, account_id, &limit.id, threshold);
+
+            // Include account name in notification if it's not "Default"
+            let body = if account_name != "Default" && !account_name.is_empty() {
+                format!(
+                    "[{}] {} is at {}% usage",
+                    account_name,
+                    limit.label,
+                    current_percent.min(100)
+                )
+            } else {
+                format!("{} is at {}% usage", limit.label, current_percent.min(100))
+            };
+
+            let mut data = limit_event_data(account_id, account_name, limit);
+            data["threshold"] = threshold.into();
+            events.push(FiredEvent {
+                event: WebhookEventType::ThresholdCrossed,
+                limit_id: limit.id.clone(),
+                key: AlertKey::Threshold(threshold),
+                data,
+                title: format!("{}% Usage Alert", threshold),
+                body,
+                webhook,
+                toast,
+            });
         }
+        events
     }
 
-    /// Check and send reset notification
-    fn check_reset_notification(
-        app: &AppHandle,
+    /// A limit that just reset (previous was high, now low)
+    fn check_reset(
         state: &NotificationState,
         account_id: &str,
         account_name: &str,
         limit: &UsageLimit,
         previous_usage: Option<&UsageData>,
-    ) {
-        // Check if this limit just reset (previous was high, now low)
-        if let Some(prev) = previous_usage {
-            if let Some(prev_limit) = prev.limits.iter().find(|l| l.id == limit.id) {
-                // utilization is already a percentage (0-100) from the API
-                let prev_percent = prev_limit.utilization as u32;
-                let curr_percent = limit.utilization as u32;
-
-                // If usage dropped significantly (more than 50%) and was previously high
-                if prev_percent >= 50 && curr_percent < prev_percent.saturating_sub(40) {
-                    let title = "Usage Reset";
-                    let body = if account_name != "Default" && !account_name.is_empty() {
-                        format!(
-                            "[{}] {} has reset! Now at {}%",
-                            account_name,
-                            limit.label,
-                            curr_percent
-                        )
-                    } else {
-                        format!(
-                            "{} has reset! Now at {}%",
-                            limit.label,
-                            curr_percent
-                        )
-                    };
-
-                    Self::send_notification(app, title, &body);
-                    state.clear_reset_warning(account_id, &limit.id);
-
-                    // Clear all threshold notifications for this limit
-                    for thresh in [50, 75, 90, 100] {
-                        state.clear_threshold(account_id, &limit.id, thresh);
-                    }
-
-                    // Emit event for frontend confetti animation
-                    let _ = app.emit("usage-reset", &limit.id);
-
-                    log::info!("Sent reset notification for {} ({})", limit.id, account_name);
-                }
-            }
+        settings: &AppSettings,
+    ) -> Option<FiredEvent> {
+        let prev_limit = previous_usage?.limits.iter().find(|l| l.id == limit.id)?;
+
+        // utilization is already a percentage (0-100) from the API
+        let prev_percent = prev_limit.utilization as u32;
+        let curr_percent = limit.utilization as u32;
+
+        // If usage dropped significantly (more than 50%) and was previously high
+        if prev_percent < 50 || curr_percent >= prev_percent.saturating_sub(40) {
+            return None;
         }
+
+        state.clear_reset_warning(account_id, &limit.id);
+        // Clear all threshold notifications for this limit
+        for thresh in [50, 75, 90, 100] {
+            state.clear_threshold(account_id, &limit.id, thresh);
+        }
+
+        let body = if account_name != "Default" && !account_name.is_empty() {
+            format!(
+                "[{}] {} has reset! Now at {}%",
+                account_name, limit.label, curr_percent
+            )
+        } else {
+            format!("{} has reset! Now at {}%", limit.label, curr_percent)
+        };
+
+        let mut data = limit_event_data(account_id, account_name, limit);
+        data["previousUtilization"] = prev_limit.utilization.into();
+        Some(FiredEvent {
+            event: WebhookEventType::UsageReset,
+            limit_id: limit.id.clone(),
+            key: AlertKey::Reset,
+            data,
+            title: "Usage Reset".to_string(),
+            body,
+            webhook: true,
+            toast: settings.notifications.notify_on_reset,
+        })
     }
 
     /// Send notification for upcoming reset (within 1 hour)
@@ -266,7 +537,7 @@ impl NotificationService {
     }
 
     /// Send a session expiry warning
-    pub fn send_session_expiry_warning(app: &AppHandle) {
+    pub fn send_session_expiry_warning(app: &AppHandle, provider_name: &str) {
         let settings = match SettingsService::get(app) {
             Ok(s) => s,
             Err(_) => return,
@@ -279,12 +550,20 @@ impl NotificationService {
         Self::send_notification(
             app,
             "Session Expiring",
-            "Your Claude session may be expiring soon. Please refresh your credentials.",
+            &format!(
+                "Your {} session may be expiring soon. Please refresh your credentials.",
+                provider_name
+            ),
         );
     }
 
-    /// Check if currently in Do Not Disturb time window
-    fn is_dnd_active(settings: &NotificationSettings) -> bool {
+    /// Whether desktop notifications may be shown at `now`
+    fn toasts_allowed(settings: &NotificationSettings, now: NaiveTime) -> bool {
+        settings.enabled && !Self::is_dnd_active(settings, now)
+    }
+
+    /// Check if `now` is in the Do Not Disturb time window
+    fn is_dnd_active(settings: &NotificationSettings, now: NaiveTime) -> bool {
         if !settings.dnd_enabled {
             return false;
         }
@@ -304,8 +583,6 @@ impl NotificationService {
             Err(_) => return false,
         };
 
-        let now = Local::now().time();
-
         // Handle overnight DND (e.g., 22:00 to 08:00)
         if start > end {
             // DND spans midnight: active if now >= start OR now < end
@@ -320,7 +597,7 @@ impl NotificationService {
     fn send_notification(app: &AppHandle, title: &str, body: &str) -> bool {
         // Check DND before sending
         if let Ok(settings) = SettingsService::get(app) {
-            if Self::is_dnd_active(&settings.notifications) {
+            if Self::is_dnd_active(&settings.notifications, Local::now().time()) {
                 log::debug!(
                     "Notification suppressed (DND active): {} - {}",
                     title,
@@ -348,3 +625,162 @@ impl NotificationService {
         }
     }
 }
+
+/// Common webhook payload fields for an event about a single limit
+fn limit_event_data(account_id: &str, account_name: &str, limit: &UsageLimit) -> serde_json::Value {
+    serde_json::json!({
+        "accountId": account_id,
+        "accountName": account_name,
+        "limitId": limit.id,
+        "label": limit.label,
+        "utilization": limit.utilization,
+        "resetsAt": limit.resets_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn forecast(utilization: f64, sample_count: usize, projected: Option<f64>) -> LimitForecast {
+        let resets_at = Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap();
+        LimitForecast {
+            limit_id: "five_hour".to_string(),
+            utilization,
+            resets_at,
+            sample_count,
+            burn_rate_per_hour: Some(20.0),
+            burn_rate_low: None,
+            burn_rate_high: None,
+            hits_limit_at: Some(resets_at - Duration::hours(1)),
+            hits_limit_earliest: None,
+            hits_limit_latest: None,
+            projected_at_reset: projected,
+            hits_before_reset: projected.is_some_and(|p| p > 100.0),
+        }
+    }
+
+    fn usage(utilization: f64) -> UsageData {
+        UsageData {
+            provider: "claude".to_string(),
+            account_id: "acc-1".to_string(),
+            account_name: "Personal".to_string(),
+            timestamp: Utc::now(),
+            limits: vec![UsageLimit {
+                id: "five_hour".to_string(),
+                label: "5-Hour".to_string(),
+                utilization,
+                resets_at: Utc::now() + Duration::hours(3),
+                category: None,
+            }],
+            extra_usage: None,
+            raw: None,
+        }
+    }
+
+    #[test]
+    fn threshold_crossing_reaches_webhooks_during_dnd() {
+        let mut settings = AppSettings::default();
+        settings.notifications.dnd_enabled = true;
+        settings.notifications.dnd_start_time = Some("22:00".to_string());
+        settings.notifications.dnd_end_time = Some("08:00".to_string());
+        let night = NaiveTime::from_hms_opt(23, 30, 0).unwrap();
+        assert!(!NotificationService::toasts_allowed(&settings.notifications, night));
+
+        let state = NotificationState::new();
+        let events =
+            NotificationService::usage_events(&state, &settings, &usage(80.0), None, &[]);
+        let thresholds: Vec<_> = events
+            .iter()
+            .filter(|e| e.event == WebhookEventType::ThresholdCrossed)
+            .map(|e| e.data["threshold"].as_u64().unwrap())
+            .collect();
+        assert_eq!(thresholds, vec![50, 75]);
+
+        // Delivered once: the next update with the same usage doesn't repeat it
+        let events =
+            NotificationService::usage_events(&state, &settings, &usage(80.0), None, &[]);
+        assert!(events.iter().all(|e| !e.webhook));
+    }
+
+    #[test]
+    fn toast_held_back_during_dnd_shows_later() {
+        let state = NotificationState::new();
+        let settings = AppSettings::default();
+
+        // Crossed while toasts weren't allowed: webhooks got it, the toast is pending
+        let events =
+            NotificationService::usage_events(&state, &settings, &usage(60.0), None, &[]);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].webhook && events[0].toast);
+
+        // Once DND is over the next update still offers the toast, but no webhook
+        let events =
+            NotificationService::usage_events(&state, &settings, &usage(60.0), None, &[]);
+        assert_eq!(events.len(), 1);
+        assert!(!events[0].webhook && events[0].toast);
+        NotificationService::mark_toast_shown(&state, "acc-1", &events[0]);
+
+        let events =
+            NotificationService::usage_events(&state, &settings, &usage(60.0), None, &[]);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn events_fire_with_desktop_notifications_off() {
+        let mut settings = AppSettings::default();
+        settings.notifications.enabled = false;
+        settings.notifications.notify_on_reset = false;
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        assert!(!NotificationService::toasts_allowed(&settings.notifications, noon));
+
+        let state = NotificationState::new();
+        let events = NotificationService::usage_events(
+            &state,
+            &settings,
+            &usage(10.0),
+            Some(&usage(95.0)),
+            &[],
+        );
+        let reset = events
+            .iter()
+            .find(|e| e.event == WebhookEventType::UsageReset)
+            .unwrap();
+        assert!(!reset.toast);
+        assert_eq!(reset.data["previousUtilization"], 95.0);
+    }
+
+    #[test]
+    fn projected_overrun_requires_enough_samples_and_headroom() {
+        assert!(NotificationService::is_projected_overrun(&forecast(60.0, 5, Some(120.0))));
+        // Too few samples to trust the slope
+        assert!(!NotificationService::is_projected_overrun(&forecast(60.0, 2, Some(120.0))));
+        // Projection stays under the limit
+        assert!(!NotificationService::is_projected_overrun(&forecast(60.0, 5, Some(95.0))));
+        // Already exhausted - the threshold alert covers it
+        assert!(!NotificationService::is_projected_overrun(&forecast(100.0, 5, Some(140.0))));
+    }
+
+    #[test]
+    fn overrun_warning_is_deduplicated_per_window() {
+        let state = NotificationState::new();
+        let resets_at = Utc.with_ymd_and_hms(2025, 1, 15, 15, 0, 0).unwrap();
+
+        let was_sent = |account_id: &str, limit_id: &str, resets_at| {
+            state.was_overrun_warning_sent(Channel::Webhook, account_id, limit_id, resets_at)
+        };
+
+        assert!(!was_sent("acc-1", "five_hour", resets_at));
+        state.mark_overrun_warning_sent(Channel::Webhook, "acc-1", "five_hour", resets_at);
+
+        // Same window, even if the reported reset time drifts slightly
+        assert!(was_sent("acc-1", "five_hour", resets_at + Duration::seconds(30)));
+        // Other accounts, limits and channels are independent
+        assert!(!was_sent("acc-2", "five_hour", resets_at));
+        assert!(!was_sent("acc-1", "seven_day", resets_at));
+        assert!(!state.was_overrun_warning_sent(Channel::Toast, "acc-1", "five_hour", resets_at));
+        // The next window warns again
+        assert!(!was_sent("acc-1", "five_hour", resets_at + Duration::hours(5)));
+    }
+}
@@ -1,46 +1,66 @@
 use crate::error::{AppError, ProviderError};
-use crate::models::{Account, UsageData};
-use crate::providers::{ClaudeProvider, UsageProvider};
+use crate::models::{Account, ProviderId, UsageData, WebhookEventType};
+use crate::providers::rate_limit::HostBudget;
+use crate::providers::retry::{self, RetryPolicy};
+use crate::providers::{ProviderRegistry, UsageProvider};
 use crate::services::{
-    CredentialService, HistoryService, NotificationService, NotificationState, SettingsService,
+    CircuitBreaker, CircuitState, CircuitStateEvent, CredentialService, ErrorClass, HistoryService,
+    NotificationService, NotificationState, SchemaDriftEvent, SchemaService, SessionImportService,
+    SettingsService, VaultService, WebhookService,
 };
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
-use tokio::sync::Mutex as AsyncMutex;
+use tokio::sync::{broadcast, Mutex as AsyncMutex};
 use tokio::time::sleep;
 
-/// Minimum time between requests (rate limit protection)
+/// Shortest allowed refresh interval
 const MIN_REFRESH_INTERVAL_SECS: u64 = 10;
 
+/// Accounts fetched at the same time
+const MAX_CONCURRENT_FETCHES: usize = 4;
+
+/// Longest a single provider request may take before it counts as a network error
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// If we detect a gap larger than this, assume system was sleeping
 const SLEEP_DETECTION_THRESHOLD_SECS: u64 = 30;
 
+/// Events buffered per API event stream subscriber before it starts lagging
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
 /// Scheduler state shared across the app
 pub struct SchedulerState {
     /// Whether the scheduler is currently running
     running: AtomicBool,
-    /// Whether the scheduler is paused due to session issues (per account)
-    paused_accounts: AsyncMutex<HashMap<String, bool>>,
-    /// Count of consecutive session errors per account
-    session_error_counts: AsyncMutex<HashMap<String, u64>>,
+    /// Circuit breaker per account (accounts without one are closed)
+    circuits: AsyncMutex<HashMap<String, CircuitBreaker>>,
     /// Last fetch timestamp (unix millis)
     last_fetch: AtomicU64,
     /// Current interval in seconds
     interval_secs: AtomicU64,
-    /// Lock for fetch operations to prevent concurrent requests
+    /// Lock for fetch cycles to prevent overlapping refreshes
     fetch_lock: AsyncMutex<()>,
+    /// Request budget per provider host
+    host_budget: HostBudget,
     /// Previous usage data for detecting resets (per account)
     previous_usage: AsyncMutex<HashMap<String, UsageData>>,
     /// Notification state for tracking sent notifications (account-aware)
     notification_state: NotificationState,
+    /// Scheduler events for API stream subscribers
+    events: broadcast::Sender<SchedulerEvent>,
+    /// Fetch results per (provider, outcome), where outcome is "success" or a `ProviderError` kind
+    fetch_counts: std::sync::Mutex<HashMap<(String, String), u64>>,
+    /// Requests per provider, counting every retry
+    fetch_attempts: std::sync::Mutex<HashMap<String, u64>>,
+    /// Earliest time a rate-limited account may be fetched again (from `Retry-After`)
+    not_before: AsyncMutex<HashMap<String, DateTime<Utc>>>,
 }
 
-/// Maximum consecutive session errors before pausing
-const MAX_SESSION_ERRORS: u64 = 3;
-
 /// Event payload for session status
 #[derive(Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -55,13 +75,17 @@ impl Default for SchedulerState {
     fn default() -> Self {
         Self {
             running: AtomicBool::new(false),
-            paused_accounts: AsyncMutex::new(HashMap::new()),
-            session_error_counts: AsyncMutex::new(HashMap::new()),
+            circuits: AsyncMutex::new(HashMap::new()),
             last_fetch: AtomicU64::new(0),
             interval_secs: AtomicU64::new(300), // Default 5 minutes
             fetch_lock: AsyncMutex::new(()),
+            host_budget: HostBudget::default(),
             previous_usage: AsyncMutex::new(HashMap::new()),
             notification_state: NotificationState::new(),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            fetch_counts: std::sync::Mutex::new(HashMap::new()),
+            fetch_attempts: std::sync::Mutex::new(HashMap::new()),
+            not_before: AsyncMutex::new(HashMap::new()),
         }
     }
 }
@@ -79,50 +103,65 @@ impl SchedulerState {
         self.running.store(running, Ordering::SeqCst);
     }
 
-    /// Check if an account is paused
-    pub async fn is_account_paused(&self, account_id: &str) -> bool {
-        let paused = self.paused_accounts.lock().await;
-        paused.get(account_id).copied().unwrap_or(false)
+    /// Check if any account's circuit is open or half-open
+    pub async fn any_account_paused(&self) -> bool {
+        let circuits = self.circuits.lock().await;
+        circuits.values().any(|c| c.state != CircuitState::Closed)
     }
 
-    /// Set pause status for an account
-    pub async fn set_account_paused(&self, account_id: &str, paused: bool) {
-        let mut map = self.paused_accounts.lock().await;
-        map.insert(account_id.to_string(), paused);
+    /// Get consecutive session errors for an account
+    pub async fn get_account_error_count(&self, account_id: &str) -> u64 {
+        let circuit = self.get_circuit(account_id).await;
+        if circuit.error_class == Some(ErrorClass::Session) {
+            u64::from(circuit.consecutive_failures)
+        } else {
+            0
+        }
     }
 
-    /// Check if any account is paused
-    pub async fn any_account_paused(&self) -> bool {
-        let paused = self.paused_accounts.lock().await;
-        paused.values().any(|&p| p)
+    /// Get an account's circuit breaker
+    pub async fn get_circuit(&self, account_id: &str) -> CircuitBreaker {
+        let circuits = self.circuits.lock().await;
+        circuits.get(account_id).cloned().unwrap_or_default()
     }
 
-    /// Get session error count for an account
-    pub async fn get_account_error_count(&self, account_id: &str) -> u64 {
-        let counts = self.session_error_counts.lock().await;
-        counts.get(account_id).copied().unwrap_or(0)
+    /// Get the circuit breaker of every account that has one
+    pub async fn all_circuits(&self) -> HashMap<String, CircuitBreaker> {
+        self.circuits.lock().await.clone()
     }
 
-    /// Increment and return session error count for an account
-    pub async fn increment_account_error_count(&self, account_id: &str) -> u64 {
-        let mut counts = self.session_error_counts.lock().await;
-        let count = counts.entry(account_id.to_string()).or_insert(0);
-        *count += 1;
-        *count
+    /// Apply `f` to an account's circuit breaker, returning its result and
+    /// the state transition it caused (if any)
+    pub async fn update_circuit<R>(
+        &self,
+        account_id: &str,
+        f: impl FnOnce(&mut CircuitBreaker) -> R,
+    ) -> (R, Option<CircuitStateEvent>) {
+        let mut circuits = self.circuits.lock().await;
+        let circuit = circuits.entry(account_id.to_string()).or_default();
+        let previous = circuit.state;
+        let result = f(circuit);
+        let event = (circuit.state != previous).then(|| CircuitStateEvent {
+            account_id: account_id.to_string(),
+            previous,
+            circuit: circuit.clone(),
+        });
+        (result, event)
     }
 
-    /// Reset session error count for an account
-    pub async fn reset_account_error_count(&self, account_id: &str) {
-        let mut counts = self.session_error_counts.lock().await;
-        counts.insert(account_id.to_string(), 0);
+    /// Let every open circuit send its probe on the next fetch
+    pub async fn probe_open_circuits(&self) {
+        let now = Utc::now();
+        let mut circuits = self.circuits.lock().await;
+        for circuit in circuits.values_mut() {
+            circuit.probe_now(now);
+        }
     }
 
-    /// Clear all account paused states and error counts
+    /// Close every circuit
     pub async fn reset_all_account_states(&self) {
-        let mut paused = self.paused_accounts.lock().await;
-        paused.clear();
-        let mut counts = self.session_error_counts.lock().await;
-        counts.clear();
+        let mut circuits = self.circuits.lock().await;
+        circuits.clear();
     }
 
     pub fn get_interval(&self) -> u64 {
@@ -141,18 +180,9 @@ impl SchedulerState {
         self.last_fetch.store(millis, Ordering::SeqCst);
     }
 
-    /// Check if enough time has passed since last fetch (rate limiting)
-    pub fn can_fetch(&self) -> bool {
-        let last = self.get_last_fetch();
-        if last == 0 {
-            return true;
-        }
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
-        let elapsed_secs = (now - last) / 1000;
-        elapsed_secs >= MIN_REFRESH_INTERVAL_SECS
+    /// Check if a refresh of all accounts is in progress
+    pub fn is_fetching(&self) -> bool {
+        self.fetch_lock.try_lock().is_err()
     }
 
     /// Get previous usage for an account
@@ -161,11 +191,72 @@ impl SchedulerState {
         previous.get(account_id).cloned()
     }
 
+    /// Get the latest usage of every account fetched so far
+    pub async fn all_previous_usage(&self) -> Vec<UsageData> {
+        let previous = self.previous_usage.lock().await;
+        previous.values().cloned().collect()
+    }
+
     /// Set previous usage for an account
     pub async fn set_previous_usage(&self, account_id: &str, data: UsageData) {
         let mut previous = self.previous_usage.lock().await;
         previous.insert(account_id.to_string(), data);
     }
+
+    /// Count the outcome of a usage fetch
+    pub fn record_fetch(&self, provider: &str, outcome: &str) {
+        let mut counts = self.fetch_counts.lock().unwrap();
+        *counts
+            .entry((provider.to_string(), outcome.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    /// Fetch counts per (provider, outcome) since the app started
+    pub fn fetch_counts(&self) -> HashMap<(String, String), u64> {
+        self.fetch_counts.lock().unwrap().clone()
+    }
+
+    /// Count the requests made for one fetch (1 + retries)
+    pub fn record_attempts(&self, provider: &str, attempts: u32) {
+        let mut counts = self.fetch_attempts.lock().unwrap();
+        *counts.entry(provider.to_string()).or_insert(0) += u64::from(attempts);
+    }
+
+    /// Requests per provider since the app started
+    pub fn fetch_attempts(&self) -> HashMap<String, u64> {
+        self.fetch_attempts.lock().unwrap().clone()
+    }
+
+    /// When a rate-limited account may be fetched again, if it is still held back
+    pub async fn get_not_before(&self, account_id: &str) -> Option<DateTime<Utc>> {
+        let not_before = self.not_before.lock().await;
+        not_before
+            .get(account_id)
+            .copied()
+            .filter(|at| *at > Utc::now())
+    }
+
+    /// Hold an account back until `at`
+    pub async fn set_not_before(&self, account_id: &str, at: DateTime<Utc>) {
+        let mut not_before = self.not_before.lock().await;
+        not_before.insert(account_id.to_string(), at);
+    }
+
+    /// Let an account be fetched again right away
+    pub async fn clear_not_before(&self, account_id: &str) {
+        let mut not_before = self.not_before.lock().await;
+        not_before.remove(account_id);
+    }
+
+    /// Receive every scheduler event emitted from now on
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SchedulerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Send an event to stream subscribers (if any)
+    fn publish(&self, event: SchedulerEvent) {
+        let _ = self.events.send(event);
+    }
 }
 
 /// Event payload for usage updates
@@ -187,6 +278,30 @@ pub struct SchedulerStatusEvent {
     pub next_refresh_secs: Option<u64>,
 }
 
+/// Any event the scheduler emits, serialized as its inner payload
+#[derive(Clone, serde::Serialize)]
+#[serde(untagged)]
+pub enum SchedulerEvent {
+    UsageUpdate(Box<UsageUpdateEvent>),
+    SessionStatus(SessionStatusEvent),
+    SchedulerStatus(SchedulerStatusEvent),
+    SchemaDrift(SchemaDriftEvent),
+    CircuitState(CircuitStateEvent),
+}
+
+impl SchedulerEvent {
+    /// Event name used by the frontend and the API stream
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UsageUpdate(_) => "usage-update",
+            Self::SessionStatus(_) => "session-status",
+            Self::SchedulerStatus(_) => "scheduler-status",
+            Self::SchemaDrift(_) => "schema-drift",
+            Self::CircuitState(_) => "circuit-state",
+        }
+    }
+}
+
 pub struct SchedulerService;
 
 impl SchedulerService {
@@ -213,13 +328,14 @@ impl SchedulerService {
         });
 
         // Emit status update
-        let _ = app.emit(
-            "scheduler-status",
-            SchedulerStatusEvent {
+        Self::emit(
+            &app,
+            &state,
+            SchedulerEvent::SchedulerStatus(SchedulerStatusEvent {
                 running: true,
                 interval_secs: state.get_interval(),
                 next_refresh_secs: Some(state.get_interval()),
-            },
+            }),
         );
     }
 
@@ -234,13 +350,14 @@ impl SchedulerService {
         log::info!("Stopping background refresh scheduler");
 
         // Emit status update
-        let _ = app.emit(
-            "scheduler-status",
-            SchedulerStatusEvent {
+        Self::emit(
+            app,
+            state,
+            SchedulerEvent::SchedulerStatus(SchedulerStatusEvent {
                 running: false,
                 interval_secs: state.get_interval(),
                 next_refresh_secs: None,
-            },
+            }),
         );
     }
 
@@ -251,9 +368,10 @@ impl SchedulerService {
         log::info!("Updated refresh interval to {} seconds", interval);
 
         // Emit status update
-        let _ = app.emit(
-            "scheduler-status",
-            SchedulerStatusEvent {
+        Self::emit(
+            app,
+            state,
+            SchedulerEvent::SchedulerStatus(SchedulerStatusEvent {
                 running: state.is_running(),
                 interval_secs: interval,
                 next_refresh_secs: if state.is_running() {
@@ -261,18 +379,21 @@ impl SchedulerService {
                 } else {
                     None
                 },
-            },
+            }),
         );
     }
 
-    /// Force an immediate refresh (respects rate limiting)
+    /// Force an immediate refresh (requests still respect each host's budget)
     pub async fn force_refresh(app: &AppHandle, state: &SchedulerState) -> Result<(), AppError> {
-        if !state.can_fetch() {
-            log::warn!("Rate limited: too soon since last fetch");
+        if state.is_fetching() {
+            log::warn!("Refresh requested while one is in progress");
             return Err(AppError::RateLimit(
-                "Please wait before refreshing again".to_string(),
+                "A refresh is already in progress".to_string(),
             ));
         }
+        if VaultService::is_locked(app) {
+            return Err(AppError::VaultLocked);
+        }
 
         Self::fetch_and_emit(app, state).await;
         Ok(())
@@ -294,7 +415,8 @@ impl SchedulerService {
                     "Detected system wake ({}s gap), refreshing immediately",
                     tick_elapsed
                 );
-                // System just woke up - refresh immediately (even if paused, to check if session is valid again)
+                // System just woke up - refresh immediately, probing open circuits to check if sessions are valid again
+                state.probe_open_circuits().await;
                 Self::fetch_all_accounts(&app, &state).await;
                 last_check = Instant::now();
 
@@ -317,6 +439,12 @@ impl SchedulerService {
 
     /// Fetch usage for all accounts and emit events
     async fn fetch_all_accounts(app: &AppHandle, state: &SchedulerState) {
+        // Credentials can't be read until the passphrase is entered
+        if VaultService::is_locked(app) {
+            log::debug!("Credential vault is locked, skipping fetch");
+            return;
+        }
+
         // Try to acquire the fetch lock (non-blocking)
         let _lock = match state.fetch_lock.try_lock() {
             Ok(lock) => lock,
@@ -332,12 +460,6 @@ impl SchedulerService {
         // Re-acquire the lock for the actual fetch
         let _lock = state.fetch_lock.lock().await;
 
-        // Check rate limit
-        if !state.can_fetch() {
-            log::debug!("Rate limited, skipping fetch");
-            return;
-        }
-
         // Update last fetch time
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -345,15 +467,31 @@ impl SchedulerService {
             .as_millis() as u64;
         state.set_last_fetch(now);
 
-        // Get all Claude accounts
-        let accounts = match CredentialService::list_accounts(app, "claude") {
-            Ok(accounts) => accounts,
+        let registry = match ProviderRegistry::for_app(app) {
+            Ok(registry) => registry,
             Err(e) => {
-                log::error!("Failed to list accounts: {}", e);
+                log::error!("Failed to create provider registry: {}", e);
                 return;
             }
         };
 
+        // Get every account of every registered provider
+        let mut accounts = Vec::new();
+        for provider_id in registry.available_ids() {
+            match CredentialService::list_accounts(app, provider_id) {
+                Ok(provider_accounts) => accounts.extend(provider_accounts),
+                Err(e) => log::error!("Failed to list {} accounts: {}", provider_id, e),
+            }
+        }
+
+        // Fetching with unreadable credentials would only fail; wait for re-entry
+        accounts.retain(|account| {
+            if account.needs_reentry.is_some() {
+                log::debug!("Skipping {}: credentials need re-entry", account.name);
+            }
+            account.needs_reentry.is_none()
+        });
+
         if accounts.is_empty() {
             log::debug!("No accounts configured, skipping fetch");
             return;
@@ -361,37 +499,162 @@ impl SchedulerService {
 
         log::info!("Scheduler fetching usage for {} account(s)", accounts.len());
 
-        // Track max utilization across all accounts for adaptive refresh
-        let mut max_utilization_overall: f64 = 0.0;
+        // Fetch accounts in parallel; each host's budget keeps requests polite
+        let max_utilization_overall = stream::iter(accounts)
+            .map(|account| Self::fetch_scheduled_account(app, state, &registry, account))
+            .buffer_unordered(MAX_CONCURRENT_FETCHES)
+            .fold(
+                0.0_f64,
+                |max, utilization| async move { max.max(utilization) },
+            )
+            .await;
 
-        // Fetch for each account sequentially (to respect rate limits)
-        for account in accounts {
-            // Skip paused accounts
-            if state.is_account_paused(&account.id).await {
-                log::debug!("Skipping paused account: {}", account.name);
-                continue;
-            }
-
-            let result = Self::fetch_account_usage(&account).await;
-            Self::process_account_result(app, state, &account, result, &mut max_utilization_overall).await;
-        }
+        // Diff once per cycle so every account's response adds to one shape
+        Self::check_schema_drift(app, state);
 
         // Adaptive refresh based on max utilization across all accounts
         Self::maybe_adjust_interval_from_utilization(app, state, max_utilization_overall);
     }
 
-    /// Fetch usage for a single account
-    async fn fetch_account_usage(account: &Account) -> Result<UsageData, AppError> {
-        let claude = ClaudeProvider::new()?;
+    /// Fetch and process one account of a scheduled refresh, returning its
+    /// highest utilization (0 if it was skipped or failed)
+    async fn fetch_scheduled_account(
+        app: &AppHandle,
+        state: &SchedulerState,
+        registry: &ProviderRegistry,
+        account: Account,
+    ) -> f64 {
+        // Skip accounts the server asked us to leave alone for now
+        if let Some(at) = state.get_not_before(&account.id).await {
+            log::debug!(
+                "Skipping rate-limited account {} until {}",
+                account.name,
+                at
+            );
+            return 0.0;
+        }
+
+        // Skip accounts with an open circuit, unless their probe is due
+        let now = Utc::now();
+        let (allowed, transition) = state
+            .update_circuit(&account.id, |circuit| circuit.allow(now))
+            .await;
+        Self::emit_circuit_transition(app, state, &account, transition);
+        if !allowed {
+            log::debug!("Skipping account {} (circuit open)", account.name);
+            return 0.0;
+        }
+
+        // Accounts are only listed for registered providers
+        let Some(provider) = registry.get(&account.provider) else {
+            return 0.0;
+        };
+
+        let mut max_utilization = 0.0;
+        let result = Self::fetch_account_usage(state, provider.as_ref(), &account).await;
+        Self::process_account_result(
+            app,
+            state,
+            provider.as_ref(),
+            &account,
+            result,
+            &mut max_utilization,
+        )
+        .await;
+        max_utilization
+    }
+
+    /// Fetch one account now and process it like a scheduled fetch
+    /// (history, notifications, events). Errors are returned to the caller
+    /// without counting towards the account's session errors.
+    pub async fn refresh_account(
+        app: &AppHandle,
+        state: &SchedulerState,
+        account_id: &str,
+    ) -> Result<UsageData, AppError> {
+        let account = CredentialService::get_account(app, account_id)?.ok_or_else(|| {
+            AppError::InvalidInput(format!("Account not found: {}", account_id))
+        })?;
+        if account.needs_reentry.is_some() {
+            return Err(AppError::NeedsReentry(account.name));
+        }
+
+        let registry = ProviderRegistry::for_app(app)?;
+        let provider = registry.get(&account.provider).ok_or_else(|| {
+            AppError::InvalidInput(format!("Provider not available: {}", account.provider))
+        })?;
 
-        if !claude.validate_credentials(&account.credentials) {
-            return Err(ProviderError::InvalidCredentials(
-                format!("Missing org_id or session_key for account {}", account.name),
-            )
+        if let Some(at) = state.get_not_before(&account.id).await {
+            return Err(AppError::RateLimit(format!(
+                "{} is rate limited until {}",
+                account.name, at
+            )));
+        }
+
+        let data = Self::fetch_account_usage(state, provider.as_ref(), &account).await?;
+        let mut max_utilization = 0.0;
+        Self::process_account_result(
+            app,
+            state,
+            provider.as_ref(),
+            &account,
+            Ok(data.clone()),
+            &mut max_utilization,
+        )
+        .await;
+
+        Ok(data)
+    }
+
+    /// Fetch usage for a single account from its provider
+    ///
+    /// Every request waits for its host's budget and times out after
+    /// `FETCH_TIMEOUT`. Transient failures (including timeouts) are retried;
+    /// a `Retry-After` on 429 holds the account back until then.
+    async fn fetch_account_usage(
+        state: &SchedulerState,
+        provider: &dyn UsageProvider,
+        account: &Account,
+    ) -> Result<UsageData, AppError> {
+        if !provider.validate_credentials(&account.credentials) {
+            return Err(ProviderError::InvalidCredentials(format!(
+                "Missing {} credentials for account {}",
+                provider.name(),
+                account.name
+            ))
             .into());
         }
 
-        let mut usage = claude.fetch_usage(&account.credentials).await?;
+        let host = provider.host();
+        let host = host.as_deref();
+        let retried = retry::retry(&RetryPolicy::default(), move || async move {
+            if let Some(host) = host {
+                state.host_budget.acquire(host).await;
+            }
+            tokio::time::timeout(FETCH_TIMEOUT, provider.fetch_account_usage(account))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(ProviderError::Network(format!(
+                        "Request timed out after {}s",
+                        FETCH_TIMEOUT.as_secs()
+                    )))
+                })
+        })
+        .await;
+        state.record_attempts(provider.id(), retried.attempts);
+
+        match &retried.result {
+            Ok(_) => state.clear_not_before(&account.id).await,
+            Err(ProviderError::RateLimited(Some(wait))) => {
+                let at = Utc::now()
+                    + chrono::Duration::from_std(*wait).unwrap_or_else(|_| chrono::Duration::zero());
+                log::warn!("Account {} is rate limited until {}", account.name, at);
+                state.set_not_before(&account.id, at).await;
+            }
+            Err(_) => {}
+        }
+
+        let mut usage = retried.result?;
 
         // Set account info on the usage data
         usage.account_id = account.id.clone();
@@ -404,24 +667,34 @@ impl SchedulerService {
     async fn process_account_result(
         app: &AppHandle,
         state: &SchedulerState,
+        provider: &dyn UsageProvider,
         account: &Account,
         result: Result<UsageData, AppError>,
         max_utilization: &mut f64,
     ) {
+        state.record_fetch(provider.id(), fetch_outcome(&result));
+
         let event = match result {
             Ok(data) => {
-                // Session is valid - reset error count and unpause if needed
-                let error_count = state.get_account_error_count(&account.id).await;
-                let was_paused = state.is_account_paused(&account.id).await;
-
-                if error_count > 0 || was_paused {
+                // Session is valid - close the circuit
+                let now = Utc::now();
+                let (was_failing, transition) = state
+                    .update_circuit(&account.id, |circuit| {
+                        let was_failing = circuit.state != CircuitState::Closed
+                            || circuit.error_class == Some(ErrorClass::Session);
+                        circuit.record_success(now);
+                        was_failing
+                    })
+                    .await;
+                Self::emit_circuit_transition(app, state, account, transition);
+
+                if was_failing {
                     log::info!("Session restored for account {}, resuming", account.name);
-                    state.reset_account_error_count(&account.id).await;
-                    state.set_account_paused(&account.id, false).await;
 
                     // Emit session status to frontend
-                    let _ = app.emit(
-                        "session-status",
+                    Self::emit_session_status(
+                        app,
+                        state,
                         SessionStatusEvent {
                             account_id: account.id.clone(),
                             valid: true,
@@ -468,7 +741,7 @@ impl SchedulerService {
                 state.set_previous_usage(&account.id, data.clone()).await;
 
                 UsageUpdateEvent {
-                    provider: "claude".to_string(),
+                    provider: provider.id().to_string(),
                     account_id: account.id.clone(),
                     data: Some(data),
                     error: None,
@@ -477,60 +750,55 @@ impl SchedulerService {
             Err(e) => {
                 log::error!("Failed to fetch usage for account {}: {}", account.name, e);
 
-                // Check if this is a session expiry error
                 let error_str = e.to_string();
-                let is_session_error = error_str.contains("expired")
-                    || error_str.contains("401")
-                    || error_str.contains("SessionExpired");
-
-                if is_session_error {
-                    NotificationService::send_session_expiry_warning(app);
-
-                    // Track consecutive session errors per account
-                    let error_count = state.increment_account_error_count(&account.id).await;
-                    log::warn!(
-                        "Session error {}/{} for account {} - {}",
-                        error_count,
-                        MAX_SESSION_ERRORS,
-                        account.name,
-                        error_str
-                    );
 
-                    // Pause this account after too many consecutive errors
-                    if error_count >= MAX_SESSION_ERRORS {
+                // Count the failure towards the account's circuit breaker
+                if let Some(class) = ErrorClass::of(&e) {
+                    let now = Utc::now();
+                    let (circuit, transition) = state
+                        .update_circuit(&account.id, |circuit| {
+                            circuit.record_failure(class, error_str.clone(), now);
+                            circuit.clone()
+                        })
+                        .await;
+
+                    if class == ErrorClass::Session {
+                        NotificationService::send_session_expiry_warning(app, provider.name());
                         log::warn!(
-                            "Too many session errors ({}) for account {}, pausing",
-                            error_count,
-                            account.name
+                            "Session error {}/{} for account {} - {}",
+                            circuit.consecutive_failures,
+                            class.policy().failure_threshold,
+                            account.name,
+                            error_str
                         );
-                        state.set_account_paused(&account.id, true).await;
 
-                        // Emit session status to frontend
-                        let _ = app.emit(
-                            "session-status",
-                            SessionStatusEvent {
-                                account_id: account.id.clone(),
-                                valid: false,
-                                error_count,
-                                paused: true,
-                            },
-                        );
-                    } else {
-                        // Emit session status (not paused yet)
-                        let _ = app.emit(
-                            "session-status",
+                        Self::emit_session_status(
+                            app,
+                            state,
                             SessionStatusEvent {
                                 account_id: account.id.clone(),
                                 valid: false,
-                                error_count,
-                                paused: false,
+                                error_count: u64::from(circuit.consecutive_failures),
+                                paused: circuit.state != CircuitState::Closed,
                             },
                         );
                     }
+
+                    // Pausing for an expired claude.ai session: try the browser's
+                    let paused = transition
+                        .as_ref()
+                        .is_some_and(|t| t.circuit.state == CircuitState::Open);
+                    Self::emit_circuit_transition(app, state, account, transition);
+                    if paused
+                        && matches!(e, AppError::Provider(ProviderError::SessionExpired))
+                        && account.provider == ProviderId::Claude.as_str()
+                    {
+                        Self::recover_session(app, state, account).await;
+                    }
                 }
 
                 UsageUpdateEvent {
-                    provider: "claude".to_string(),
+                    provider: provider.id().to_string(),
                     account_id: account.id.clone(),
                     data: None,
                     error: Some(error_str),
@@ -538,8 +806,88 @@ impl SchedulerService {
             }
         };
 
-        // Emit to frontend
-        let _ = app.emit("usage-update", event);
+        // Emit to frontend, API stream and webhooks
+        WebhookService::dispatch(app, WebhookEventType::UsageUpdate, &event);
+        Self::emit(app, state, SchedulerEvent::UsageUpdate(Box::new(event)));
+    }
+
+    /// Replace an account's expired session with one from a local browser
+    /// and probe it on the next fetch (only when the user opted in)
+    async fn recover_session(app: &AppHandle, state: &SchedulerState, account: &Account) {
+        let enabled = SettingsService::get(app)
+            .map(|s| s.auto_import_browser_session)
+            .unwrap_or(false);
+        if !enabled {
+            return;
+        }
+
+        match SessionImportService::recover(app, account).await {
+            Ok(Some(_)) => {
+                let now = Utc::now();
+                state
+                    .update_circuit(&account.id, |circuit| circuit.probe_now(now))
+                    .await;
+                log::info!(
+                    "Found a browser session for account {}, retrying on the next fetch",
+                    account.name
+                );
+            }
+            Ok(None) => log::info!("No browser session found for account {}", account.name),
+            Err(e) => log::warn!(
+                "Failed to import a browser session for account {}: {}",
+                account.name,
+                e
+            ),
+        }
+    }
+
+    /// Diff the response shapes seen since the last check against the known ones
+    ///
+    /// Runs after each scheduled fetch cycle; shapes from single-account
+    /// refreshes are held until then.
+    fn check_schema_drift(app: &AppHandle, state: &SchedulerState) {
+        match SchemaService::check(app) {
+            Ok(drifts) => {
+                for drift in drifts {
+                    Self::emit(app, state, SchedulerEvent::SchemaDrift(drift));
+                }
+            }
+            Err(e) => log::warn!("Failed to check response schemas: {}", e),
+        }
+    }
+
+    /// Log and emit a circuit breaker state change
+    fn emit_circuit_transition(
+        app: &AppHandle,
+        state: &SchedulerState,
+        account: &Account,
+        transition: Option<CircuitStateEvent>,
+    ) {
+        let Some(event) = transition else {
+            return;
+        };
+
+        log::info!(
+            "Circuit for account {} changed from {:?} to {:?} ({:?}, next probe at {:?})",
+            account.name,
+            event.previous,
+            event.circuit.state,
+            event.circuit.error_class,
+            event.circuit.next_probe_at
+        );
+        Self::emit(app, state, SchedulerEvent::CircuitState(event));
+    }
+
+    /// Emit session status to the frontend, API stream and webhooks
+    fn emit_session_status(app: &AppHandle, state: &SchedulerState, event: SessionStatusEvent) {
+        WebhookService::dispatch(app, WebhookEventType::SessionStatus, &event);
+        Self::emit(app, state, SchedulerEvent::SessionStatus(event));
+    }
+
+    /// Emit an event to the frontend and API stream subscribers
+    fn emit(app: &AppHandle, state: &SchedulerState, event: SchedulerEvent) {
+        let _ = app.emit(event.name(), &event);
+        state.publish(event);
     }
 
     /// Adjust interval based on max utilization (adaptive refresh)
@@ -576,13 +924,14 @@ impl SchedulerService {
             state.set_interval(new_interval);
 
             // Emit status update
-            let _ = app.emit(
-                "scheduler-status",
-                SchedulerStatusEvent {
+            Self::emit(
+                app,
+                state,
+                SchedulerEvent::SchedulerStatus(SchedulerStatusEvent {
                     running: state.is_running(),
                     interval_secs: new_interval,
                     next_refresh_secs: Some(new_interval),
-                },
+                }),
             );
         }
     }
@@ -592,3 +941,200 @@ impl SchedulerService {
         Self::fetch_all_accounts(app, state).await;
     }
 }
+
+/// Metrics label for a fetch result
+fn fetch_outcome(result: &Result<UsageData, AppError>) -> &'static str {
+    match result {
+        Ok(_) => "success",
+        Err(AppError::Provider(e)) => e.kind(),
+        Err(_) => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Credentials, UsageLimit};
+    use async_trait::async_trait;
+
+    /// Provider that returns one fixed limit when an API key is set
+    /// (the key "limited" gets a 429 with `Retry-After: 60`)
+    struct FakeProvider;
+
+    #[async_trait]
+    impl UsageProvider for FakeProvider {
+        fn id(&self) -> &str {
+            "acme"
+        }
+
+        fn name(&self) -> &str {
+            "Acme"
+        }
+
+        async fn fetch_usage(&self, credentials: &Credentials) -> Result<UsageData, ProviderError> {
+            if credentials.api_key.as_deref() == Some("limited") {
+                return Err(ProviderError::RateLimited(Some(Duration::from_secs(60))));
+            }
+            Ok(UsageData {
+                provider: self.id().to_string(),
+                account_id: String::new(),
+                account_name: String::new(),
+                timestamp: Utc::now(),
+                limits: vec![UsageLimit {
+                    id: "daily".to_string(),
+                    label: "Daily".to_string(),
+                    utilization: 12.0,
+                    resets_at: Utc::now(),
+                    category: None,
+                }],
+                extra_usage: None,
+                raw: None,
+            })
+        }
+
+        fn validate_credentials(&self, credentials: &Credentials) -> bool {
+            credentials.api_key.is_some()
+        }
+
+        fn host(&self) -> Option<String> {
+            Some("acme.test".to_string())
+        }
+    }
+
+    fn account(api_key: Option<&str>) -> Account {
+        Account::new(
+            "Team".to_string(),
+            "acme".to_string(),
+            Credentials {
+                api_key: api_key.map(String::from),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn fetch_uses_the_account_provider() {
+        let account = account(Some("key"));
+        let state = SchedulerState::new();
+        let usage = SchedulerService::fetch_account_usage(&state, &FakeProvider, &account)
+            .await
+            .unwrap();
+
+        assert_eq!(usage.provider, "acme");
+        assert_eq!(usage.account_id, account.id);
+        assert_eq!(usage.account_name, "Team");
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_invalid_credentials() {
+        let state = SchedulerState::new();
+        let result =
+            SchedulerService::fetch_account_usage(&state, &FakeProvider, &account(None)).await;
+        assert_eq!(fetch_outcome(&result), "invalid_credentials");
+    }
+
+    #[tokio::test]
+    async fn retry_after_holds_the_account_back() {
+        let state = SchedulerState::new();
+        let account = account(Some("limited"));
+
+        let result = SchedulerService::fetch_account_usage(&state, &FakeProvider, &account).await;
+
+        assert_eq!(fetch_outcome(&result), "rate_limited");
+        assert_eq!(state.fetch_attempts()["acme"], 1);
+        let not_before = state.get_not_before(&account.id).await.unwrap();
+        assert!(not_before > Utc::now() + chrono::Duration::seconds(50));
+
+        state.clear_not_before(&account.id).await;
+        assert!(state.get_not_before(&account.id).await.is_none());
+    }
+
+    #[test]
+    fn scheduler_event_serializes_as_payload() {
+        let event = SchedulerEvent::SchedulerStatus(SchedulerStatusEvent {
+            running: true,
+            interval_secs: 300,
+            next_refresh_secs: Some(300),
+        });
+
+        assert_eq!(event.name(), "scheduler-status");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "running": true, "intervalSecs": 300, "nextRefreshSecs": 300 })
+        );
+    }
+
+    #[tokio::test]
+    async fn published_events_reach_every_subscriber() {
+        let state = SchedulerState::new();
+        let mut first = state.subscribe_events();
+        let mut second = state.subscribe_events();
+
+        state.publish(SchedulerEvent::SessionStatus(SessionStatusEvent {
+            account_id: "acc-1".to_string(),
+            valid: false,
+            error_count: 1,
+            paused: false,
+        }));
+
+        assert_eq!(first.recv().await.unwrap().name(), "session-status");
+        assert_eq!(second.recv().await.unwrap().name(), "session-status");
+    }
+
+    #[tokio::test]
+    async fn circuit_transitions_are_reported() {
+        let state = SchedulerState::new();
+        let now = Utc::now();
+
+        let (_, transition) = state
+            .update_circuit("acc-1", |circuit| {
+                circuit.record_failure(ErrorClass::Blocked, "blocked".to_string(), now)
+            })
+            .await;
+        assert!(transition.is_none());
+
+        let (_, transition) = state
+            .update_circuit("acc-1", |circuit| {
+                circuit.record_failure(ErrorClass::Blocked, "blocked".to_string(), now)
+            })
+            .await;
+        let event = SchedulerEvent::CircuitState(transition.unwrap());
+        assert_eq!(event.name(), "circuit-state");
+
+        let payload = serde_json::to_value(&event).unwrap();
+        assert_eq!(payload["accountId"], "acc-1");
+        assert_eq!(payload["previous"], "closed");
+        assert_eq!(payload["state"], "open");
+        assert_eq!(payload["errorClass"], "blocked");
+
+        assert_eq!(state.get_circuit("acc-1").await.state, CircuitState::Open);
+        assert_eq!(state.get_account_error_count("acc-1").await, 0);
+
+        state.reset_all_account_states().await;
+        assert!(!state.any_account_paused().await);
+    }
+
+    #[test]
+    fn fetch_outcomes_are_counted_per_provider() {
+        let state = SchedulerState::new();
+        let expired: Result<UsageData, AppError> = Err(ProviderError::SessionExpired.into());
+
+        state.record_fetch("claude", "success");
+        state.record_fetch("claude", "success");
+        state.record_fetch("claude", fetch_outcome(&expired));
+
+        let counts = state.fetch_counts();
+        assert_eq!(counts[&("claude".to_string(), "success".to_string())], 2);
+        assert_eq!(counts[&("claude".to_string(), "session_expired".to_string())], 1);
+    }
+
+    #[test]
+    fn publishing_without_subscribers_is_harmless() {
+        let state = SchedulerState::new();
+        state.publish(SchedulerEvent::SchedulerStatus(SchedulerStatusEvent {
+            running: false,
+            interval_secs: 300,
+            next_refresh_secs: None,
+        }));
+    }
+}
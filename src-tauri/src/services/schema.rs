@@ -0,0 +1,240 @@
+use crate::error::AppError;
+use crate::models::SchemaRecord;
+use crate::providers::schema::{self, Observation, SchemaDiff};
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "schemas.json";
+const SCHEMAS_KEY: &str = "schemas";
+
+/// Serializes read-modify-write of the known shapes
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Event payload for a provider response that changed shape
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDriftEvent {
+    pub provider: String,
+    pub endpoint: String,
+    pub diff: SchemaDiff,
+    pub detected_at: DateTime<Utc>,
+}
+
+pub struct SchemaService;
+
+impl SchemaService {
+    /// Known response shape of every provider endpoint seen so far
+    pub fn list(app: &AppHandle) -> Result<Vec<SchemaRecord>, AppError> {
+        Self::load(app)
+    }
+
+    /// Compare responses observed since the last check with their known shapes
+    ///
+    /// Returns one event per endpoint whose shape drifted.
+    pub fn check(app: &AppHandle) -> Result<Vec<SchemaDriftEvent>, AppError> {
+        let observations = schema::take_observations();
+        if observations.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        let mut records = Self::load(app)?;
+        let drifts: Vec<SchemaDriftEvent> = observations
+            .into_iter()
+            .filter_map(|observation| apply(&mut records, observation, now))
+            .collect();
+
+        for drift in &drifts {
+            log::warn!(
+                "Response schema drift for {} {}: added {:?}, removed {:?}, changed {:?}",
+                drift.provider,
+                drift.endpoint,
+                drift.diff.added,
+                drift.diff.removed,
+                drift.diff.changed
+            );
+        }
+
+        Self::save(app, &records)?;
+        Ok(drifts)
+    }
+
+    fn load(app: &AppHandle) -> Result<Vec<SchemaRecord>, AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+        match store.get(SCHEMAS_KEY) {
+            Some(value) => Ok(serde_json::from_value(value.clone())?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn save(app: &AppHandle, records: &[SchemaRecord]) -> Result<(), AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+        store.set(SCHEMAS_KEY, serde_json::to_value(records)?);
+        store.save().map_err(|e| AppError::Store(e.to_string()))
+    }
+}
+
+/// Fold an observation into the known shapes, returning the drift if it changed shape
+///
+/// Without drift the observation is merged into the current shape, so fields
+/// that are usually null still get their concrete type once one shows up. On
+/// drift its types replace the current ones, but paths it lacks are kept.
+fn apply(
+    records: &mut Vec<SchemaRecord>,
+    observation: Observation,
+    now: DateTime<Utc>,
+) -> Option<SchemaDriftEvent> {
+    let Some(record) = records
+        .iter_mut()
+        .find(|r| r.provider == observation.provider && r.endpoint == observation.endpoint)
+    else {
+        records.push(SchemaRecord {
+            provider: observation.provider,
+            endpoint: observation.endpoint,
+            expected: observation.shape.clone(),
+            current: observation.shape,
+            diff: None,
+            last_seen: now,
+            drifted_at: None,
+        });
+        return None;
+    };
+
+    record.last_seen = now;
+    let diff = schema::diff(&record.current, &observation.shape);
+    if !diff.is_drift() {
+        schema::merge(&mut record.current, &observation.shape);
+        if record.diff.is_none() {
+            record.expected = record.current.clone();
+        }
+        return None;
+    }
+
+    record.expected = record.current.clone();
+    schema::overlay(&mut record.current, &observation.shape);
+    record.diff = Some(diff.clone());
+    record.drifted_at = Some(now);
+
+    Some(SchemaDriftEvent {
+        provider: record.provider.clone(),
+        endpoint: record.endpoint.clone(),
+        diff,
+        detected_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn observation(response: serde_json::Value) -> Observation {
+        Observation {
+            provider: "claude".to_string(),
+            endpoint: "usage".to_string(),
+            shape: schema::shape_of(&response),
+        }
+    }
+
+    #[test]
+    fn test_apply_records_first_shape_without_drift() {
+        let mut records = Vec::new();
+        let now = Utc::now();
+
+        let drift = apply(
+            &mut records,
+            observation(json!({ "five_hour": { "utilization": 10.0 } })),
+            now,
+        );
+
+        assert!(drift.is_none());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].expected, records[0].current);
+        assert_eq!(records[0].last_seen, now);
+    }
+
+    #[test]
+    fn test_apply_merges_null_fields_without_drift() {
+        let mut records = Vec::new();
+        let now = Utc::now();
+        apply(
+            &mut records,
+            observation(json!({ "seven_day_opus": null })),
+            now,
+        );
+
+        let drift = apply(
+            &mut records,
+            observation(json!({ "seven_day_opus": { "utilization": 2.0 } })),
+            now,
+        );
+
+        assert!(drift.is_none());
+        assert_eq!(records[0].current["seven_day_opus"], "object");
+        assert_eq!(records[0].expected, records[0].current);
+    }
+
+    #[test]
+    fn test_apply_reports_drift_and_keeps_expected_shape() {
+        let mut records = Vec::new();
+        let now = Utc::now();
+        apply(
+            &mut records,
+            observation(json!({ "five_hour": { "utilization": 10.0 } })),
+            now,
+        );
+
+        let drift = apply(
+            &mut records,
+            observation(json!({ "five_hour": { "utilization": "10%" } })),
+            now,
+        )
+        .expect("type change is drift");
+
+        assert_eq!(drift.diff.changed[0].path, "five_hour.utilization");
+        assert_eq!(records[0].expected["five_hour.utilization"], "number");
+        assert_eq!(records[0].current["five_hour.utilization"], "string");
+        assert_eq!(records[0].drifted_at, Some(now));
+
+        // The new shape is now the baseline
+        assert!(apply(
+            &mut records,
+            observation(json!({ "five_hour": { "utilization": "11%" } })),
+            now,
+        )
+        .is_none());
+        assert_eq!(records[0].expected["five_hour.utilization"], "number");
+    }
+
+    #[test]
+    fn test_apply_alternating_account_shapes_do_not_drift() {
+        let mut records = Vec::new();
+        let now = Utc::now();
+        // One account has an Opus limit, the other doesn't
+        let with_opus = json!({
+            "five_hour": { "utilization": 10.0 },
+            "seven_day_opus": { "utilization": 2.0 }
+        });
+        let without_opus = json!({ "five_hour": { "utilization": 30.0 } });
+
+        // A fetch cycle observes both accounts as one merged shape
+        let mut cycle = observation(with_opus.clone());
+        schema::merge(&mut cycle.shape, &schema::shape_of(&without_opus));
+        assert!(apply(&mut records, cycle, now).is_none());
+
+        // Later cycles may only reach one of them
+        for _ in 0..3 {
+            assert!(apply(&mut records, observation(without_opus.clone()), now).is_none());
+            assert!(apply(&mut records, observation(with_opus.clone()), now).is_none());
+        }
+        assert_eq!(records[0].current["seven_day_opus.utilization"], "number");
+        assert!(records[0].diff.is_none());
+    }
+}
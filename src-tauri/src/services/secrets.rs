@@ -0,0 +1,363 @@
+//! Storage for credential secrets
+//!
+//! Session keys and API keys live in the platform secret store (Secret
+//! Service/libsecret on Linux, Keychain on macOS, Credential Manager on
+//! Windows). Where there is none, e.g. a Linux session without a Secret
+//! Service daemon, they go to `secrets.json` in the app data directory,
+//! encrypted with a random key kept in `secrets.key` that only the user can
+//! read. `credentials.json` only keeps a reference naming the backend.
+
+use crate::error::AppError;
+use crate::services::crypto::{self, Key};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Service name of platform secret store entries
+const KEYRING_SERVICE: &str = "com.aipulse.app";
+
+/// Entry looked up to check that the platform secret store answers
+const KEYRING_PROBE: &str = "probe";
+
+const SECRETS_FILE: &str = "secrets.json";
+const KEY_FILE: &str = "secrets.key";
+
+/// Prefix of a credentials.json value that points at a stored secret
+const REFERENCE_PREFIX: &str = "secret:";
+
+/// Where a secret is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretBackend {
+    /// Platform secret store
+    Keyring,
+    /// Encrypted `secrets.json`
+    File,
+}
+
+impl SecretBackend {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Keyring => "keyring",
+            Self::File => "file",
+        }
+    }
+
+    /// Value kept in credentials.json for a secret stored in this backend
+    pub fn reference(self) -> String {
+        format!("{}{}", REFERENCE_PREFIX, self.name())
+    }
+
+    /// Backend a credentials.json value points at (`None` if it is not a reference)
+    pub fn from_reference(value: &str) -> Option<Self> {
+        match value.strip_prefix(REFERENCE_PREFIX)? {
+            "keyring" => Some(Self::Keyring),
+            "file" => Some(Self::File),
+            _ => None,
+        }
+    }
+}
+
+/// Secrets of all accounts, keyed by name (e.g. `<account id>/session_key`)
+pub struct SecretStore {
+    /// Whether the platform secret store answered when the store was opened
+    keyring: bool,
+    file: FileSecrets,
+}
+
+impl SecretStore {
+    /// Open the store, falling back to the encrypted file in `dir` if the
+    /// platform secret store is unavailable
+    pub fn open(dir: &Path) -> Self {
+        let keyring = keyring_available();
+        if keyring {
+            log::info!("Storing credentials in the platform secret store");
+        }
+        Self {
+            keyring,
+            file: FileSecrets::new(dir),
+        }
+    }
+
+    /// Open a store that only uses the encrypted file in `dir`
+    #[cfg(test)]
+    pub fn file_only(dir: &Path) -> Self {
+        Self {
+            keyring: false,
+            file: FileSecrets::new(dir),
+        }
+    }
+
+    /// Backend new secrets are written to
+    pub fn backend(&self) -> SecretBackend {
+        if self.keyring {
+            SecretBackend::Keyring
+        } else {
+            SecretBackend::File
+        }
+    }
+
+    /// Store a secret, returning the reference to keep in its place
+    pub fn put(&self, name: &str, value: &str) -> Result<String, AppError> {
+        let backend = self.backend();
+        match backend {
+            SecretBackend::Keyring => keyring_entry(name)?
+                .set_password(value)
+                .map_err(keyring_error)?,
+            SecretBackend::File => self.file.set(name, value)?,
+        }
+        Ok(backend.reference())
+    }
+
+    /// Read a secret (`None` if it was never stored or has been deleted)
+    pub fn get(&self, backend: SecretBackend, name: &str) -> Result<Option<String>, AppError> {
+        match backend {
+            SecretBackend::Keyring => match keyring_entry(name)?.get_password() {
+                Ok(value) => Ok(Some(value)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(e) => Err(keyring_error(e)),
+            },
+            SecretBackend::File => self.file.get(name),
+        }
+    }
+
+    /// Delete a secret (deleting a missing secret is not an error)
+    pub fn delete(&self, backend: SecretBackend, name: &str) -> Result<(), AppError> {
+        match backend {
+            SecretBackend::Keyring => match keyring_entry(name)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(keyring_error(e)),
+            },
+            SecretBackend::File => self.file.delete(name),
+        }
+    }
+}
+
+fn keyring_entry(name: &str) -> Result<keyring::Entry, AppError> {
+    keyring::Entry::new(KEYRING_SERVICE, name).map_err(keyring_error)
+}
+
+fn keyring_error(e: keyring::Error) -> AppError {
+    AppError::Store(format!("Secret store error: {}", e))
+}
+
+fn keyring_available() -> bool {
+    let probe = keyring_entry(KEYRING_PROBE).and_then(|entry| match entry.get_password() {
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(keyring_error(e)),
+    });
+    match probe {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("{}; using encrypted file instead", e);
+            false
+        }
+    }
+}
+
+/// Encrypted-file fallback: name -> AES-256-GCM ciphertext
+struct FileSecrets {
+    path: PathBuf,
+    key_path: PathBuf,
+    /// Serializes read-modify-write of the file
+    lock: Mutex<()>,
+}
+
+impl FileSecrets {
+    fn new(dir: &Path) -> Self {
+        Self {
+            path: dir.join(SECRETS_FILE),
+            key_path: dir.join(KEY_FILE),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, AppError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let entries = self.load()?;
+        let Some(encrypted) = entries.get(name) else {
+            return Ok(None);
+        };
+        let key = self.key()?;
+        Ok(Some(crypto::decrypt(&key, encrypted)?))
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), AppError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let key = self.key()?;
+        let encrypted = crypto::encrypt(&key, value)?;
+        let mut entries = self.load()?;
+        entries.insert(name.to_string(), encrypted);
+        self.save(&entries)
+    }
+
+    fn delete(&self, name: &str) -> Result<(), AppError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = self.load()?;
+        if entries.remove(name).is_some() {
+            self.save(&entries)?;
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Result<HashMap<String, String>, AppError> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(AppError::Store(format!(
+                "Failed to read {}: {}",
+                SECRETS_FILE, e
+            ))),
+        }
+    }
+
+    fn save(&self, entries: &HashMap<String, String>) -> Result<(), AppError> {
+        write_private(&self.path, &serde_json::to_vec_pretty(entries)?)
+    }
+
+    /// The file's key, created on first use
+    fn key(&self) -> Result<Key, AppError> {
+        match fs::read_to_string(&self.key_path) {
+            Ok(encoded) => {
+                let bytes = BASE64
+                    .decode(encoded.trim())
+                    .map_err(|e| AppError::Store(format!("Invalid {}: {}", KEY_FILE, e)))?;
+                bytes
+                    .try_into()
+                    .map_err(|_| AppError::Store(format!("Invalid {}: wrong length", KEY_FILE)))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = crypto::generate_key();
+                write_private(&self.key_path, BASE64.encode(key).as_bytes())?;
+                Ok(key)
+            }
+            Err(e) => Err(AppError::Store(format!(
+                "Failed to read {}: {}",
+                KEY_FILE, e
+            ))),
+        }
+    }
+}
+
+/// Write a file only the current user can read
+///
+/// The contents go to a temporary file next to `path` that is then renamed
+/// over it, so a crash mid-write leaves the old file intact and a file created
+/// with looser permissions is replaced rather than reused.
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = dir.join(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+
+    let write = || -> std::io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        AppError::Store(format!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("ai-pulse-secrets-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_reference_round_trip() {
+        for backend in [SecretBackend::Keyring, SecretBackend::File] {
+            assert_eq!(
+                SecretBackend::from_reference(&backend.reference()),
+                Some(backend)
+            );
+        }
+        assert_eq!(SecretBackend::from_reference("sk-ant-123"), None);
+        assert_eq!(SecretBackend::from_reference("secret:vault"), None);
+    }
+
+    #[test]
+    fn test_file_secrets_are_encrypted_at_rest() {
+        let dir = temp_dir();
+        let file = FileSecrets::new(&dir);
+
+        file.set("acc-1/session_key", "sk-ant-secret").unwrap();
+        assert_eq!(
+            file.get("acc-1/session_key").unwrap().as_deref(),
+            Some("sk-ant-secret")
+        );
+        assert_eq!(file.get("acc-2/session_key").unwrap(), None);
+
+        let on_disk = fs::read_to_string(dir.join(SECRETS_FILE)).unwrap();
+        assert!(!on_disk.contains("sk-ant-secret"));
+
+        file.delete("acc-1/session_key").unwrap();
+        assert_eq!(file.get("acc-1/session_key").unwrap(), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_secrets_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir();
+        FileSecrets::new(&dir).set("acc-1/api_key", "key").unwrap();
+
+        for file in [SECRETS_FILE, KEY_FILE] {
+            let mode = fs::metadata(dir.join(file)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600, "{}", file);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rewrite_replaces_a_readable_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir();
+        let path = dir.join(SECRETS_FILE);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, b"{\"a\":\"b\"}").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":\"b\"}");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // No temporary files left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_secrets_need_their_key() {
+        let dir = temp_dir();
+        let file = FileSecrets::new(&dir);
+        file.set("acc-1/api_key", "key").unwrap();
+
+        fs::remove_file(dir.join(KEY_FILE)).unwrap();
+        assert!(FileSecrets::new(&dir).get("acc-1/api_key").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+//! claude.ai sessions from local browsers
+//!
+//! Reads the `sessionKey` cookie from browser profiles and asks claude.ai
+//! which organization it belongs to, so an expired session can be replaced
+//! without copying anything out of DevTools.
+
+use crate::error::{AppError, ProviderError};
+use crate::models::{Account, ClaudeOrganization};
+use crate::providers::ClaudeProvider;
+use crate::services::browser_cookies::{self, BrowserCookie};
+use crate::services::CredentialService;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager};
+
+const CLAUDE_DOMAIN: &str = "claude.ai";
+const SESSION_COOKIE: &str = "sessionKey";
+
+/// Organizations that can use claude.ai (API-only ones can't)
+const CHAT_CAPABILITY: &str = "chat";
+
+/// A working claude.ai session found in a browser
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserSession {
+    pub browser: String,
+    pub profile: String,
+    pub session_key: String,
+    pub org_id: String,
+    pub org_name: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub struct SessionImportService;
+
+impl SessionImportService {
+    /// Find a working session in the local browsers
+    ///
+    /// With `org_id` set, only a session of that organization counts.
+    /// `stale` is a session key known not to work (e.g. the one being
+    /// replaced) and is skipped.
+    pub async fn find(
+        app: &AppHandle,
+        org_id: Option<&str>,
+        stale: Option<&str>,
+    ) -> Result<Option<BrowserSession>, AppError> {
+        let scratch = app.path().app_cache_dir()?;
+        let cookies = tokio::task::spawn_blocking(move || {
+            browser_cookies::find(&scratch, CLAUDE_DOMAIN, SESSION_COOKIE)
+        })
+        .await
+        .map_err(|e| AppError::Store(format!("Failed to read browser cookies: {}", e)))?;
+
+        let provider = ClaudeProvider::new()?;
+        Ok(Self::first_working(&provider, cookies, org_id, stale, Utc::now()).await?)
+    }
+
+    /// Replace the session of a claude.ai account with one from a browser
+    ///
+    /// Returns the updated account, or None when no browser has a working
+    /// session for its organization.
+    pub async fn recover(app: &AppHandle, account: &Account) -> Result<Option<Account>, AppError> {
+        let Some(session) = Self::find(
+            app,
+            account.credentials.org_id.as_deref(),
+            account.credentials.session_key.as_deref(),
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut account = account.clone();
+        account.credentials.session_key = Some(session.session_key);
+        account.credentials.org_id = Some(session.org_id);
+        CredentialService::save_account(app, &account)?;
+
+        log::info!(
+            "Imported a new session for {} from {} ({})",
+            account.name,
+            session.browser,
+            session.profile
+        );
+        Ok(Some(account))
+    }
+
+    /// First cookie claude.ai accepts, along with its organization
+    ///
+    /// Expired sessions are skipped; any error other than an expired session
+    /// stops the search, since the next cookie would likely fail the same way.
+    async fn first_working(
+        provider: &ClaudeProvider,
+        cookies: Vec<BrowserCookie>,
+        org_id: Option<&str>,
+        stale: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<BrowserSession>, ProviderError> {
+        let mut tried = HashSet::new();
+
+        for cookie in cookies {
+            if stale == Some(cookie.value.as_str())
+                || cookie.expires_at.is_some_and(|at| at <= now)
+                || !tried.insert(cookie.value.clone())
+            {
+                continue;
+            }
+
+            let organizations = match provider.list_organizations(&cookie.value).await {
+                Ok(organizations) => organizations,
+                Err(ProviderError::SessionExpired) => {
+                    log::debug!(
+                        "Session in {} ({}) has expired",
+                        cookie.browser,
+                        cookie.profile
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            if let Some(org) = choose_organization(&organizations, org_id) {
+                return Ok(Some(BrowserSession {
+                    browser: cookie.browser,
+                    profile: cookie.profile,
+                    session_key: cookie.value,
+                    org_id: org.uuid.clone(),
+                    org_name: org.name.clone(),
+                    expires_at: cookie.expires_at,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// The wanted organization, or the first one that can use claude.ai
+fn choose_organization<'a>(
+    organizations: &'a [ClaudeOrganization],
+    org_id: Option<&str>,
+) -> Option<&'a ClaudeOrganization> {
+    match org_id {
+        Some(org_id) => organizations.iter().find(|org| org.uuid == org_id),
+        None => organizations
+            .iter()
+            .find(|org| org.capabilities.iter().any(|c| c == CHAT_CAPABILITY))
+            .or_else(|| organizations.first()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn cookie(value: &str, expires_at: Option<DateTime<Utc>>) -> BrowserCookie {
+        BrowserCookie {
+            browser: "Firefox".to_string(),
+            profile: "abc.default".to_string(),
+            value: value.to_string(),
+            expires_at,
+        }
+    }
+
+    fn org(uuid: &str, capabilities: &[&str]) -> ClaudeOrganization {
+        ClaudeOrganization {
+            uuid: uuid.to_string(),
+            name: format!("Org {}", uuid),
+            capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_choose_organization() {
+        let organizations = vec![org("api", &["api"]), org("chat", &["chat", "claude_pro"])];
+
+        assert_eq!(
+            choose_organization(&organizations, None).unwrap().uuid,
+            "chat"
+        );
+        assert_eq!(
+            choose_organization(&organizations, Some("api"))
+                .unwrap()
+                .uuid,
+            "api"
+        );
+        assert!(choose_organization(&organizations, Some("other")).is_none());
+        assert_eq!(
+            choose_organization(&organizations[..1], None).unwrap().uuid,
+            "api"
+        );
+        assert!(choose_organization(&[], None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_first_working_skips_expired_sessions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .and(header("cookie", "sessionKey=sk-ant-sid01-old"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .and(header("cookie", "sessionKey=sk-ant-sid01-new"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "uuid": "org-1", "name": "Personal", "capabilities": ["chat"] }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&server.uri()).unwrap();
+        let now = Utc::now();
+        let cookies = vec![
+            cookie("sk-ant-sid01-stale", None),
+            cookie("sk-ant-sid01-gone", Some(now - Duration::days(1))),
+            cookie("sk-ant-sid01-old", None),
+            cookie("sk-ant-sid01-new", Some(now + Duration::days(30))),
+            cookie("sk-ant-sid01-new", None),
+        ];
+
+        let session = SessionImportService::first_working(
+            &provider,
+            cookies,
+            None,
+            Some("sk-ant-sid01-stale"),
+            now,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(session.session_key, "sk-ant-sid01-new");
+        assert_eq!(session.org_id, "org-1");
+        assert_eq!(session.org_name, "Personal");
+        assert_eq!(session.browser, "Firefox");
+    }
+
+    #[tokio::test]
+    async fn test_first_working_requires_the_organization() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "uuid": "org-1", "name": "Personal", "capabilities": ["chat"] }
+            ])))
+            .mount(&server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&server.uri()).unwrap();
+        let session = SessionImportService::first_working(
+            &provider,
+            vec![cookie("sk-ant-sid01-a", None)],
+            Some("org-2"),
+            None,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        assert!(session.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_first_working_stops_on_other_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/organizations"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = ClaudeProvider::with_base_url(&server.uri()).unwrap();
+        let result = SessionImportService::first_working(
+            &provider,
+            vec![
+                cookie("sk-ant-sid01-a", None),
+                cookie("sk-ant-sid01-b", None),
+            ],
+            None,
+            None,
+            Utc::now(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ProviderError::CloudflareBlocked)));
+    }
+}
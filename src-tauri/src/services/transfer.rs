@@ -0,0 +1,425 @@
+//! Passphrase-encrypted account bundles for moving to another machine
+//!
+//! Stored secrets can't simply be copied: they live in the OS keyring or are
+//! encrypted with a key tied to this machine. An export bundle instead holds
+//! the accounts with their credentials, the settings and optionally the usage
+//! history, sealed with a key derived from a passphrase picked for the export
+//! (Argon2id, fresh salt per bundle). Importing saves the accounts like any
+//! other, so they go through the usual credential migration and secret store.
+
+use crate::error::{AppError, CryptoError};
+use crate::models::{Account, AppSettings, UsageHistoryEntry};
+use crate::services::crypto::{self, KdfParams, Sealed};
+use crate::services::vault::{self, VaultService};
+use crate::services::{CredentialService, HistoryService, SettingsService};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::AppHandle;
+
+const BUNDLE_FORMAT: &str = "ai-pulse-accounts";
+const BUNDLE_VERSION: u32 = 1;
+
+/// Appended to the name of an account imported under a new ID
+const RENAMED_SUFFIX: &str = " (imported)";
+
+/// Bundle as written to disk
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Bundle {
+    format: String,
+    version: u32,
+    created_at: DateTime<Utc>,
+    /// Sealed JSON of `BundleContents`
+    payload: String,
+}
+
+/// What a bundle holds once opened
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleContents {
+    accounts: Vec<Account>,
+    settings: AppSettings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    history: Option<Vec<UsageHistoryEntry>>,
+}
+
+/// What to do with a bundled account whose ID already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflict {
+    /// Keep the existing account
+    #[default]
+    Skip,
+    /// Import the bundled account next to it under a new ID
+    Rename,
+    /// Replace the existing account
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    /// Names of accounts that didn't exist yet
+    pub added: Vec<String>,
+    /// Names of existing accounts that were replaced
+    pub overwritten: Vec<String>,
+    /// Names given to accounts imported under a new ID
+    pub renamed: Vec<String>,
+    /// Names of bundled accounts left out because their ID exists
+    pub skipped: Vec<String>,
+    /// The bundled settings replaced the current ones
+    pub settings_imported: bool,
+    /// History entries added (ones already recorded are not counted)
+    pub history_entries: usize,
+}
+
+/// Accounts to save and how bundled account IDs map to the saved ones
+struct ImportPlan {
+    accounts: Vec<Account>,
+    renamed_ids: HashMap<String, String>,
+    /// Bundled accounts left out, whose history is left out too
+    skipped_ids: HashSet<String>,
+    summary: ImportSummary,
+}
+
+pub struct TransferService;
+
+impl TransferService {
+    /// Export every account with its credentials, the settings and optionally
+    /// the usage history as a bundle sealed with `passphrase`
+    pub async fn export(
+        app: &AppHandle,
+        passphrase: &str,
+        include_history: bool,
+    ) -> Result<String, AppError> {
+        vault::check_new_passphrase(passphrase)?;
+        if VaultService::is_locked(app) {
+            return Err(AppError::VaultLocked);
+        }
+
+        let contents = BundleContents {
+            accounts: CredentialService::list_all_accounts(app)?,
+            settings: SettingsService::get(app)?,
+            history: if include_history {
+                Some(HistoryService::get_all_entries(app)?)
+            } else {
+                None
+            },
+        };
+
+        let account_count = contents.accounts.len();
+        let passphrase = passphrase.to_string();
+        let bundle =
+            run_blocking(move || Self::seal(&contents, &passphrase, KdfParams::default())).await?;
+        log::info!(
+            "Exported {} accounts{}",
+            account_count,
+            if include_history { " with history" } else { "" }
+        );
+        Ok(bundle)
+    }
+
+    /// Import a bundle created by `export`
+    ///
+    /// Settings are only replaced with `import_settings`; bundled history is
+    /// merged in, except that of skipped accounts.
+    pub async fn import(
+        app: &AppHandle,
+        bundle: &str,
+        passphrase: &str,
+        on_conflict: ImportConflict,
+        import_settings: bool,
+    ) -> Result<ImportSummary, AppError> {
+        if VaultService::is_locked(app) {
+            return Err(AppError::VaultLocked);
+        }
+
+        let (bundle, passphrase) = (bundle.to_string(), passphrase.to_string());
+        let contents = run_blocking(move || Self::open(&bundle, &passphrase)).await?;
+        let existing = CredentialService::account_ids(app)?;
+        let mut plan = Self::plan(contents.accounts, &existing, on_conflict);
+
+        for account in &plan.accounts {
+            CredentialService::save_account(app, account)?;
+        }
+
+        if import_settings {
+            SettingsService::save(app, &contents.settings)?;
+            plan.summary.settings_imported = true;
+        }
+
+        if let Some(history) = contents.history {
+            let history = Self::remap_history(history, &plan.renamed_ids, &plan.skipped_ids);
+            plan.summary.history_entries = HistoryService::import_entries(app, &history)?;
+        }
+
+        log::info!(
+            "Imported accounts: {} added, {} overwritten, {} renamed, {} skipped",
+            plan.summary.added.len(),
+            plan.summary.overwritten.len(),
+            plan.summary.renamed.len(),
+            plan.summary.skipped.len()
+        );
+        Ok(plan.summary)
+    }
+
+    fn seal(
+        contents: &BundleContents,
+        passphrase: &str,
+        params: KdfParams,
+    ) -> Result<String, AppError> {
+        let salt = crypto::generate_salt();
+        let key = crypto::derive_passphrase_key(passphrase, &salt, &params)?;
+        let payload = Sealed::seal(&key, params, &salt, &serde_json::to_string(contents)?)?;
+
+        let bundle = Bundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            created_at: Utc::now(),
+            payload: payload.to_string(),
+        };
+        Ok(serde_json::to_string_pretty(&bundle)?)
+    }
+
+    fn open(bundle: &str, passphrase: &str) -> Result<BundleContents, AppError> {
+        let invalid = || AppError::InvalidInput("Not an AI Pulse account export".to_string());
+
+        let bundle: Bundle = serde_json::from_str(bundle).map_err(|_| invalid())?;
+        if bundle.format != BUNDLE_FORMAT {
+            return Err(invalid());
+        }
+        if bundle.version > BUNDLE_VERSION {
+            return Err(AppError::InvalidInput(
+                "This export was created by a newer version of AI Pulse".to_string(),
+            ));
+        }
+
+        // A crafted bundle must not tie up memory or CPU before the passphrase is checked
+        let sealed = Sealed::parse(&bundle.payload).ok_or_else(invalid)?;
+        if !sealed.params.within_limits() {
+            return Err(invalid());
+        }
+
+        let key = crypto::derive_passphrase_key(passphrase, &sealed.salt, &sealed.params)?;
+        let plaintext = sealed.open(&key).map_err(|e| match e {
+            CryptoError::Decrypt(_) => AppError::InvalidInput("Wrong passphrase".to_string()),
+            e => e.into(),
+        })?;
+        Ok(serde_json::from_str(&plaintext)?)
+    }
+
+    /// Decide what happens to each bundled account given the existing IDs
+    fn plan(
+        accounts: Vec<Account>,
+        existing: &HashSet<String>,
+        on_conflict: ImportConflict,
+    ) -> ImportPlan {
+        let mut plan = ImportPlan {
+            accounts: Vec::new(),
+            renamed_ids: HashMap::new(),
+            skipped_ids: HashSet::new(),
+            summary: ImportSummary::default(),
+        };
+
+        for mut account in accounts {
+            if !existing.contains(&account.id) {
+                plan.summary.added.push(account.name.clone());
+                plan.accounts.push(account);
+                continue;
+            }
+
+            match on_conflict {
+                ImportConflict::Skip => {
+                    plan.skipped_ids.insert(account.id);
+                    plan.summary.skipped.push(account.name);
+                }
+                ImportConflict::Overwrite => {
+                    plan.summary.overwritten.push(account.name.clone());
+                    plan.accounts.push(account);
+                }
+                ImportConflict::Rename => {
+                    let id = uuid::Uuid::new_v4().to_string();
+                    plan.renamed_ids.insert(account.id.clone(), id.clone());
+                    account.id = id;
+                    account.name.push_str(RENAMED_SUFFIX);
+                    plan.summary.renamed.push(account.name.clone());
+                    plan.accounts.push(account);
+                }
+            }
+        }
+
+        plan
+    }
+
+    /// Point the history of accounts imported under a new ID at that ID, and
+    /// drop the history of skipped accounts
+    fn remap_history(
+        entries: Vec<UsageHistoryEntry>,
+        renamed_ids: &HashMap<String, String>,
+        skipped_ids: &HashSet<String>,
+    ) -> Vec<UsageHistoryEntry> {
+        entries
+            .into_iter()
+            .filter(|entry| !skipped_ids.contains(&entry.account_id))
+            .map(|mut entry| {
+                if let Some(id) = renamed_ids.get(&entry.account_id) {
+                    entry.id = format!("{}-{}-{}", entry.timestamp.timestamp(), entry.provider, id);
+                    entry.account_id = id.clone();
+                }
+                entry
+            })
+            .collect()
+    }
+}
+
+/// Run key derivation (and the work around it) on the blocking pool, so it
+/// doesn't hold up an async worker
+async fn run_blocking<T: Send + 'static>(
+    operation: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tokio::task::spawn_blocking(operation)
+        .await
+        .map_err(|e| AppError::Store(format!("Account transfer failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Credentials;
+
+    const TEST_PARAMS: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn account(id: &str, name: &str) -> Account {
+        let mut account = Account::new(
+            name.to_string(),
+            "claude".to_string(),
+            Credentials {
+                session_key: Some("sk-ant-secret".to_string()),
+                ..Default::default()
+            },
+        );
+        account.id = id.to_string();
+        account
+    }
+
+    fn history_entry(account_id: &str) -> UsageHistoryEntry {
+        let timestamp = Utc::now();
+        UsageHistoryEntry {
+            id: format!("{}-claude-{}", timestamp.timestamp(), account_id),
+            provider: "claude".to_string(),
+            account_id: account_id.to_string(),
+            account_name: "Work".to_string(),
+            timestamp,
+            limits: Vec::new(),
+        }
+    }
+
+    fn contents() -> BundleContents {
+        BundleContents {
+            accounts: vec![account("a1", "Personal"), account("a2", "Work")],
+            settings: AppSettings::default(),
+            history: Some(vec![history_entry("a2")]),
+        }
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let bundle = TransferService::seal(&contents(), "correct horse", TEST_PARAMS).unwrap();
+        assert!(!bundle.contains("sk-ant-secret"));
+
+        let opened = TransferService::open(&bundle, "correct horse").unwrap();
+        assert_eq!(opened.accounts.len(), 2);
+        assert_eq!(
+            opened.accounts[0].credentials.session_key.as_deref(),
+            Some("sk-ant-secret")
+        );
+        assert_eq!(opened.history.map(|h| h.len()), Some(1));
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_passphrase_and_other_files() {
+        let bundle = TransferService::seal(&contents(), "correct horse", TEST_PARAMS).unwrap();
+
+        assert!(matches!(
+            TransferService::open(&bundle, "wrong horse"),
+            Err(AppError::InvalidInput(message)) if message == "Wrong passphrase"
+        ));
+        assert!(matches!(
+            TransferService::open("{\"entries\": []}", "correct horse"),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        let newer = bundle.replace("\"version\": 1", "\"version\": 99");
+        assert!(matches!(
+            TransferService::open(&newer, "correct horse"),
+            Err(AppError::InvalidInput(message)) if message.contains("newer version")
+        ));
+    }
+
+    #[test]
+    fn test_open_rejects_costly_kdf_parameters() {
+        let bundle = TransferService::seal(&contents(), "correct horse", TEST_PARAMS).unwrap();
+
+        for costly in ["m=4194304,t=1,p=1", "m=64,t=1000000,p=1", "m=64,t=1,p=1000"] {
+            let crafted = bundle.replace("m=64,t=1,p=1", costly);
+            assert!(matches!(
+                TransferService::open(&crafted, "correct horse"),
+                Err(AppError::InvalidInput(message)) if message.contains("Not an AI Pulse")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_plan_resolves_conflicts() {
+        let existing: HashSet<String> = ["a2".to_string()].into();
+        let bundled = || vec![account("a1", "Personal"), account("a2", "Work")];
+
+        let plan = TransferService::plan(bundled(), &existing, ImportConflict::Skip);
+        assert_eq!(plan.accounts.len(), 1);
+        assert_eq!(plan.summary.added, vec!["Personal"]);
+        assert_eq!(plan.summary.skipped, vec!["Work"]);
+
+        let plan = TransferService::plan(bundled(), &existing, ImportConflict::Overwrite);
+        assert_eq!(plan.accounts[1].id, "a2");
+        assert_eq!(plan.summary.overwritten, vec!["Work"]);
+
+        let plan = TransferService::plan(bundled(), &existing, ImportConflict::Rename);
+        let renamed = &plan.accounts[1];
+        assert_ne!(renamed.id, "a2");
+        assert_eq!(renamed.name, "Work (imported)");
+        assert_eq!(plan.renamed_ids.get("a2"), Some(&renamed.id));
+        assert_eq!(plan.summary.renamed, vec!["Work (imported)"]);
+    }
+
+    #[test]
+    fn test_remap_history_follows_renamed_accounts() {
+        let renamed_ids: HashMap<String, String> = [("a2".to_string(), "b2".to_string())].into();
+        let entries = vec![history_entry("a1"), history_entry("a2")];
+
+        let remapped = TransferService::remap_history(entries, &renamed_ids, &HashSet::new());
+        assert_eq!(remapped[0].account_id, "a1");
+        assert_eq!(remapped[1].account_id, "b2");
+        assert!(remapped[1].id.ends_with("-claude-b2"));
+    }
+
+    #[test]
+    fn test_remap_history_drops_skipped_accounts() {
+        let existing: HashSet<String> = ["a2".to_string()].into();
+        let plan = TransferService::plan(
+            vec![account("a1", "Personal"), account("a2", "Work")],
+            &existing,
+            ImportConflict::Skip,
+        );
+        let entries = vec![history_entry("a1"), history_entry("a2")];
+
+        let remapped =
+            TransferService::remap_history(entries, &plan.renamed_ids, &plan.skipped_ids);
+        assert_eq!(remapped.len(), 1);
+        assert_eq!(remapped[0].account_id, "a1");
+    }
+}
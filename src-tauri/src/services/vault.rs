@@ -0,0 +1,363 @@
+//! Optional master passphrase for stored credentials
+//!
+//! When the vault is enabled, every secret is encrypted with a key derived
+//! from the passphrase (Argon2id, random per-install salt) before it reaches
+//! the secret store, so a copy of the OS keyring or `secrets.json` is useless
+//! without the passphrase. The key only lives in memory while the vault is
+//! unlocked; while it is locked, credentials can't be read and the scheduler
+//! pauses.
+
+use crate::error::{AppError, CryptoError};
+use crate::services::crypto::{self, KdfParams, Key, Sealed};
+use crate::services::CredentialService;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "vault.json";
+
+/// Sealed `CHECK_PLAINTEXT`, used to tell a wrong passphrase from a right one.
+/// Its KDF parameters and salt are the vault's.
+const CHECK_KEY: &str = "check";
+const CHECK_PLAINTEXT: &str = "ai-pulse-vault";
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Reject passphrases too short to protect secrets (for the vault or an
+/// account bundle)
+pub(crate) fn check_new_passphrase(passphrase: &str) -> Result<(), AppError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(AppError::InvalidInput(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Key of an unlocked vault
+#[derive(Clone)]
+pub struct UnlockedVault {
+    key: Key,
+    params: KdfParams,
+    salt: Vec<u8>,
+}
+
+impl UnlockedVault {
+    /// Derive the key for `passphrase`, checking it against the vault's check value
+    fn derive(check: &Sealed, passphrase: &str) -> Result<Self, AppError> {
+        if !check.params.within_limits() {
+            return Err(AppError::Store(format!(
+                "Invalid {}: key derivation parameters are too costly",
+                STORE_FILE
+            )));
+        }
+        let key = crypto::derive_passphrase_key(passphrase, &check.salt, &check.params)?;
+        match check.open(&key) {
+            Ok(plaintext) if plaintext == CHECK_PLAINTEXT => Ok(Self {
+                key,
+                params: check.params,
+                salt: check.salt.clone(),
+            }),
+            _ => Err(AppError::InvalidInput("Wrong passphrase".to_string())),
+        }
+    }
+
+    /// Vault with cheap KDF parameters
+    #[cfg(test)]
+    pub(crate) fn for_test(passphrase: &str) -> Self {
+        let params = KdfParams {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let salt = crypto::generate_salt();
+        let key = crypto::derive_passphrase_key(passphrase, &salt, &params).unwrap();
+        Self { key, params, salt }
+    }
+
+    fn seal(&self, plaintext: &str) -> Result<Sealed, AppError> {
+        Ok(Sealed::seal(&self.key, self.params, &self.salt, plaintext)?)
+    }
+
+    fn open(&self, sealed: &Sealed) -> Result<String, AppError> {
+        if sealed.params != self.params || sealed.salt != self.salt {
+            return Err(
+                CryptoError::Decrypt("sealed with a different passphrase".to_string()).into(),
+            );
+        }
+        Ok(sealed.open(&self.key)?)
+    }
+}
+
+/// What the vault can do with secrets right now
+#[derive(Clone)]
+pub enum VaultKey {
+    /// No passphrase: secrets are stored as they are
+    Disabled,
+    /// Passphrase set but not entered: secrets can't be read or written
+    Locked,
+    Unlocked(UnlockedVault),
+}
+
+impl VaultKey {
+    /// Encrypt a secret before it is stored
+    pub fn seal(&self, value: &str) -> Result<String, AppError> {
+        match self {
+            Self::Disabled => Ok(value.to_string()),
+            Self::Locked => Err(AppError::VaultLocked),
+            Self::Unlocked(vault) => Ok(vault.seal(value)?.to_string()),
+        }
+    }
+
+    /// Decrypt a stored secret (secrets stored without the vault pass through)
+    pub fn open(&self, value: &str) -> Result<String, AppError> {
+        let Some(sealed) = Sealed::parse(value) else {
+            return Ok(value.to_string());
+        };
+        match self {
+            Self::Unlocked(vault) => vault.open(&sealed),
+            Self::Locked => Err(AppError::VaultLocked),
+            // Entering a passphrase won't help; the secret has to be entered again
+            Self::Disabled => Err(CryptoError::Decrypt(
+                "sealed with a passphrase that is no longer set".to_string(),
+            )
+            .into()),
+        }
+    }
+}
+
+/// In-memory key of the unlocked vault
+#[derive(Default)]
+pub struct VaultState {
+    unlocked: Mutex<Option<UnlockedVault>>,
+}
+
+impl VaultState {
+    fn get(&self) -> Option<UnlockedVault> {
+        self.unlocked
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn set(&self, vault: Option<UnlockedVault>) {
+        *self.unlocked.lock().unwrap_or_else(|e| e.into_inner()) = vault;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    /// A master passphrase is set
+    pub enabled: bool,
+    /// The passphrase hasn't been entered since the app started (or the vault was locked)
+    pub locked: bool,
+}
+
+pub struct VaultService;
+
+impl VaultService {
+    pub fn status(app: &AppHandle) -> Result<VaultStatus, AppError> {
+        Ok(match Self::current(app)? {
+            VaultKey::Disabled => VaultStatus {
+                enabled: false,
+                locked: false,
+            },
+            VaultKey::Locked => VaultStatus {
+                enabled: true,
+                locked: true,
+            },
+            VaultKey::Unlocked(_) => VaultStatus {
+                enabled: true,
+                locked: false,
+            },
+        })
+    }
+
+    /// Check if a passphrase is set and hasn't been entered
+    pub fn is_locked(app: &AppHandle) -> bool {
+        matches!(Self::current(app), Ok(VaultKey::Locked))
+    }
+
+    /// The vault's current key
+    pub fn current(app: &AppHandle) -> Result<VaultKey, AppError> {
+        if Self::load_check(app)?.is_none() {
+            return Ok(VaultKey::Disabled);
+        }
+        Ok(match Self::state(app).get() {
+            Some(vault) => VaultKey::Unlocked(vault),
+            None => VaultKey::Locked,
+        })
+    }
+
+    /// Set a master passphrase and encrypt every stored secret with it
+    pub fn enable(app: &AppHandle, passphrase: &str) -> Result<(), AppError> {
+        if Self::load_check(app)?.is_some() {
+            return Err(AppError::InvalidInput(
+                "A master passphrase is already set".to_string(),
+            ));
+        }
+        check_new_passphrase(passphrase)?;
+
+        let params = KdfParams::default();
+        let salt = crypto::generate_salt();
+        let key = crypto::derive_passphrase_key(passphrase, &salt, &params)?;
+        let vault = UnlockedVault { key, params, salt };
+        let check = vault.seal(CHECK_PLAINTEXT)?;
+
+        Self::save_check(app, Some(&check))?;
+        Self::state(app).set(Some(vault));
+
+        // Encrypts every secret with the new key
+        CredentialService::ensure_migrated(app)?;
+        log::info!("Master passphrase enabled");
+        Ok(())
+    }
+
+    /// Enter the passphrase, encrypting any secret that isn't yet
+    pub fn unlock(app: &AppHandle, passphrase: &str) -> Result<(), AppError> {
+        let check = Self::load_check(app)?
+            .ok_or_else(|| AppError::InvalidInput("No master passphrase is set".to_string()))?;
+        let vault = UnlockedVault::derive(&check, passphrase)?;
+        Self::state(app).set(Some(vault));
+
+        CredentialService::ensure_migrated(app)?;
+        log::info!("Credential vault unlocked");
+        Ok(())
+    }
+
+    /// Forget the key until the passphrase is entered again
+    pub fn lock(app: &AppHandle) {
+        Self::state(app).set(None);
+        log::info!("Credential vault locked");
+    }
+
+    /// Remove the master passphrase, storing secrets without it again
+    pub fn disable(app: &AppHandle, passphrase: &str) -> Result<(), AppError> {
+        Self::unlock(app, passphrase)?;
+        let vault = Self::current(app)?;
+
+        CredentialService::reseal(app, &vault, &VaultKey::Disabled)?;
+        Self::save_check(app, None)?;
+        Self::state(app).set(None);
+        log::info!("Master passphrase removed");
+        Ok(())
+    }
+
+    fn state(app: &AppHandle) -> tauri::State<'_, VaultState> {
+        if app.try_state::<VaultState>().is_none() {
+            app.manage(VaultState::default());
+        }
+        app.state::<VaultState>()
+    }
+
+    fn load_check(app: &AppHandle) -> Result<Option<Sealed>, AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+        match store.get(CHECK_KEY) {
+            Some(value) => {
+                let value: String = serde_json::from_value(value)?;
+                Sealed::parse(&value)
+                    .map(Some)
+                    .ok_or_else(|| AppError::Store(format!("Invalid {}", STORE_FILE)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn save_check(app: &AppHandle, check: Option<&Sealed>) -> Result<(), AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+        match check {
+            Some(check) => store.set(CHECK_KEY, check.to_string()),
+            None => {
+                store.delete(CHECK_KEY);
+            }
+        }
+        store.save().map_err(|e| AppError::Store(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unlocked(passphrase: &str) -> (UnlockedVault, Sealed) {
+        let vault = UnlockedVault::for_test(passphrase);
+        let check = vault.seal(CHECK_PLAINTEXT).unwrap();
+        (vault, check)
+    }
+
+    #[test]
+    fn test_unlock_checks_passphrase() {
+        let (_, check) = unlocked("correct horse");
+
+        assert!(UnlockedVault::derive(&check, "correct horse").is_ok());
+        assert!(matches!(
+            UnlockedVault::derive(&check, "wrong horse"),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_unlock_rejects_costly_parameters() {
+        let (_, mut check) = unlocked("correct horse");
+        check.params.iterations = 1_000_000;
+
+        assert!(matches!(
+            UnlockedVault::derive(&check, "correct horse"),
+            Err(AppError::Store(_))
+        ));
+    }
+
+    #[test]
+    fn test_vault_key_seal_and_open() {
+        let (vault, _) = unlocked("correct horse");
+        let key = VaultKey::Unlocked(vault);
+
+        let sealed = key.seal("sk-ant-secret").unwrap();
+        assert!(sealed.starts_with(crypto::PASSPHRASE_PREFIX));
+        assert_eq!(key.open(&sealed).unwrap(), "sk-ant-secret");
+
+        // Secrets stored before the vault was enabled pass through
+        assert_eq!(key.open("sk-ant-plain").unwrap(), "sk-ant-plain");
+
+        assert!(matches!(
+            VaultKey::Locked.open(&sealed),
+            Err(AppError::VaultLocked)
+        ));
+        assert!(matches!(
+            VaultKey::Locked.seal("sk-ant-secret"),
+            Err(AppError::VaultLocked)
+        ));
+        assert_eq!(
+            VaultKey::Disabled.seal("sk-ant-plain").unwrap(),
+            "sk-ant-plain"
+        );
+    }
+
+    #[test]
+    fn test_open_rejects_other_vaults() {
+        let (first, _) = unlocked("correct horse");
+        let (second, _) = unlocked("correct horse");
+
+        let sealed = VaultKey::Unlocked(first).seal("sk-ant-secret").unwrap();
+        assert!(VaultKey::Unlocked(second).open(&sealed).is_err());
+    }
+
+    #[test]
+    fn test_disabled_vault_cannot_open_sealed_secrets() {
+        let (vault, _) = unlocked("correct horse");
+        let sealed = VaultKey::Unlocked(vault).seal("sk-ant-secret").unwrap();
+
+        // A decrypt error (not VaultLocked) flags the field for re-entry
+        assert!(matches!(
+            VaultKey::Disabled.open(&sealed),
+            Err(AppError::Crypto(CryptoError::Decrypt(_)))
+        ));
+    }
+}
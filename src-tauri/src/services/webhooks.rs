@@ -0,0 +1,514 @@
+use crate::error::AppError;
+use crate::models::{
+    WebhookConfig, WebhookDeadLetter, WebhookDelivery, WebhookDeliveryStatus, WebhookEventType,
+    WebhookPayload,
+};
+use crate::services::SettingsService;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "webhooks.json";
+const DELIVERIES_KEY: &str = "deliveries";
+const DEAD_LETTERS_KEY: &str = "dead_letters";
+
+/// Number of delivery log entries kept (oldest are dropped first)
+const MAX_DELIVERIES: usize = 500;
+/// Number of dead letters kept (oldest are dropped first)
+const MAX_DEAD_LETTERS: usize = 200;
+
+/// Attempts per delivery before it goes to the dead-letter queue
+const MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first retry, doubled after each attempt (2s, 4s, 8s, 16s)
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const SIGNATURE_HEADER: &str = "X-AI-Pulse-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-AI-Pulse-Timestamp";
+pub const EVENT_HEADER: &str = "X-AI-Pulse-Event";
+pub const DELIVERY_HEADER: &str = "X-AI-Pulse-Delivery";
+
+/// Serializes read-modify-write of the delivery log and dead-letter queue
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct WebhookService;
+
+impl WebhookService {
+    /// Deliver an event to every webhook subscribed to it (in the background)
+    pub fn dispatch<T: Serialize>(app: &AppHandle, event: WebhookEventType, data: &T) {
+        let webhooks: Vec<WebhookConfig> = match SettingsService::get(app) {
+            Ok(settings) => settings
+                .webhooks
+                .into_iter()
+                .filter(|w| w.accepts(event))
+                .collect(),
+            Err(_) => return,
+        };
+
+        if webhooks.is_empty() {
+            return;
+        }
+
+        let data = match serde_json::to_value(data) {
+            Ok(v) => v,
+            Err(e) => {
+                log::warn!("Failed to serialize {} webhook payload: {}", event.as_str(), e);
+                return;
+            }
+        };
+
+        for webhook in webhooks {
+            let payload = WebhookPayload {
+                id: uuid::Uuid::new_v4().to_string(),
+                event,
+                timestamp: Utc::now(),
+                data: data.clone(),
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                Self::deliver_and_record(&app, &webhook, &payload).await;
+            });
+        }
+    }
+
+    /// Get the delivery log (newest first), optionally for a single webhook
+    pub fn list_deliveries(
+        app: &AppHandle,
+        webhook_id: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<WebhookDelivery>, AppError> {
+        let _guard = STORE_LOCK.lock().unwrap();
+        let deliveries: Vec<WebhookDelivery> = Self::read(app, DELIVERIES_KEY)?;
+
+        Ok(deliveries
+            .into_iter()
+            .rev()
+            .filter(|d| webhook_id.map_or(true, |id| d.webhook_id == id))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    /// Get the dead-letter queue (newest first)
+    pub fn list_dead_letters(app: &AppHandle) -> Result<Vec<WebhookDeadLetter>, AppError> {
+        let _guard = STORE_LOCK.lock().unwrap();
+        let mut dead_letters: Vec<WebhookDeadLetter> = Self::read(app, DEAD_LETTERS_KEY)?;
+        dead_letters.reverse();
+        Ok(dead_letters)
+    }
+
+    /// Redeliver a dead letter to its webhook. It is queued again if delivery fails.
+    pub async fn retry_dead_letter(
+        app: &AppHandle,
+        delivery_id: &str,
+    ) -> Result<WebhookDelivery, AppError> {
+        let dead_letter = {
+            let _guard = STORE_LOCK.lock().unwrap();
+            let mut dead_letters: Vec<WebhookDeadLetter> = Self::read(app, DEAD_LETTERS_KEY)?;
+            let index = dead_letters
+                .iter()
+                .position(|d| d.delivery.id == delivery_id)
+                .ok_or_else(|| {
+                    AppError::InvalidInput(format!("Dead letter not found: {}", delivery_id))
+                })?;
+            let dead_letter = dead_letters.remove(index);
+            Self::write(app, DEAD_LETTERS_KEY, &dead_letters)?;
+            dead_letter
+        };
+
+        let settings = SettingsService::get(app)?;
+        let webhook = settings
+            .webhooks
+            .into_iter()
+            .find(|w| w.id == dead_letter.delivery.webhook_id)
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!(
+                    "Webhook no longer exists: {}",
+                    dead_letter.delivery.webhook_id
+                ))
+            })?;
+
+        Ok(Self::deliver_and_record(app, &webhook, &dead_letter.payload).await)
+    }
+
+    /// Empty the dead-letter queue. Returns the number of entries removed.
+    pub fn clear_dead_letters(app: &AppHandle) -> Result<usize, AppError> {
+        let _guard = STORE_LOCK.lock().unwrap();
+        let dead_letters: Vec<WebhookDeadLetter> = Self::read(app, DEAD_LETTERS_KEY)?;
+        Self::write(app, DEAD_LETTERS_KEY, &Vec::<WebhookDeadLetter>::new())?;
+        Ok(dead_letters.len())
+    }
+
+    async fn deliver_and_record(
+        app: &AppHandle,
+        webhook: &WebhookConfig,
+        payload: &WebhookPayload,
+    ) -> WebhookDelivery {
+        let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to create webhook HTTP client: {}", e);
+                return failed_without_attempt(webhook, payload, e.to_string());
+            }
+        };
+
+        let delivery = deliver(&client, webhook, payload, INITIAL_BACKOFF).await;
+
+        match delivery.status {
+            WebhookDeliveryStatus::Delivered => log::debug!(
+                "Delivered {} webhook to {} ({} attempts)",
+                payload.event.as_str(),
+                webhook.url,
+                delivery.attempts
+            ),
+            WebhookDeliveryStatus::Failed => log::warn!(
+                "Webhook {} to {} failed after {} attempts: {}",
+                payload.event.as_str(),
+                webhook.url,
+                delivery.attempts,
+                delivery.error.as_deref().unwrap_or("unknown error")
+            ),
+        }
+
+        if let Err(e) = Self::record(app, &delivery, payload) {
+            log::warn!("Failed to record webhook delivery: {}", e);
+        }
+
+        delivery
+    }
+
+    /// Append to the delivery log, and to the dead-letter queue if the delivery failed
+    fn record(
+        app: &AppHandle,
+        delivery: &WebhookDelivery,
+        payload: &WebhookPayload,
+    ) -> Result<(), AppError> {
+        let _guard = STORE_LOCK.lock().unwrap();
+
+        let mut deliveries: Vec<WebhookDelivery> = Self::read(app, DELIVERIES_KEY)?;
+        push_capped(&mut deliveries, delivery.clone(), MAX_DELIVERIES);
+        Self::write(app, DELIVERIES_KEY, &deliveries)?;
+
+        if delivery.status == WebhookDeliveryStatus::Failed {
+            let mut dead_letters: Vec<WebhookDeadLetter> = Self::read(app, DEAD_LETTERS_KEY)?;
+            push_capped(
+                &mut dead_letters,
+                WebhookDeadLetter {
+                    delivery: delivery.clone(),
+                    payload: payload.clone(),
+                },
+                MAX_DEAD_LETTERS,
+            );
+            Self::write(app, DEAD_LETTERS_KEY, &dead_letters)?;
+        }
+
+        Ok(())
+    }
+
+    fn read<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Result<Vec<T>, AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+
+        match store.get(key) {
+            Some(v) => Ok(serde_json::from_value(v)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn write<T: Serialize>(app: &AppHandle, key: &str, items: &[T]) -> Result<(), AppError> {
+        let store = app
+            .store(STORE_FILE)
+            .map_err(|e| AppError::Store(e.to_string()))?;
+
+        store.set(key.to_string(), serde_json::to_value(items)?);
+        store.save().map_err(|e| AppError::Store(e.to_string()))
+    }
+}
+
+/// Signature for a request body: `sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">`
+///
+/// Including the timestamp (sent in `X-AI-Pulse-Timestamp`) lets receivers reject replays.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// POST a payload, retrying network errors, 5xx, 408 and 429 with exponential backoff
+async fn deliver(
+    client: &reqwest::Client,
+    webhook: &WebhookConfig,
+    payload: &WebhookPayload,
+    initial_backoff: Duration,
+) -> WebhookDelivery {
+    let created_at = Utc::now();
+    let body = serde_json::to_vec(payload).unwrap_or_default();
+    let secret = webhook.secret.as_deref().filter(|s| !s.is_empty());
+
+    let mut backoff = initial_backoff;
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let timestamp = Utc::now().timestamp();
+        let mut request = client
+            .post(&webhook.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, payload.event.as_str())
+            .header(DELIVERY_HEADER, &payload.id)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .body(body.clone());
+        if let Some(secret) = secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, timestamp, &body));
+        }
+
+        let (status_code, error, retryable) = match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    (Some(status.as_u16()), None, false)
+                } else {
+                    let retryable = status.is_server_error()
+                        || status == StatusCode::REQUEST_TIMEOUT
+                        || status == StatusCode::TOO_MANY_REQUESTS;
+                    (Some(status.as_u16()), Some(format!("HTTP {}", status)), retryable)
+                }
+            }
+            Err(e) => (None, Some(e.to_string()), true),
+        };
+
+        if error.is_none() || !retryable || attempts >= MAX_ATTEMPTS {
+            let status = if error.is_none() {
+                WebhookDeliveryStatus::Delivered
+            } else {
+                WebhookDeliveryStatus::Failed
+            };
+            return WebhookDelivery {
+                id: payload.id.clone(),
+                webhook_id: webhook.id.clone(),
+                event: payload.event,
+                url: webhook.url.clone(),
+                status,
+                attempts,
+                status_code,
+                error,
+                created_at,
+                completed_at: Utc::now(),
+            };
+        }
+
+        log::debug!(
+            "Webhook attempt {}/{} to {} failed, retrying in {:?}",
+            attempts,
+            MAX_ATTEMPTS,
+            webhook.url,
+            backoff
+        );
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+fn failed_without_attempt(
+    webhook: &WebhookConfig,
+    payload: &WebhookPayload,
+    error: String,
+) -> WebhookDelivery {
+    WebhookDelivery {
+        id: payload.id.clone(),
+        webhook_id: webhook.id.clone(),
+        event: payload.event,
+        url: webhook.url.clone(),
+        status: WebhookDeliveryStatus::Failed,
+        attempts: 0,
+        status_code: None,
+        error: Some(error),
+        created_at: Utc::now(),
+        completed_at: Utc::now(),
+    }
+}
+
+/// Append an item, dropping the oldest ones beyond `cap`
+fn push_capped<T>(items: &mut Vec<T>, item: T, cap: usize) {
+    items.push(item);
+    if items.len() > cap {
+        let excess = items.len() - cap;
+        items.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_webhook(url: String, secret: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            id: "hook-1".to_string(),
+            name: "Test".to_string(),
+            url,
+            secret: secret.map(String::from),
+            events: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    fn make_payload() -> WebhookPayload {
+        WebhookPayload {
+            id: "delivery-1".to_string(),
+            event: WebhookEventType::UsageReset,
+            timestamp: Utc::now(),
+            data: serde_json::json!({ "limitId": "five_hour" }),
+        }
+    }
+
+    #[test]
+    fn signature_matches_known_vector() {
+        // HMAC-SHA256("secret", "1700000000.{}")
+        assert_eq!(
+            sign("secret", 1_700_000_000, b"{}"),
+            "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+    }
+
+    #[test]
+    fn webhook_event_filter() {
+        let mut webhook = make_webhook("http://localhost".to_string(), None);
+        assert!(webhook.accepts(WebhookEventType::UsageUpdate));
+
+        webhook.events = vec![WebhookEventType::ThresholdCrossed];
+        assert!(webhook.accepts(WebhookEventType::ThresholdCrossed));
+        assert!(!webhook.accepts(WebhookEventType::UsageUpdate));
+
+        webhook.enabled = false;
+        assert!(!webhook.accepts(WebhookEventType::ThresholdCrossed));
+    }
+
+    #[test]
+    fn push_capped_drops_oldest() {
+        let mut items = vec![1, 2, 3];
+        push_capped(&mut items, 4, 3);
+        assert_eq!(items, vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn delivery_is_signed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header(EVENT_HEADER, "usage-reset"))
+            .and(header(DELIVERY_HEADER, "delivery-1"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let webhook = make_webhook(format!("{}/hook", server.uri()), Some("s3cret"));
+        let delivery = deliver(
+            &reqwest::Client::new(),
+            &webhook,
+            &make_payload(),
+            Duration::ZERO,
+        )
+        .await;
+
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Delivered);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.status_code, Some(204));
+
+        let request = &server.received_requests().await.unwrap()[0];
+        let timestamp: i64 = request.headers[TIMESTAMP_HEADER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let signature = request.headers[SIGNATURE_HEADER].to_str().unwrap();
+        assert_eq!(signature, sign("s3cret", timestamp, &request.body));
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let webhook = make_webhook(server.uri(), None);
+        let delivery = deliver(
+            &reqwest::Client::new(),
+            &webhook,
+            &make_payload(),
+            Duration::ZERO,
+        )
+        .await;
+
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Delivered);
+        assert_eq!(delivery.attempts, 3);
+        assert!(delivery.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn client_errors_fail_without_retry() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let webhook = make_webhook(server.uri(), None);
+        let delivery = deliver(
+            &reqwest::Client::new(),
+            &webhook,
+            &make_payload(),
+            Duration::ZERO,
+        )
+        .await;
+
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Failed);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.status_code, Some(404));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(MAX_ATTEMPTS as u64)
+            .mount(&server)
+            .await;
+
+        let webhook = make_webhook(server.uri(), None);
+        let delivery = deliver(
+            &reqwest::Client::new(),
+            &webhook,
+            &make_payload(),
+            Duration::ZERO,
+        )
+        .await;
+
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Failed);
+        assert_eq!(delivery.attempts, MAX_ATTEMPTS);
+        assert_eq!(delivery.error.as_deref(), Some("HTTP 500 Internal Server Error"));
+    }
+}
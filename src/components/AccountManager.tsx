@@ -1,16 +1,32 @@
 import { useState, useEffect, useCallback } from "react";
-import { Plus, Trash2, Eye, EyeOff, Loader2, Wifi, Check, Edit2, X } from "lucide-react";
+import {
+  Plus,
+  Trash2,
+  Eye,
+  EyeOff,
+  Loader2,
+  Wifi,
+  Check,
+  Edit2,
+  X,
+  AlertTriangle,
+  Globe,
+} from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import {
-  listAccounts,
+  listAllAccounts,
+  listProviders,
   saveAccount,
   deleteAccount,
+  repairCredentials,
   testAccountConnection,
+  findBrowserSession,
+  type BrowserSession,
   type TestConnectionResult,
 } from "@/lib/tauri";
-import type { Account } from "@/lib/types";
+import type { Account, Credentials, ProviderId, ProviderMetadata } from "@/lib/types";
 import { useAccountsStore, useUsageStore } from "@/lib/store";
 
 interface AccountManagerProps {
@@ -24,22 +40,32 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
   const [editingAccount, setEditingAccount] = useState<Account | null>(null);
   const [isAddingNew, setIsAddingNew] = useState(false);
   const [deletingId, setDeletingId] = useState<string | null>(null);
+  const [isRepairing, setIsRepairing] = useState(false);
+
+  const [providers, setProviders] = useState<ProviderMetadata[]>([]);
 
   // Form state
+  const [formProvider, setFormProvider] = useState<ProviderId>("claude");
+  const [formFields, setFormFields] = useState<Record<string, string>>({});
   const [formName, setFormName] = useState("");
   const [formOrgId, setFormOrgId] = useState("");
   const [formSessionKey, setFormSessionKey] = useState("");
   const [showSessionKey, setShowSessionKey] = useState(false);
   const [isSaving, setIsSaving] = useState(false);
   const [isTesting, setIsTesting] = useState(false);
+  const [isImporting, setIsImporting] = useState(false);
   const [testResult, setTestResult] = useState<TestConnectionResult | null>(null);
   const [error, setError] = useState<string | null>(null);
 
   const loadAccounts = useCallback(async () => {
     setIsLoading(true);
     try {
-      const accountList = await listAccounts("claude");
+      const [accountList, providerList] = await Promise.all([
+        listAllAccounts(),
+        listProviders(),
+      ]);
       setAccounts(accountList);
+      setProviders(providerList.filter((p) => p.status === "available"));
     } catch (err) {
       console.error("Failed to load accounts:", err);
     } finally {
@@ -51,7 +77,40 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
     loadAccounts();
   }, [loadAccounts]);
 
+  // Credential fields of a non-Claude provider (Claude keeps its dedicated form)
+  const providerFields =
+    formProvider === "claude"
+      ? []
+      : providers.find((p) => p.id === formProvider)?.requiredCredentials ?? [];
+
+  const isFormComplete =
+    formProvider === "claude"
+      ? formOrgId.trim() !== "" && formSessionKey.trim() !== ""
+      : providerFields.every((field) => {
+          const value = formFields[field.key]?.trim() ?? "";
+          if (value === "") return field.isOptional;
+          return !field.isNumeric || Number(value) > 0;
+        });
+
+  const buildCredentials = (): Credentials => {
+    if (formProvider === "claude") {
+      return {
+        org_id: formOrgId.trim(),
+        session_key: formSessionKey.trim(),
+      };
+    }
+    const credentials: Credentials = {};
+    for (const field of providerFields) {
+      const value = formFields[field.key]?.trim() ?? "";
+      if (value === "") continue;
+      Object.assign(credentials, { [field.key]: field.isNumeric ? Number(value) : value });
+    }
+    return credentials;
+  };
+
   const resetForm = () => {
+    setFormProvider("claude");
+    setFormFields({});
     setFormName("");
     setFormOrgId("");
     setFormSessionKey("");
@@ -70,6 +129,12 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
 
   const handleStartEdit = (account: Account) => {
     setEditingAccount(account);
+    setFormProvider(account.provider);
+    setFormFields(
+      Object.fromEntries(
+        Object.entries(account.credentials).map(([key, value]) => [key, String(value ?? "")])
+      )
+    );
     setFormName(account.name);
     setFormOrgId(account.credentials.org_id || "");
     setFormSessionKey(account.credentials.session_key || "");
@@ -86,12 +151,15 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
     setError(null);
     setTestResult(null);
 
-    if (!formOrgId.trim() || !formSessionKey.trim()) {
+    if (!isFormComplete) {
       setTestResult({
         success: false,
         error_code: "MISSING_FIELDS",
-        error_message: "Please fill in both fields",
-        hint: "Both Organization ID and Session Key are required.",
+        error_message: "Please fill in all fields",
+        hint:
+          formProvider === "claude"
+            ? "Both Organization ID and Session Key are required."
+            : "All credential fields are required.",
       });
       return;
     }
@@ -101,11 +169,8 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
       const testAccount: Account = {
         id: editingAccount?.id || crypto.randomUUID(),
         name: formName.trim() || "Default",
-        provider: "claude",
-        credentials: {
-          org_id: formOrgId.trim(),
-          session_key: formSessionKey.trim(),
-        },
+        provider: formProvider,
+        credentials: buildCredentials(),
         createdAt: editingAccount?.createdAt || new Date().toISOString(),
       };
 
@@ -123,11 +188,42 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
     }
   };
 
+  const applyBrowserSession = (session: BrowserSession) => {
+    setFormOrgId(session.orgId);
+    setFormSessionKey(session.sessionKey);
+    setTestResult(null);
+  };
+
+  const handleImportFromBrowser = async () => {
+    setError(null);
+    setIsImporting(true);
+    try {
+      const session = await findBrowserSession(formOrgId.trim() || undefined);
+      if (session) {
+        applyBrowserSession(session);
+      } else {
+        setError(
+          formOrgId.trim()
+            ? "No browser is logged in to this organization on claude.ai"
+            : "No browser is logged in to claude.ai"
+        );
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
   const handleSave = async () => {
     setError(null);
 
-    if (!formOrgId.trim() || !formSessionKey.trim()) {
-      setError("Both Organization ID and Session Key are required");
+    if (!isFormComplete) {
+      setError(
+        formProvider === "claude"
+          ? "Both Organization ID and Session Key are required"
+          : "All credential fields are required"
+      );
       return;
     }
 
@@ -136,12 +232,10 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
       const account: Account = {
         id: editingAccount?.id || crypto.randomUUID(),
         name: formName.trim() || "Default",
-        provider: "claude",
-        credentials: {
-          org_id: formOrgId.trim(),
-          session_key: formSessionKey.trim(),
-        },
+        provider: formProvider,
+        credentials: buildCredentials(),
         createdAt: editingAccount?.createdAt || new Date().toISOString(),
+        needsReentry: editingAccount?.needsReentry,
       };
 
       await saveAccount(account);
@@ -169,7 +263,20 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
     }
   };
 
+  const handleRepair = async () => {
+    setIsRepairing(true);
+    try {
+      await repairCredentials();
+      await loadAccounts();
+    } catch (err) {
+      console.error("Failed to repair credentials:", err);
+    } finally {
+      setIsRepairing(false);
+    }
+  };
+
   const isFormOpen = isAddingNew || editingAccount !== null;
+  const needsReentry = accounts.filter((a) => a.needsReentry);
 
   if (isLoading) {
     return (
@@ -191,6 +298,24 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
         )}
       </div>
 
+      {/* Unreadable credentials */}
+      {needsReentry.length > 0 && !isFormOpen && (
+        <div className="flex items-start gap-2 p-3 rounded-lg border border-destructive/50 bg-destructive/10 text-sm">
+          <AlertTriangle className="h-4 w-4 mt-0.5 text-destructive shrink-0" />
+          <div className="flex-1">
+            <p className="font-medium">Some credentials need to be entered again</p>
+            <p className="text-xs text-muted-foreground mt-1">
+              They couldn't be read back from secure storage, so these accounts aren't fetched.
+              Edit each account to enter them again, or clean up the unreadable entries first.
+            </p>
+          </div>
+          <Button variant="outline" size="sm" onClick={handleRepair} disabled={isRepairing}>
+            {isRepairing && <Loader2 className="h-4 w-4 mr-1 animate-spin" />}
+            Clean Up
+          </Button>
+        </div>
+      )}
+
       {/* Account List */}
       {accounts.length > 0 && !isFormOpen && (
         <div className="space-y-2">
@@ -208,8 +333,15 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
                 <div>
                   <p className="font-medium text-sm">{account.name}</p>
                   <p className="text-xs text-muted-foreground">
-                    {account.credentials.org_id?.slice(0, 8)}...
+                    {account.provider === "claude"
+                      ? `${account.credentials.org_id?.slice(0, 8)}...`
+                      : providers.find((p) => p.id === account.provider)?.name ?? account.provider}
                   </p>
+                  {account.needsReentry && (
+                    <p className="text-xs text-destructive" title={account.needsReentry.reason}>
+                      Credentials need re-entry
+                    </p>
+                  )}
                 </div>
               </div>
               <div className="flex items-center gap-1">
@@ -273,48 +405,113 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
             </p>
           </div>
 
-          <div className="space-y-2">
-            <Label htmlFor="org-id">Organization ID</Label>
-            <Input
-              id="org-id"
-              placeholder="xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
-              value={formOrgId}
-              onChange={(e) => setFormOrgId(e.target.value)}
-            />
-            <p className="text-xs text-muted-foreground">
-              Find this in your Claude.ai URL: claude.ai/settings/organization/[org-id]
-            </p>
-          </div>
+          {providers.length > 1 && !editingAccount && (
+            <div className="space-y-2">
+              <Label htmlFor="account-provider">Provider</Label>
+              <select
+                id="account-provider"
+                value={formProvider}
+                onChange={(e) => {
+                  setFormProvider(e.target.value as ProviderId);
+                  setFormFields({});
+                  setTestResult(null);
+                }}
+                className="w-full h-10 px-3 rounded-md border border-input bg-background text-sm ring-offset-background focus:outline-none focus:ring-2 focus:ring-ring focus:ring-offset-2"
+              >
+                {providers.map((p) => (
+                  <option key={p.id} value={p.id}>
+                    {p.name}
+                  </option>
+                ))}
+              </select>
+              {formProvider !== "claude" && (
+                <p className="text-xs text-muted-foreground">
+                  {providers.find((p) => p.id === formProvider)?.description}
+                </p>
+              )}
+            </div>
+          )}
 
-          <div className="space-y-2">
-            <Label htmlFor="session-key">Session Key</Label>
-            <div className="relative">
+          {providerFields.map((field) => (
+            <div key={field.key} className="space-y-2">
+              <Label htmlFor={`credential-${field.key}`}>
+                {field.label}
+                {field.isOptional && (
+                  <span className="text-muted-foreground font-normal"> (optional)</span>
+                )}
+              </Label>
               <Input
-                id="session-key"
-                type={showSessionKey ? "text" : "password"}
-                placeholder="sk-ant-..."
-                value={formSessionKey}
-                onChange={(e) => setFormSessionKey(e.target.value)}
-                className="pr-10"
+                id={`credential-${field.key}`}
+                type={field.isSecret ? "password" : field.isNumeric ? "number" : "text"}
+                placeholder={field.placeholder}
+                value={formFields[field.key] ?? ""}
+                onChange={(e) =>
+                  setFormFields((fields) => ({ ...fields, [field.key]: e.target.value }))
+                }
               />
-              <Button
-                type="button"
-                variant="ghost"
-                size="icon"
-                className="absolute right-0 top-0 h-full px-3"
-                onClick={() => setShowSessionKey(!showSessionKey)}
-              >
-                {showSessionKey ? (
-                  <EyeOff className="h-4 w-4" />
-                ) : (
-                  <Eye className="h-4 w-4" />
-                )}
-              </Button>
             </div>
-            <p className="text-xs text-muted-foreground">
-              Find this in browser DevTools: Application → Cookies → sessionKey
-            </p>
-          </div>
+          ))}
+
+          {formProvider === "claude" && (
+            <>
+              <div className="space-y-2">
+                <Label htmlFor="org-id">Organization ID</Label>
+                <Input
+                  id="org-id"
+                  placeholder="xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx"
+                  value={formOrgId}
+                  onChange={(e) => setFormOrgId(e.target.value)}
+                />
+                <p className="text-xs text-muted-foreground">
+                  Find this in your Claude.ai URL: claude.ai/settings/organization/[org-id]
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label htmlFor="session-key">Session Key</Label>
+                <div className="relative">
+                  <Input
+                    id="session-key"
+                    type={showSessionKey ? "text" : "password"}
+                    placeholder="sk-ant-..."
+                    value={formSessionKey}
+                    onChange={(e) => setFormSessionKey(e.target.value)}
+                    className="pr-10"
+                  />
+                  <Button
+                    type="button"
+                    variant="ghost"
+                    size="icon"
+                    className="absolute right-0 top-0 h-full px-3"
+                    onClick={() => setShowSessionKey(!showSessionKey)}
+                  >
+                    {showSessionKey ? (
+                      <EyeOff className="h-4 w-4" />
+                    ) : (
+                      <Eye className="h-4 w-4" />
+                    )}
+                  </Button>
+                </div>
+                <p className="text-xs text-muted-foreground">
+                  Find this in browser DevTools: Application → Cookies → sessionKey
+                </p>
+                <Button
+                  type="button"
+                  variant="outline"
+                  size="sm"
+                  onClick={handleImportFromBrowser}
+                  disabled={isImporting || isSaving || isTesting}
+                >
+                  {isImporting ? (
+                    <Loader2 className="h-4 w-4 mr-2 animate-spin" />
+                  ) : (
+                    <Globe className="h-4 w-4 mr-2" />
+                  )}
+                  Import from Browser
+                </Button>
+              </div>
+            </>
+          )}
 
           {/* Test Connection Result */}
           {testResult && (
@@ -331,6 +528,20 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
               {testResult.hint && !testResult.success && (
                 <p className="text-xs mt-1 opacity-80">{testResult.hint}</p>
               )}
+              {testResult.browser_session && (
+                <Button
+                  type="button"
+                  variant="outline"
+                  size="sm"
+                  className="mt-2"
+                  onClick={() =>
+                    testResult.browser_session && applyBrowserSession(testResult.browser_session)
+                  }
+                >
+                  <Globe className="h-4 w-4 mr-2" />
+                  {`Use session from ${testResult.browser_session.browser} (${testResult.browser_session.profile})`}
+                </Button>
+              )}
             </div>
           )}
 
@@ -344,7 +555,7 @@ export function AccountManager({ onAccountsChanged }: AccountManagerProps) {
             <Button
               variant="outline"
               onClick={handleTestConnection}
-              disabled={isTesting || isSaving || !formOrgId.trim() || !formSessionKey.trim()}
+              disabled={isTesting || isSaving || !isFormComplete}
             >
               {isTesting ? (
                 <Loader2 className="h-4 w-4 mr-2 animate-spin" />
@@ -19,7 +19,13 @@ import {
   exportHistoryCsv,
   clearHistory,
 } from "@/lib/tauri";
-import type { UsageHistoryEntry, HistoryMetadata, UsageStats, ProviderId } from "@/lib/types";
+import type {
+  UsageHistoryEntry,
+  HistoryMetadata,
+  UsageStats,
+  ProviderId,
+  HistoryResolution,
+} from "@/lib/types";
 
 interface AnalyticsProps {
   provider?: ProviderId;
@@ -27,6 +33,46 @@ interface AnalyticsProps {
 
 type TimeRange = "24h" | "7d" | "30d" | "all";
 
+// Longer windows use rollups so the chart stays readable and raw retention doesn't cut it short
+const RESOLUTION_FOR_RANGE: Record<TimeRange, HistoryResolution> = {
+  "24h": "raw",
+  "7d": "raw",
+  "30d": "hourly",
+  all: "daily",
+};
+
+// Length of a rollup bucket; rollups only cover completed buckets
+const BUCKET_MS: Record<HistoryResolution, number> = {
+  raw: 0,
+  hourly: 60 * 60 * 1000,
+  daily: 24 * 60 * 60 * 1000,
+};
+
+// Rollups for the range plus the raw samples since the last completed bucket,
+// so the chart reaches the present
+async function queryHistoryWithTail(
+  provider: ProviderId,
+  resolution: HistoryResolution,
+  startDate?: string,
+  endDate?: string
+): Promise<UsageHistoryEntry[]> {
+  const entries = await queryHistory({ provider, startDate, endDate, limit: 1000, resolution });
+  if (resolution === "raw") return entries;
+
+  // Entries come newest first and carry the start of their bucket
+  const tailStart = entries.length
+    ? new Date(new Date(entries[0].timestamp).getTime() + BUCKET_MS[resolution]).toISOString()
+    : startDate;
+  const tail = await queryHistory({
+    provider,
+    startDate: tailStart,
+    endDate,
+    limit: 1000,
+    resolution: "raw",
+  });
+  return [...tail, ...entries];
+}
+
 export function Analytics({ provider = "claude" }: AnalyticsProps) {
   const [history, setHistory] = useState<UsageHistoryEntry[]>([]);
   const [metadata, setMetadata] = useState<HistoryMetadata | null>(null);
@@ -69,12 +115,12 @@ export function Analytics({ provider = "claude" }: AnalyticsProps) {
 
     try {
       // Load history
-      const historyData = await queryHistory({
+      const historyData = await queryHistoryWithTail(
         provider,
-        startDate: dateRange.startDate,
-        endDate: dateRange.endDate,
-        limit: 1000,
-      });
+        RESOLUTION_FOR_RANGE[timeRange],
+        dateRange.startDate,
+        dateRange.endDate
+      );
       setHistory(historyData);
 
       // Load metadata
@@ -1,7 +1,7 @@
 import { useState, useEffect, useCallback } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-shell";
-import { RefreshCw, Settings, BarChart3, Activity, Copy, Check } from "lucide-react";
+import { RefreshCw, Settings, BarChart3, Activity, Copy, Check, Plus } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { UsageCard, UsageCardSkeleton } from "@/components/UsageCard";
 import { Analytics } from "@/components/Analytics";
@@ -9,17 +9,26 @@ import { SessionBanner } from "@/components/SessionBanner";
 import { Confetti } from "@/components/Confetti";
 import { useUsageStore, useAccountsStore } from "@/lib/store";
 import { useUsage } from "@/hooks/useUsage";
+import { incrementCounter } from "@/lib/tauri";
 import { formatUsageForClipboard, copyToClipboard } from "@/lib/utils";
 import type { ProviderId } from "@/lib/types";
 
 const PROVIDER_URLS: Record<ProviderId, string> = {
   claude: "https://claude.ai",
+  claude_code: "https://claude.ai",
+  anthropic_api: "https://console.anthropic.com",
+  openai_api: "https://platform.openai.com/usage",
+  counter: "https://chat.openai.com",
   chatgpt: "https://chat.openai.com",
   gemini: "https://gemini.google.com",
 };
 
 const PROVIDER_NAMES: Record<ProviderId, string> = {
   claude: "Claude.ai",
+  claude_code: "Claude Code",
+  anthropic_api: "Anthropic Console",
+  openai_api: "OpenAI Platform",
+  counter: "Manual Counter",
   chatgpt: "ChatGPT",
   gemini: "Gemini",
 };
@@ -35,7 +44,7 @@ export function Dashboard({ provider = "claude", onSettingsClick }: DashboardPro
   const [activeTab, setActiveTab] = useState<TabType>("usage");
   const [copied, setCopied] = useState(false);
   const [showConfetti, setShowConfetti] = useState(false);
-  const { usage, isLoading, error, lastRefresh, getAllUsage } = useUsageStore();
+  const { usage, isLoading, error, lastRefresh, getAllUsage, setUsage } = useUsageStore();
   const { accounts } = useAccountsStore();
   const { forceRefresh } = useUsage();
 
@@ -80,6 +89,17 @@ export function Dashboard({ provider = "claude", onSettingsClick }: DashboardPro
     }
   }, [provider]);
 
+  const handleLogUse = useCallback(
+    async (accountId: string) => {
+      try {
+        setUsage(accountId, await incrementCounter(accountId));
+      } catch (err) {
+        console.error("Failed to log counter use:", err);
+      }
+    },
+    [setUsage]
+  );
+
   // Listen for menu events from native app menu (macOS)
   useEffect(() => {
     const unlistenUsage = listen("menu-usage", () => {
@@ -234,6 +254,19 @@ export function Dashboard({ provider = "claude", onSettingsClick }: DashboardPro
                         ))}
                       </div>
                     )}
+
+                    {/* Manual counters are ticked by hand */}
+                    {account.provider === "counter" && (
+                      <Button
+                        variant="outline"
+                        size="sm"
+                        className="mt-2 w-full"
+                        onClick={() => handleLogUse(account.id)}
+                      >
+                        <Plus className="h-4 w-4 mr-1" />
+                        Log use
+                      </Button>
+                    )}
                   </div>
                 ))}
               </div>
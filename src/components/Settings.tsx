@@ -412,6 +412,39 @@ export function Settings({ isOpen, onClose, onCredentialsSaved }: SettingsProps)
                 </p>
               </div>
 
+              <div className="flex items-center justify-between">
+                <div className="space-y-0.5">
+                  <Label htmlFor="auto-import-browser-session">Import sessions from browsers</Label>
+                  <p className="text-xs text-muted-foreground">
+                    Replace an expired Claude.ai session with one from a local browser automatically
+                  </p>
+                </div>
+                <button
+                  id="auto-import-browser-session"
+                  role="switch"
+                  aria-checked={settings?.autoImportBrowserSession ?? false}
+                  onClick={() =>
+                    handleSettingChange(
+                      "autoImportBrowserSession",
+                      !settings?.autoImportBrowserSession
+                    )
+                  }
+                  className={`
+                    relative inline-flex h-6 w-11 shrink-0 cursor-pointer rounded-full border-2 border-transparent
+                    transition-colors focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring
+                    ${settings?.autoImportBrowserSession ? "bg-primary" : "bg-input"}
+                  `}
+                >
+                  <span
+                    className={`
+                      pointer-events-none flex h-5 w-5 items-center justify-center rounded-full bg-background shadow-lg ring-0
+                      transition-transform
+                      ${settings?.autoImportBrowserSession ? "translate-x-5" : "translate-x-0"}
+                    `}
+                  />
+                </button>
+              </div>
+
             </div>
 
             {/* Notification Settings */}
@@ -576,6 +609,40 @@ export function Settings({ isOpen, onClose, onCredentialsSaved }: SettingsProps)
                     </button>
                   </div>
 
+                  <div className="flex items-center justify-between">
+                    <div className="space-y-0.5">
+                      <Label htmlFor="notify-overrun">Predictive alerts</Label>
+                      <p className="text-xs text-muted-foreground">
+                        Alert when the current pace will exceed a limit before it resets
+                      </p>
+                    </div>
+                    <button
+                      id="notify-overrun"
+                      role="switch"
+                      aria-checked={settings.notifications.notifyOnProjectedOverrun}
+                      onClick={() => {
+                        const newNotifications = {
+                          ...settings.notifications,
+                          notifyOnProjectedOverrun: !settings.notifications.notifyOnProjectedOverrun,
+                        };
+                        handleSettingChange("notifications", newNotifications);
+                      }}
+                      className={`
+                        relative inline-flex h-6 w-11 shrink-0 cursor-pointer rounded-full border-2 border-transparent
+                        transition-colors focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring
+                        ${settings.notifications.notifyOnProjectedOverrun ? "bg-primary" : "bg-input"}
+                      `}
+                    >
+                      <span
+                        className={`
+                          pointer-events-none flex h-5 w-5 items-center justify-center rounded-full bg-background shadow-lg ring-0
+                          transition-transform
+                          ${settings.notifications.notifyOnProjectedOverrun ? "translate-x-5" : "translate-x-0"}
+                        `}
+                      />
+                    </button>
+                  </div>
+
                   {/* Do Not Disturb */}
                   <div className="pt-3 border-t space-y-3">
                     <div className="flex items-center justify-between">
@@ -807,6 +874,34 @@ token = "${settings.apiServerToken || ''}"`;
             </p>
           </div>
 
+          <div className="flex items-center justify-between">
+            <div className="space-y-0.5">
+              <Label htmlFor="api-metrics-public">Public Metrics Endpoint</Label>
+              <p className="text-xs text-muted-foreground">
+                Let Prometheus scrape /metrics without the auth token
+              </p>
+            </div>
+            <button
+              id="api-metrics-public"
+              role="switch"
+              aria-checked={settings.apiMetricsPublic}
+              onClick={() => onSettingChange('apiMetricsPublic', !settings.apiMetricsPublic)}
+              className={`
+                relative inline-flex h-6 w-11 shrink-0 cursor-pointer rounded-full border-2 border-transparent
+                transition-colors focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring
+                ${settings.apiMetricsPublic ? "bg-primary" : "bg-input"}
+              `}
+            >
+              <span
+                className={`
+                  pointer-events-none flex h-5 w-5 items-center justify-center rounded-full bg-background shadow-lg ring-0
+                  transition-transform
+                  ${settings.apiMetricsPublic ? "translate-x-5" : "translate-x-0"}
+                `}
+              />
+            </button>
+          </div>
+
           <div className="space-y-2">
             <Label>CLI Configuration</Label>
             <div className="bg-muted rounded-md p-3 font-mono text-xs relative">
@@ -2,7 +2,7 @@ import { useCallback, useEffect, useRef } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { useUsageStore, useAccountsStore, useSettingsStore } from "@/lib/store";
 import {
-  listAccounts,
+  listAllAccounts,
   fetchUsageForAccount,
   forceRefresh as forceRefreshCommand,
 } from "@/lib/tauri";
@@ -37,9 +37,9 @@ export function useUsage() {
   // Load all accounts from backend
   const loadAccounts = useCallback(async () => {
     try {
-      const claudeAccounts = await listAccounts("claude");
-      setAccounts(claudeAccounts);
-      return claudeAccounts;
+      const allAccounts = await listAllAccounts();
+      setAccounts(allAccounts);
+      return allAccounts;
     } catch (err) {
       console.error("Failed to load accounts:", err);
       return [];
@@ -222,6 +222,7 @@ describe('useSettingsStore', () => {
           thresholds: [50, 75, 90],
           notifyOnReset: true,
           notifyOnExpiry: true,
+          notifyOnProjectedOverrun: false,
           dndEnabled: false,
           dndStartTime: "22:00",
           dndEndTime: "08:00",
@@ -234,6 +235,9 @@ describe('useSettingsStore', () => {
         apiServerEnabled: false,
         apiServerPort: 31415,
         apiServerToken: null,
+        apiMetricsPublic: false,
+        webhooks: [],
+        customProviders: [],
       },
       isLoading: false,
     })
@@ -153,6 +153,7 @@ const defaultSettings: AppSettings = {
     thresholds: [50, 75, 90],
     notifyOnReset: true,
     notifyOnExpiry: true,
+    notifyOnProjectedOverrun: false,
     dndEnabled: false,
     dndStartTime: "22:00",
     dndEndTime: "08:00",
@@ -166,6 +167,9 @@ const defaultSettings: AppSettings = {
   apiServerEnabled: false,
   apiServerPort: 31415,
   apiServerToken: null,
+  apiMetricsPublic: false,
+  webhooks: [],
+  customProviders: [],
 };
 
 export const useSettingsStore = create<SettingsState>((set) => ({
@@ -25,13 +25,15 @@ import {
   exportHistoryCsv,
   clearHistory,
   listAccounts,
+  listAllAccounts,
   getAccount,
   saveAccount,
   deleteAccount,
   testAccountConnection,
+  findBrowserSession,
 } from './tauri'
 import type { UsageData, UsageHistoryEntry, HistoryMetadata, RetentionPolicy, UsageStats, Account, Credentials } from './types'
-import type { AppSettings, SchedulerStatus, TestConnectionResult } from './tauri'
+import type { AppSettings, BrowserSession, SchedulerStatus, TestConnectionResult } from './tauri'
 
 // Mock Tauri invoke
 vi.mock('@tauri-apps/api/core', () => ({
@@ -125,6 +127,26 @@ describe('Tauri API Integration', () => {
     })
   })
 
+  describe('listAllAccounts', () => {
+    it('lists accounts for every available provider', async () => {
+      mockInvoke
+        .mockResolvedValueOnce([
+          { id: 'claude', name: 'Claude', status: 'available', requiredCredentials: [] },
+          { id: 'anthropic_api', name: 'Anthropic API', status: 'available', requiredCredentials: [] },
+          { id: 'chatgpt', name: 'ChatGPT', status: 'blocked', requiredCredentials: [] },
+        ])
+        .mockResolvedValueOnce([{ id: '1', name: 'Personal', provider: 'claude', credentials: {}, createdAt: '2025-01-01' }])
+        .mockResolvedValueOnce([{ id: '2', name: 'Team', provider: 'anthropic_api', credentials: {}, createdAt: '2025-01-02' }])
+
+      const result = await listAllAccounts()
+
+      expect(mockInvoke).toHaveBeenCalledWith('list_providers')
+      expect(mockInvoke).toHaveBeenCalledWith('list_accounts', { provider: 'anthropic_api' })
+      expect(mockInvoke).not.toHaveBeenCalledWith('list_accounts', { provider: 'chatgpt' })
+      expect(result.map((a) => a.id)).toEqual(['1', '2'])
+    })
+  })
+
   describe('getAccount', () => {
     it('returns account when it exists', async () => {
       const mockAccount: Account = {
@@ -224,6 +246,34 @@ describe('Tauri API Integration', () => {
     })
   })
 
+  describe('findBrowserSession', () => {
+    it('looks for a session of the given organization', async () => {
+      const session: BrowserSession = {
+        browser: 'Firefox',
+        profile: 'abc.default-release',
+        sessionKey: 'sk-ant-sid01-new',
+        orgId: 'org1',
+        orgName: 'Personal',
+        expiresAt: null,
+      }
+      mockInvoke.mockResolvedValue(session)
+
+      const result = await findBrowserSession('org1')
+
+      expect(mockInvoke).toHaveBeenCalledWith('find_browser_session', { orgId: 'org1' })
+      expect(result).toEqual(session)
+    })
+
+    it('passes null when no organization is given', async () => {
+      mockInvoke.mockResolvedValue(null)
+
+      const result = await findBrowserSession()
+
+      expect(mockInvoke).toHaveBeenCalledWith('find_browser_session', { orgId: null })
+      expect(result).toBeNull()
+    })
+  })
+
   describe('validateCredentials', () => {
     it('validates credentials and returns true', async () => {
       mockInvoke.mockResolvedValue(true)
@@ -346,6 +396,7 @@ describe('Tauri API Integration', () => {
           thresholds: [50, 75, 90],
           notifyOnReset: true,
           notifyOnExpiry: true,
+          notifyOnProjectedOverrun: false,
           dndEnabled: false,
           dndStartTime: "22:00",
           dndEndTime: "08:00",
@@ -355,9 +406,13 @@ describe('Tauri API Integration', () => {
           { id: 'chatgpt', enabled: false, credentials: {} },
           { id: 'gemini', enabled: false, credentials: {} },
         ],
+        autoImportBrowserSession: false,
         apiServerEnabled: false,
         apiServerPort: 31415,
         apiServerToken: null,
+        apiMetricsPublic: false,
+        webhooks: [],
+        customProviders: [],
       }
       mockInvoke.mockResolvedValue(mockSettings)
 
@@ -384,14 +439,19 @@ describe('Tauri API Integration', () => {
           thresholds: [50, 75, 90],
           notifyOnReset: true,
           notifyOnExpiry: true,
+          notifyOnProjectedOverrun: false,
           dndEnabled: false,
           dndStartTime: "22:00",
           dndEndTime: "08:00",
         },
         providers: [],
+        autoImportBrowserSession: true,
         apiServerEnabled: false,
         apiServerPort: 31415,
         apiServerToken: null,
+        apiMetricsPublic: false,
+        webhooks: [],
+        customProviders: [],
       }
 
       await saveSettings(settings)
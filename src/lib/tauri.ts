@@ -1,8 +1,10 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  CircuitBreaker,
   UsageData,
   ProviderId,
   ProviderMetadata,
+  CredentialField,
   UsageHistoryEntry,
   HistoryMetadata,
   HistoryQuery,
@@ -10,6 +12,11 @@ import type {
   UsageStats,
   Account,
   Credentials,
+  AccountForecast,
+  WebhookConfig,
+  WebhookDelivery,
+  WebhookDeadLetter,
+  SchemaRecord,
 } from "./types";
 
 // Re-export for backward compatibility
@@ -24,6 +31,11 @@ export async function fetchUsageForAccount(accountId: string): Promise<UsageData
   return invoke<UsageData>("fetch_usage_for_account", { accountId });
 }
 
+// Log uses for a manual counter account; returns its refreshed usage
+export async function incrementCounter(accountId: string, amount = 1): Promise<UsageData> {
+  return invoke<UsageData>("increment_counter", { accountId, amount });
+}
+
 export async function validateCredentials(
   provider: ProviderId,
   credentials: Credentials
@@ -37,6 +49,18 @@ export interface TestConnectionResult {
   error_code: string | null;
   error_message: string | null;
   hint: string | null;
+  // Working session found in a local browser when a claude.ai session expired
+  browser_session?: BrowserSession;
+}
+
+// claude.ai session read from a local browser profile
+export interface BrowserSession {
+  browser: string;
+  profile: string;
+  sessionKey: string;
+  orgId: string;
+  orgName: string;
+  expiresAt: string | null;
 }
 
 export async function testConnection(
@@ -58,6 +82,17 @@ export async function listAccounts(provider: ProviderId): Promise<Account[]> {
   return invoke<Account[]>("list_accounts", { provider });
 }
 
+// Accounts of every available provider
+export async function listAllAccounts(): Promise<Account[]> {
+  const providers = await listProviders();
+  const lists = await Promise.all(
+    providers
+      .filter((p) => p.status === "available")
+      .map((p) => listAccounts(p.id))
+  );
+  return lists.flat();
+}
+
 export async function getAccount(accountId: string): Promise<Account | null> {
   return invoke<Account | null>("get_account", { accountId });
 }
@@ -70,12 +105,21 @@ export async function deleteAccount(accountId: string): Promise<void> {
   return invoke("delete_account", { accountId });
 }
 
+export async function repairCredentials(): Promise<Account[]> {
+  return invoke<Account[]>("repair_credentials");
+}
+
 export async function testAccountConnection(
   account: Account
 ): Promise<TestConnectionResult> {
   return invoke<TestConnectionResult>("test_account_connection", { account });
 }
 
+// Find a working claude.ai session in local browsers (of orgId when given)
+export async function findBrowserSession(orgId?: string): Promise<BrowserSession | null> {
+  return invoke<BrowserSession | null>("find_browser_session", { orgId: orgId ?? null });
+}
+
 // Credential commands
 export async function getCredentials(provider: ProviderId): Promise<Credentials | null> {
   return invoke<Credentials | null>("get_credentials", { provider });
@@ -144,6 +188,8 @@ export interface SessionStatus {
   valid: boolean;
   errorCount: number;
   paused: boolean;
+  /** Circuit breaker per account ID (accounts that never failed are omitted) */
+  circuits: Record<string, CircuitBreaker>;
 }
 
 export async function getSessionStatus(): Promise<SessionStatus> {
@@ -163,10 +209,40 @@ export interface AppSettings {
   globalShortcut: string | null;
   notifications: NotificationSettings;
   providers: ProviderConfig[];
+  // Replace an expired claude.ai session from a local browser automatically
+  autoImportBrowserSession: boolean;
   // API Server settings (for CLI and IDE integrations)
   apiServerEnabled: boolean;
   apiServerPort: number;
   apiServerToken: string | null;
+  apiMetricsPublic: boolean;
+  // Outbound webhooks for usage events
+  webhooks: WebhookConfig[];
+  // User-defined HTTP/JSON providers
+  customProviders: CustomProviderConfig[];
+}
+
+export interface CustomLimitMapping {
+  id: string;
+  label?: string | null;
+  labelPointer?: string | null;
+  utilizationPointer: string;
+  utilizationScale?: number;
+  resetsAtPointer: string;
+  category?: string | null;
+}
+
+export interface CustomProviderConfig {
+  id: string;
+  name: string;
+  description?: string | null;
+  url: string;
+  headers?: Record<string, string>;
+  credentials?: CredentialField[];
+  limits: CustomLimitMapping[];
+  sessionExpiredStatuses?: number[];
+  rateLimitedStatuses?: number[];
+  enabled?: boolean;
 }
 
 export interface NotificationSettings {
@@ -174,6 +250,7 @@ export interface NotificationSettings {
   thresholds: number[];
   notifyOnReset: boolean;
   notifyOnExpiry: boolean;
+  notifyOnProjectedOverrun: boolean;
   dndEnabled: boolean;
   dndStartTime: string | null;
   dndEndTime: string | null;
@@ -242,3 +319,119 @@ export async function exportHistoryCsv(
 export async function clearHistory(): Promise<void> {
   return invoke("clear_history");
 }
+
+// ============================================================================
+// Forecast commands
+// ============================================================================
+
+export async function getUsageForecast(
+  accountId?: string
+): Promise<AccountForecast[]> {
+  return invoke<AccountForecast[]>("get_usage_forecast", { accountId });
+}
+
+// ============================================================================
+// Diagnostics commands
+// ============================================================================
+
+export async function getResponseSchemas(): Promise<SchemaRecord[]> {
+  return invoke<SchemaRecord[]>("get_response_schemas");
+}
+
+// ============================================================================
+// Webhook commands
+// ============================================================================
+
+export async function listWebhookDeliveries(
+  webhookId?: string,
+  limit?: number
+): Promise<WebhookDelivery[]> {
+  return invoke<WebhookDelivery[]>("list_webhook_deliveries", {
+    webhookId,
+    limit,
+  });
+}
+
+export async function listWebhookDeadLetters(): Promise<WebhookDeadLetter[]> {
+  return invoke<WebhookDeadLetter[]>("list_webhook_dead_letters");
+}
+
+export async function retryWebhookDeadLetter(
+  deliveryId: string
+): Promise<WebhookDelivery> {
+  return invoke<WebhookDelivery>("retry_webhook_dead_letter", { deliveryId });
+}
+
+export async function clearWebhookDeadLetters(): Promise<number> {
+  return invoke<number>("clear_webhook_dead_letters");
+}
+
+// ============================================================================
+// Vault commands
+// ============================================================================
+
+export interface VaultStatus {
+  /** A master passphrase is set */
+  enabled: boolean;
+  /** The passphrase hasn't been entered yet; fetching is paused */
+  locked: boolean;
+}
+
+export async function getVaultStatus(): Promise<VaultStatus> {
+  return invoke<VaultStatus>("get_vault_status");
+}
+
+export async function enableVault(passphrase: string): Promise<VaultStatus> {
+  return invoke<VaultStatus>("enable_vault", { passphrase });
+}
+
+export async function unlockVault(passphrase: string): Promise<VaultStatus> {
+  return invoke<VaultStatus>("unlock_vault", { passphrase });
+}
+
+export async function lockVault(): Promise<VaultStatus> {
+  return invoke<VaultStatus>("lock_vault");
+}
+
+export async function disableVault(passphrase: string): Promise<VaultStatus> {
+  return invoke<VaultStatus>("disable_vault", { passphrase });
+}
+
+// ============================================================================
+// Transfer commands
+// ============================================================================
+
+/** What to do with a bundled account whose ID already exists */
+export type ImportConflict = "skip" | "rename" | "overwrite";
+
+export interface ImportSummary {
+  added: string[];
+  overwritten: string[];
+  /** New names of accounts imported next to an existing one */
+  renamed: string[];
+  skipped: string[];
+  settingsImported: boolean;
+  historyEntries: number;
+}
+
+// Returns the encrypted bundle to save to a file
+export async function exportAccounts(
+  passphrase: string,
+  includeHistory = false
+): Promise<string> {
+  return invoke<string>("export_accounts", { passphrase, includeHistory });
+}
+
+export async function importAccounts(
+  bundle: string,
+  passphrase: string,
+  onConflict: ImportConflict = "skip",
+  importSettings = false
+): Promise<ImportSummary> {
+  return invoke<ImportSummary>("import_accounts", {
+    bundle,
+    passphrase,
+    onConflict,
+    importSettings,
+  });
+}
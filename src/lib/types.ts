@@ -1,4 +1,11 @@
-export type ProviderId = "claude" | "chatgpt" | "gemini";
+export type ProviderId =
+  | "claude"
+  | "claude_code"
+  | "anthropic_api"
+  | "openai_api"
+  | "counter"
+  | "chatgpt"
+  | "gemini";
 
 // ============================================================================
 // Account Types (Multi-Account Support)
@@ -8,6 +15,16 @@ export interface Credentials {
   org_id?: string;
   session_key?: string;
   api_key?: string;
+  monthly_budget?: number;
+  monthly_token_budget?: number;
+  projects_dir?: string;
+  five_hour_token_cap?: number;
+  weekly_token_cap?: number;
+  counter_cap?: number;
+  window_hours?: number;
+  weekly_reset?: string;
+  // Fields declared by custom providers
+  [key: string]: string | number | undefined;
 }
 
 export interface Account {
@@ -16,6 +33,15 @@ export interface Account {
   provider: ProviderId;
   credentials: Credentials;
   createdAt: string;
+  /** Set when stored secrets couldn't be read and must be entered again */
+  needsReentry?: CredentialsIssue;
+}
+
+export interface CredentialsIssue {
+  /** Affected credential fields (e.g. "session_key", "extra.token") */
+  fields: string[];
+  /** Why they couldn't be read */
+  reason: string;
 }
 
 // ============================================================================
@@ -28,9 +54,18 @@ export interface UsageData {
   accountName: string;
   timestamp: string;
   limits: UsageLimit[];
+  extraUsage?: ExtraUsage;
   raw?: unknown;
 }
 
+// Paid overage credits (Claude)
+export interface ExtraUsage {
+  isEnabled: boolean;
+  monthlyLimit: number | null;
+  usedCredits: number | null;
+  utilization: number | null;
+}
+
 // ============================================================================
 // Provider Types
 // ============================================================================
@@ -42,6 +77,8 @@ export interface CredentialField {
   label: string;
   placeholder: string;
   isSecret: boolean;
+  isNumeric: boolean;
+  isOptional: boolean;
 }
 
 export interface ProviderMetadata {
@@ -60,17 +97,19 @@ export interface UsageLimit {
   category?: string;
 }
 
+// Every field shaped like LimitUsage is a limit; `extra_usage` holds overage credits
 export interface ClaudeUsageResponse {
-  five_hour: LimitUsage;
-  seven_day?: LimitUsage;
-  seven_day_oauth_apps?: LimitUsage;
-  seven_day_opus?: LimitUsage;
-  seven_day_sonnet?: LimitUsage;
+  five_hour?: LimitUsage | null;
+  seven_day?: LimitUsage | null;
+  seven_day_oauth_apps?: LimitUsage | null;
+  seven_day_opus?: LimitUsage | null;
+  seven_day_sonnet?: LimitUsage | null;
+  [field: string]: unknown;
 }
 
 export interface LimitUsage {
   utilization: number;
-  resets_at: string;
+  resets_at: string | null;
 }
 
 // ============================================================================
@@ -90,6 +129,8 @@ export interface UsageLimitSnapshot {
   id: string;
   utilization: number;
   resetsAt: string;
+  minUtilization?: number;
+  maxUtilization?: number;
 }
 
 export interface HistoryMetadata {
@@ -107,8 +148,12 @@ export interface HistoryQuery {
   endDate?: string;
   limit?: number;
   offset?: number;
+  cursor?: string;
+  resolution?: HistoryResolution;
 }
 
+export type HistoryResolution = "raw" | "hourly" | "daily";
+
 export interface UsageStats {
   provider: string;
   limitId: string;
@@ -120,7 +165,139 @@ export interface UsageStats {
   sampleCount: number;
 }
 
+export interface LimitForecast {
+  limitId: string;
+  utilization: number;
+  resetsAt: string;
+  sampleCount: number;
+  burnRatePerHour: number | null;
+  burnRateLow: number | null;
+  burnRateHigh: number | null;
+  hitsLimitAt: string | null;
+  hitsLimitEarliest: string | null;
+  hitsLimitLatest: string | null;
+  projectedAtReset: number | null;
+  hitsBeforeReset: boolean;
+}
+
+export interface AccountForecast {
+  accountId: string;
+  accountName: string;
+  provider: string;
+  asOf: string;
+  limits: LimitForecast[];
+}
+
 export interface RetentionPolicy {
   retentionDays: number;
+  hourlyRetentionMonths: number;
   autoCleanup: boolean;
 }
+
+// ============================================================================
+// Webhook Types
+// ============================================================================
+
+export type WebhookEventType =
+  | "usage-update"
+  | "session-status"
+  | "usage-reset"
+  | "threshold-crossed"
+  | "projected-overrun";
+
+export interface WebhookConfig {
+  id: string;
+  name: string;
+  url: string;
+  secret: string | null;
+  /** Events to deliver (empty = all events) */
+  events: WebhookEventType[];
+  enabled: boolean;
+}
+
+export interface WebhookPayload {
+  id: string;
+  event: WebhookEventType;
+  timestamp: string;
+  data: unknown;
+}
+
+export interface WebhookDelivery {
+  id: string;
+  webhookId: string;
+  event: WebhookEventType;
+  url: string;
+  status: "delivered" | "failed";
+  attempts: number;
+  statusCode: number | null;
+  error: string | null;
+  createdAt: string;
+  completedAt: string;
+}
+
+export interface WebhookDeadLetter {
+  delivery: WebhookDelivery;
+  payload: WebhookPayload;
+}
+
+// ============================================================================
+// Schema Diagnostics Types
+// ============================================================================
+
+/** Key path -> JSON type, e.g. "five_hour.utilization" -> "number" */
+export type ResponseShape = Record<string, string>;
+
+export interface SchemaTypeChange {
+  path: string;
+  expected: string;
+  actual: string;
+}
+
+export interface SchemaDiff {
+  added: string[];
+  removed: string[];
+  changed: SchemaTypeChange[];
+}
+
+export interface SchemaRecord {
+  provider: string;
+  endpoint: string;
+  expected: ResponseShape;
+  current: ResponseShape;
+  diff?: SchemaDiff;
+  lastSeen: string;
+  driftedAt?: string;
+}
+
+/** Payload of the "schema-drift" event */
+export interface SchemaDriftEvent {
+  provider: string;
+  endpoint: string;
+  diff: SchemaDiff;
+  detectedAt: string;
+}
+
+// ============================================================================
+// Circuit Breaker Types
+// ============================================================================
+
+export type CircuitState = "closed" | "open" | "half_open";
+
+export type ErrorClass = "session" | "blocked" | "rate_limited" | "transient";
+
+export interface CircuitBreaker {
+  state: CircuitState;
+  /** Class of the current run of failures */
+  errorClass: ErrorClass | null;
+  consecutiveFailures: number;
+  lastError: string | null;
+  changedAt: string | null;
+  /** When an open circuit is next probed */
+  nextProbeAt: string | null;
+}
+
+/** Payload of the "circuit-state" event */
+export interface CircuitStateEvent extends CircuitBreaker {
+  accountId: string;
+  previous: CircuitState;
+}